@@ -0,0 +1,98 @@
+//! Compares the time it takes `Rule`, `BytecodeRule` and `ClosureRule` to apply a rule.
+//!
+//! Run with `cargo bench --bench backends`.
+
+extern crate jsonlogic;
+extern crate serde_json;
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use jsonlogic::{BytecodeRule, ClosureRule, Rule};
+use serde_json::{json, Value};
+
+const ITERATIONS: u32 = 40;
+
+/// The backends are measured in turns this many times and the fastest time of each is reported,
+/// which makes the comparison robust against noise on the machine.
+const ROUNDS: usize = 50;
+
+fn main() {
+    let fizzbuzz = json!({
+        "if": [
+            {"==": [{ "%": [{ "var": "i" }, 15] }, 0]},
+            "fizzbuzz",
+            {"==": [{ "%": [{ "var": "i" }, 3] }, 0]},
+            "fizz",
+            {"==": [{ "%": [{ "var": "i" }, 5] }, 0]},
+            "buzz",
+            { "var": "i" }
+        ]
+    });
+    let fizzbuzz_data: Vec<Value> = (1..=100).map(|i| json!({ "i": i })).collect();
+    bench("fizzbuzz", &fizzbuzz, &fizzbuzz_data);
+
+    let deep = deep_and_or(8);
+    let deep_data = vec![
+        json!({ "user": { "age": 42, "country": "DE", "score": 7.5 } }),
+        json!({ "user": { "age": 17, "country": "US", "score": 2 } }),
+    ];
+    bench("deep and/or", &deep, &deep_data);
+}
+
+/// Builds `and`s and `or`s that are nested `depth` times, with comparisons at the leaves.
+fn deep_and_or(depth: usize) -> Value {
+    if depth == 0 {
+        return json!({ "or": [
+            { ">=": [{ "var": "user.age" }, 18] },
+            { "==": [{ "var": "user.country" }, "DE"] },
+            { "<": [5, { "var": "user.score" }] }
+        ] });
+    }
+
+    let operator = if depth % 2 == 1 { "and" } else { "or" };
+    json!({ operator: [deep_and_or(depth - 1), deep_and_or(depth - 1)] })
+}
+
+fn bench(name: &str, json_logic: &Value, data: &[Value]) {
+    let rule = Rule::compile(json_logic).unwrap();
+    let bytecode = BytecodeRule::compile(json_logic).unwrap();
+    let closure = ClosureRule::compile(json_logic).unwrap();
+
+    let mut times = [Duration::MAX; 3];
+    for _ in 0..ROUNDS {
+        times[0] = times[0].min(measure(data, |data| rule.apply(data)));
+        times[1] = times[1].min(measure(data, |data| bytecode.apply(data)));
+        times[2] = times[2].min(measure(data, |data| closure.apply(data)));
+    }
+
+    println!("{}:", name);
+    report("Rule", times[0], times[0]);
+    report("BytecodeRule", times[1], times[0]);
+    report("ClosureRule", times[2], times[0]);
+}
+
+fn measure(data: &[Value], apply: impl Fn(&Value) -> Result<Value, Value>) -> Duration {
+    // Warm up.
+    for data in data {
+        black_box(apply(black_box(data)).unwrap());
+    }
+
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        for data in data {
+            black_box(apply(black_box(data)).unwrap());
+        }
+    }
+    start.elapsed() / (ITERATIONS * data.len() as u32)
+}
+
+/// Prints the time of the backend and how much faster it is than `Rule`.
+fn report(backend: &str, time: Duration, rule_time: Duration) {
+    println!(
+        "  {:<14}{:>10} ns per apply{:>8.2}x",
+        backend,
+        time.as_nanos(),
+        rule_time.as_secs_f64() / time.as_secs_f64()
+    );
+}
@@ -0,0 +1,966 @@
+use serde_json::{json, Value};
+use std::borrow::Cow;
+use std::mem;
+
+use crate::data::Path;
+use crate::expression::Expression;
+#[cfg(feature = "regex")]
+use crate::operators::{apply_pattern, Pattern};
+use crate::operators::{
+    evaluate_let, group, logic, sort_by_keys, try_alternatives, Function, Operator,
+};
+use crate::{Data, StringMode};
+
+/// A comparison with its own instruction, which compares numbers without any coercion.
+#[derive(Debug, PartialEq, Copy, Clone)]
+enum Comparison {
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    Equal,
+    StrictEqual,
+    NotEqual,
+    StrictNotEqual,
+}
+
+impl Comparison {
+    /// Returns the comparison of the operator, if it has one.
+    fn of(operator: Operator) -> Option<Comparison> {
+        match operator {
+            Operator::LessThan => Some(Comparison::Less),
+            Operator::LessEqualThan => Some(Comparison::LessEqual),
+            Operator::GreaterThan => Some(Comparison::Greater),
+            Operator::GreaterEqualThan => Some(Comparison::GreaterEqual),
+            Operator::Equal => Some(Comparison::Equal),
+            Operator::StrictEqual => Some(Comparison::StrictEqual),
+            Operator::NotEqual => Some(Comparison::NotEqual),
+            Operator::StrictNotEqual => Some(Comparison::StrictNotEqual),
+            _ => None,
+        }
+    }
+
+    fn compare(self, a: &Value, b: &Value) -> bool {
+        // Decimals are compared by the coercing comparisons.
+        #[cfg(not(feature = "decimal"))]
+        if let (Value::Number(a), Value::Number(b)) = (a, b) {
+            if let (Some(a), Some(b)) = (exact_f64(a), exact_f64(b)) {
+                match self {
+                    Comparison::Less => return a < b,
+                    Comparison::LessEqual => return a <= b,
+                    Comparison::Greater => return a > b,
+                    Comparison::GreaterEqual => return a >= b,
+                    // Large integers may be equal as floats, so they are compared exactly below.
+                    _ => (),
+                }
+            }
+        }
+
+        match self {
+            Comparison::Less => logic::less_than(a, b),
+            Comparison::LessEqual => logic::less_equal_than(a, b),
+            Comparison::Greater => logic::greater_than(a, b),
+            Comparison::GreaterEqual => logic::greater_equal_than(a, b),
+            Comparison::Equal => logic::is_abstract_equal(a, b),
+            Comparison::StrictEqual => logic::is_strict_equal(a, b),
+            Comparison::NotEqual => !logic::is_abstract_equal(a, b),
+            Comparison::StrictNotEqual => !logic::is_strict_equal(a, b),
+        }
+    }
+}
+
+/// Returns the number as a float, if that does not round it. Larger integers are ordered exactly
+/// by the operators.
+#[cfg(not(feature = "decimal"))]
+fn exact_f64(number: &serde_json::Number) -> Option<f64> {
+    const MAX_EXACT: u64 = 1 << 53;
+    match (number.as_i64(), number.as_u64()) {
+        (Some(int), _) if int.unsigned_abs() > MAX_EXACT => None,
+        (None, Some(int)) if int > MAX_EXACT => None,
+        _ => number.as_f64(),
+    }
+}
+
+/// An operation that evaluates a block of instructions for every element of an array.
+#[derive(Debug, PartialEq, Copy, Clone)]
+enum Iteration {
+    Map,
+    Filter,
+    All,
+    Some,
+    None,
+    Sort,
+    Find,
+    Count,
+    GroupBy,
+}
+
+/// A single instruction of the stack machine. Jump targets are indices into the block of the
+/// instruction.
+#[derive(Debug, PartialEq, Copy, Clone)]
+enum Instruction {
+    /// Pushes the constant with the given index. Constants and the whole data are borrowed on the
+    /// stack and only cloned if an operator needs them, or if they are the result.
+    Push(usize),
+    /// Pushes the whole data.
+    LoadData,
+    /// Pushes the value at the path, which is the constant with the given index. If there is no
+    /// value at the path, jumps to `default` or pushes `null` if there is no default.
+    LoadVar {
+        path: usize,
+        default: Option<usize>,
+    },
+    /// Like `LoadVar`, but with the parsed path with the given index.
+    LoadPath {
+        path: usize,
+        default: Option<usize>,
+    },
+    /// Like `LoadVar`, but pops the path from the stack.
+    LoadDynamicVar {
+        default: Option<usize>,
+    },
+    Jump(usize),
+    /// Pops a value and jumps if it is falsy.
+    JumpIfFalsy(usize),
+    /// Pops two values and jumps if the comparison is false. Used for the conditions of `if`.
+    JumpIfNot(Comparison, usize),
+    /// Jumps if the value on top of the stack is falsy, otherwise pops it. Used by `and`.
+    JumpIfFalsyElsePop(usize),
+    /// Jumps if the value on top of the stack is truthy, otherwise pops it. Used by `or`.
+    JumpIfTruthyElsePop(usize),
+    /// Pops two values and pushes the result of the comparison.
+    Compare(Comparison),
+    /// Pops the given number of arguments and pushes the result of the operator.
+    Call(Operator, usize),
+    /// Pops an array and evaluates the block with the given index for each of its elements.
+    Iterate(Iteration, usize),
+    /// Pops the initial value and an array and reduces the array with the block with the given
+    /// index.
+    Reduce(usize),
+    /// Evaluates the blocks of the alternatives of `try` with the given index.
+    Try(usize),
+    /// Evaluates the blocks of the arguments of `let` with the given index.
+    Let(usize),
+    /// Pops a value and pushes the result of `match` or `captures` with the compiled pattern with
+    /// the given index.
+    #[cfg(feature = "regex")]
+    Match(Operator, usize),
+}
+
+/// The number of values that the stack holds without allocating.
+const INLINE_VALUES: usize = 8;
+
+/// An unused slot of the stack.
+const EMPTY: Cow<'static, Value> = Cow::Borrowed(&Value::Null);
+
+/// The stack of the stack machine. Most blocks need only a few values at once, which are kept in
+/// an array instead of allocating. Constants and the data are borrowed and only cloned if an
+/// operator needs them, or if they are the result.
+struct Stack<'a> {
+    inline: [Cow<'a, Value>; INLINE_VALUES],
+    /// The slots of blocks that need more than `INLINE_VALUES` values at once.
+    allocated: Vec<Cow<'a, Value>>,
+    len: usize,
+}
+
+impl<'a> Stack<'a> {
+    /// Creates a stack for at most `depth` values.
+    fn new(depth: usize) -> Stack<'a> {
+        Stack {
+            inline: [EMPTY; INLINE_VALUES],
+            allocated: if depth > INLINE_VALUES {
+                vec![EMPTY; depth]
+            } else {
+                vec![]
+            },
+            len: 0,
+        }
+    }
+
+    fn slots(&mut self) -> &mut [Cow<'a, Value>] {
+        if self.allocated.is_empty() {
+            &mut self.inline
+        } else {
+            &mut self.allocated
+        }
+    }
+
+    /// Returns the values on the stack, from the bottom to the top.
+    fn values(&mut self) -> &mut [Cow<'a, Value>] {
+        let len = self.len;
+        &mut self.slots()[..len]
+    }
+
+    /// Returns the value on top of the stack.
+    fn last(&mut self) -> &Value {
+        let len = self.len;
+        &self.slots()[len - 1]
+    }
+
+    fn push(&mut self, value: Cow<'a, Value>) {
+        let len = self.len;
+        self.slots()[len] = value;
+        self.len += 1;
+    }
+
+    fn pop(&mut self) -> Cow<'a, Value> {
+        self.len -= 1;
+        let len = self.len;
+        mem::replace(&mut self.slots()[len], EMPTY)
+    }
+
+    /// Removes the values above `len`. They are dropped when their slot is reused or with the
+    /// stack.
+    fn truncate(&mut self, len: usize) {
+        self.len = len;
+    }
+}
+
+/// A JsonLogic rule that has been compiled to bytecode, an alternative to `Rule` that does not
+/// walk the expression tree on every evaluation.
+///
+/// Conditional operations (`if`, `and`, `or` and the default of `var`) are compiled to jumps and
+/// the logic of `map`, `filter`, `reduce`, `all`, `some` and `none` as well as the arguments of
+/// `try` and `let` to separate blocks of instructions. Comparisons and (in)equalities of two
+/// values have their own instruction and comparisons of two numbers do not go through the type
+/// coercion. All other operators are called with their evaluated arguments, so the results are the
+/// same as the ones of `Rule`.
+///
+/// # Example
+///
+/// ```
+/// use serde_json::json;
+/// use jsonlogic::BytecodeRule;
+///
+/// let json_logic = json!({ "if": [{ ">": [{ "var": "temp" }, 30] }, "hot", "fine"] });
+/// let rule = BytecodeRule::compile(&json_logic).unwrap();
+///
+/// assert_eq!(rule.apply(&json!({ "temp": 35 })), Ok(json!("hot")));
+/// assert_eq!(rule.apply(&json!({ "temp": 20 })), Ok(json!("fine")));
+/// ```
+#[derive(Debug, PartialEq)]
+pub struct BytecodeRule {
+    /// The first block is the rule itself, the other ones are the logic of operations like `map`.
+    blocks: Vec<Vec<Instruction>>,
+    /// The most values that each block needs on the stack at once.
+    depths: Vec<usize>,
+    constants: Vec<Value>,
+    paths: Vec<Path>,
+    /// The blocks of the arguments of every `try` and `let`, which evaluate their arguments
+    /// themselves.
+    argument_blocks: Vec<Vec<usize>>,
+    #[cfg(feature = "regex")]
+    patterns: Vec<Pattern>,
+    string_mode: StringMode,
+}
+
+impl BytecodeRule {
+    /// Parses and compiles the given JsonLogic rule. Errors if the rule contains an unrecognized
+    /// operation.
+    pub fn compile(json_logic: &Value) -> Result<BytecodeRule, String> {
+        let expr = Expression::from_json(json_logic)?;
+        Ok(BytecodeRule::from_expression(&expr))
+    }
+
+    fn from_expression(expr: &Expression) -> BytecodeRule {
+        let mut rule = BytecodeRule {
+            blocks: vec![],
+            depths: vec![],
+            constants: vec![],
+            paths: vec![],
+            argument_blocks: vec![],
+            #[cfg(feature = "regex")]
+            patterns: vec![],
+            string_mode: StringMode::default(),
+        };
+        rule.compile_block(expr);
+        rule
+    }
+
+    /// Sets how strings are measured and indexed when applying the rule, instead of
+    /// `StringMode::Chars`.
+    pub fn with_string_mode(self, string_mode: StringMode) -> BytecodeRule {
+        BytecodeRule {
+            string_mode,
+            ..self
+        }
+    }
+
+    fn data<'d>(&self, data: &'d Value) -> Data<'d> {
+        Data::from_json(data).with_string_mode(self.string_mode)
+    }
+
+    /// Applies the rule to the given data. Errors with the thrown value if the rule throws an
+    /// error that is not caught, like `Rule::apply`.
+    pub fn apply(&self, data: &Value) -> Result<Value, Value> {
+        self.run(0, &self.data(data))
+    }
+
+    /// Applies the rule to the given data, with `clock` as the source of the current time for
+    /// the `now` operator.
+    #[cfg(feature = "datetime")]
+    pub fn apply_with_clock(&self, data: &Value, clock: &dyn crate::Clock) -> Result<Value, Value> {
+        self.run(0, &self.data(data).with_clock(clock))
+    }
+
+    /// Compiles the expression into a new block and returns its index.
+    fn compile_block(&mut self, expr: &Expression) -> usize {
+        let index = self.blocks.len();
+        self.blocks.push(vec![]);
+        let mut code = vec![];
+        self.compile_expression(expr, &mut code);
+        self.depths.resize(self.blocks.len(), 0);
+        self.depths[index] = stack_depth(&code);
+        self.blocks[index] = code;
+        index
+    }
+
+    fn constant(&mut self, value: &Value) -> usize {
+        self.constants.push(value.clone());
+        self.constants.len() - 1
+    }
+
+    fn compile_expression(&mut self, expr: &Expression, code: &mut Vec<Instruction>) {
+        match expr {
+            Expression::Constant(value) | Expression::Paths(value, _) => {
+                let index = self.constant(value);
+                code.push(Instruction::Push(index));
+            }
+            #[cfg(feature = "regex")]
+            Expression::Pattern(value, _) => {
+                let index = self.constant(value);
+                code.push(Instruction::Push(index));
+            }
+            Expression::Reference(_, expr) => self.compile_expression(expr, code),
+            Expression::Computed(operator, args) => self.compile_operation(*operator, args, code),
+        }
+    }
+
+    fn compile_operation(
+        &mut self,
+        operator: Operator,
+        args: &[Expression],
+        code: &mut Vec<Instruction>,
+    ) {
+        match (operator, args.len()) {
+            (Operator::Variable, _) => self.compile_variable(args, code),
+            (Operator::If, 0) | (Operator::And, 0) | (Operator::Or, 0) => {
+                let index = self.constant(&Value::Null);
+                code.push(Instruction::Push(index));
+            }
+            (Operator::If, 1) => self.compile_expression(&args[0], code),
+            (Operator::If, _) => {
+                // Pairs of condition and value, with an optional else value at the end.
+                let mut end_jumps = vec![];
+                let mut pairs = args.chunks_exact(2);
+                for pair in &mut pairs {
+                    let condition_jump = self.compile_condition(&pair[0], code);
+                    self.compile_expression(&pair[1], code);
+                    end_jumps.push(code.len());
+                    code.push(Instruction::Jump(0));
+                    code[condition_jump] = match code[condition_jump] {
+                        Instruction::JumpIfNot(comparison, _) => {
+                            Instruction::JumpIfNot(comparison, code.len())
+                        }
+                        _ => Instruction::JumpIfFalsy(code.len()),
+                    };
+                }
+                match pairs.remainder().first() {
+                    Some(otherwise) => self.compile_expression(otherwise, code),
+                    None => {
+                        let index = self.constant(&Value::Null);
+                        code.push(Instruction::Push(index));
+                    }
+                }
+                for jump in end_jumps {
+                    code[jump] = Instruction::Jump(code.len());
+                }
+            }
+            (Operator::And, _) | (Operator::Or, _) => {
+                let mut end_jumps = vec![];
+                for (index, arg) in args.iter().enumerate() {
+                    self.compile_expression(arg, code);
+                    if index < args.len() - 1 {
+                        end_jumps.push(code.len());
+                        code.push(Instruction::Jump(0));
+                    }
+                }
+                let end = code.len();
+                for jump in end_jumps {
+                    code[jump] = if operator == Operator::And {
+                        Instruction::JumpIfFalsyElsePop(end)
+                    } else {
+                        Instruction::JumpIfTruthyElsePop(end)
+                    };
+                }
+            }
+            (operator, 2) if Comparison::of(operator).is_some() => {
+                self.compile_expression(&args[0], code);
+                self.compile_expression(&args[1], code);
+                code.push(Instruction::Compare(Comparison::of(operator).unwrap()));
+            }
+            (Operator::Map, len)
+            | (Operator::Filter, len)
+            | (Operator::All, len)
+            | (Operator::Some, len)
+            | (Operator::None, len)
+            | (Operator::Sort, len)
+            | (Operator::Find, len)
+            | (Operator::Count, len)
+            | (Operator::GroupBy, len)
+                if len >= 2 =>
+            {
+                let iteration = match operator {
+                    Operator::Map => Iteration::Map,
+                    Operator::Filter => Iteration::Filter,
+                    Operator::All => Iteration::All,
+                    Operator::Some => Iteration::Some,
+                    Operator::Sort => Iteration::Sort,
+                    Operator::Find => Iteration::Find,
+                    Operator::Count => Iteration::Count,
+                    Operator::GroupBy => Iteration::GroupBy,
+                    _ => Iteration::None,
+                };
+                self.compile_expression(&args[0], code);
+                let block = self.compile_block(&args[1]);
+                code.push(Instruction::Iterate(iteration, block));
+            }
+            (Operator::Reduce, len) if len >= 2 => {
+                self.compile_expression(&args[0], code);
+                match args.get(2) {
+                    Some(initial) => self.compile_expression(initial, code),
+                    None => {
+                        let index = self.constant(&Value::Null);
+                        code.push(Instruction::Push(index));
+                    }
+                }
+                let block = self.compile_block(&args[1]);
+                code.push(Instruction::Reduce(block));
+            }
+            (Operator::Try, _) | (Operator::Let, _) => {
+                let blocks = args.iter().map(|arg| self.compile_block(arg)).collect();
+                self.argument_blocks.push(blocks);
+                let index = self.argument_blocks.len() - 1;
+                code.push(if operator == Operator::Try {
+                    Instruction::Try(index)
+                } else {
+                    Instruction::Let(index)
+                });
+            }
+            #[cfg(feature = "regex")]
+            (Operator::Match, 2) | (Operator::Captures, 2) if args[1].pattern().is_some() => {
+                self.compile_expression(&args[0], code);
+                self.patterns.push(args[1].pattern().unwrap().clone());
+                code.push(Instruction::Match(operator, self.patterns.len() - 1));
+            }
+            _ => {
+                for arg in args.iter() {
+                    self.compile_expression(arg, code);
+                }
+                code.push(Instruction::Call(operator, args.len()));
+            }
+        }
+    }
+
+    /// Compiles the condition of an `if` and a jump for when it is false, which is patched by the
+    /// caller. Returns the index of the jump. Comparisons jump without pushing their result.
+    fn compile_condition(&mut self, expr: &Expression, code: &mut Vec<Instruction>) -> usize {
+        match expr {
+            Expression::Computed(operator, args) if args.len() == 2 => {
+                if let Some(comparison) = Comparison::of(*operator) {
+                    self.compile_expression(&args[0], code);
+                    self.compile_expression(&args[1], code);
+                    code.push(Instruction::JumpIfNot(comparison, 0));
+                    return code.len() - 1;
+                }
+            }
+            _ => (),
+        }
+        self.compile_expression(expr, code);
+        code.push(Instruction::JumpIfFalsy(0));
+        code.len() - 1
+    }
+
+    fn compile_variable(&mut self, args: &[Expression], code: &mut Vec<Instruction>) {
+        let path = match args.first() {
+            None => {
+                code.push(Instruction::LoadData);
+                return;
+            }
+            Some(Expression::Paths(_, paths)) => {
+                self.paths.push(paths[0].clone());
+                Some(Instruction::LoadPath {
+                    path: self.paths.len() - 1,
+                    default: None,
+                })
+            }
+            Some(Expression::Constant(path)) => match path {
+                Value::Null => {
+                    code.push(Instruction::LoadData);
+                    return;
+                }
+                Value::String(s) if s.is_empty() => {
+                    code.push(Instruction::LoadData);
+                    return;
+                }
+                _ => Some(Instruction::LoadVar {
+                    path: self.constant(path),
+                    default: None,
+                }),
+            },
+            Some(path) => {
+                self.compile_expression(path, code);
+                None
+            }
+        };
+
+        let load = code.len();
+        code.push(Instruction::LoadData);
+        let default = match args.get(1) {
+            Some(default) => {
+                // Skip the default if the value exists.
+                let skip = code.len();
+                code.push(Instruction::Jump(0));
+                let start = code.len();
+                self.compile_expression(default, code);
+                code[skip] = Instruction::Jump(code.len());
+                Some(start)
+            }
+            None => None,
+        };
+        code[load] = match path {
+            Some(Instruction::LoadPath { path, .. }) => Instruction::LoadPath { path, default },
+            Some(Instruction::LoadVar { path, .. }) => Instruction::LoadVar { path, default },
+            _ => Instruction::LoadDynamicVar { default },
+        };
+    }
+
+    /// Runs the block with the given index and returns its result. Errors with the thrown value
+    /// if an error is thrown that is not caught.
+    fn run(&self, block: usize, data: &Data) -> Result<Value, Value> {
+        let code = &self.blocks[block];
+        let mut stack = Stack::new(self.depths[block]);
+        let mut pc = 0;
+
+        while pc < code.len() {
+            match code[pc] {
+                Instruction::Push(index) => stack.push(Cow::Borrowed(&self.constants[index])),
+                Instruction::LoadData => stack.push(Cow::Borrowed(data.get_plain())),
+                Instruction::LoadVar { default, .. }
+                | Instruction::LoadPath { default, .. }
+                | Instruction::LoadDynamicVar { default } => {
+                    let value = match code[pc] {
+                        Instruction::LoadVar { path, .. } => data.get_value(&self.constants[path]),
+                        Instruction::LoadPath { path, .. } => {
+                            data.get_value_by_path(&self.paths[path])
+                        }
+                        _ => {
+                            let path = stack.pop();
+                            match path.as_ref() {
+                                Value::Null => Some(data.get_plain().clone()),
+                                Value::String(s) if s.is_empty() => Some(data.get_plain().clone()),
+                                path => data.get_value(path),
+                            }
+                        }
+                    };
+                    match (value, default) {
+                        (Some(value), _) => stack.push(Cow::Owned(value)),
+                        (None, Some(default)) => {
+                            pc = default;
+                            continue;
+                        }
+                        (None, None) => stack.push(Cow::Owned(Value::Null)),
+                    }
+                }
+                Instruction::Jump(target) => {
+                    pc = target;
+                    continue;
+                }
+                Instruction::JumpIfFalsy(target) => {
+                    let truthy = logic::is_truthy(stack.last());
+                    stack.truncate(stack.len - 1);
+                    if !truthy {
+                        pc = target;
+                        continue;
+                    }
+                }
+                Instruction::JumpIfNot(comparison, target) => {
+                    let start = stack.len - 2;
+                    let values = stack.values();
+                    let result = comparison.compare(&values[start], &values[start + 1]);
+                    stack.truncate(start);
+                    if !result {
+                        pc = target;
+                        continue;
+                    }
+                }
+                Instruction::JumpIfFalsyElsePop(target) => {
+                    if !logic::is_truthy(stack.last()) {
+                        pc = target;
+                        continue;
+                    }
+                    stack.truncate(stack.len - 1);
+                }
+                Instruction::JumpIfTruthyElsePop(target) => {
+                    if logic::is_truthy(stack.last()) {
+                        pc = target;
+                        continue;
+                    }
+                    stack.truncate(stack.len - 1);
+                }
+                Instruction::Compare(comparison) => {
+                    let start = stack.len - 2;
+                    let values = stack.values();
+                    let result = comparison.compare(&values[start], &values[start + 1]);
+                    stack.truncate(start);
+                    stack.push(Cow::Owned(Value::Bool(result)));
+                }
+                Instruction::Call(operator, count) => {
+                    let start = stack.len - count;
+                    let args = &mut stack.values()[start..];
+                    let value = match operator.function() {
+                        // Avoid allocating for the common case of up to two arguments.
+                        Function::Eager(compute) => match args {
+                            [] => compute(&[], data),
+                            [a] => compute(&[take_value(a)], data),
+                            [a, b] => compute(&[take_value(a), take_value(b)], data),
+                            _ => {
+                                compute(&args.iter_mut().map(take_value).collect::<Vec<_>>(), data)
+                            }
+                        },
+                        Function::Lazy(compute) => call_lazy(compute, args, data)?,
+                    };
+                    stack.truncate(start);
+                    stack.push(Cow::Owned(value));
+                }
+                Instruction::Iterate(iteration, block) => {
+                    let value = stack.pop().into_owned();
+                    stack.push(Cow::Owned(self.iterate(iteration, block, value, data)?));
+                }
+                Instruction::Reduce(block) => {
+                    let initial = stack.pop();
+                    let value = stack.pop();
+                    stack.push(Cow::Owned(self.reduce(block, &value, initial, data)?));
+                }
+                Instruction::Try(index) => {
+                    stack.push(Cow::Owned(try_alternatives(
+                        &self.argument_blocks[index],
+                        data,
+                        |&block, data| self.run(block, data),
+                    )?));
+                }
+                Instruction::Let(index) => {
+                    stack.push(Cow::Owned(evaluate_let(
+                        &self.argument_blocks[index],
+                        data,
+                        &|&block, data| self.run(block, data),
+                    )?));
+                }
+                #[cfg(feature = "regex")]
+                Instruction::Match(operator, pattern) => {
+                    let value = stack.pop();
+                    stack.push(Cow::Owned(apply_pattern(
+                        operator,
+                        &value,
+                        &self.patterns[pattern],
+                    )));
+                }
+            }
+            pc += 1;
+        }
+
+        Ok(stack.values().last_mut().map_or(Value::Null, take_value))
+    }
+
+    /// Reduces the array with the block, with the same semantics as the operator.
+    #[inline(never)]
+    fn reduce(
+        &self,
+        block: usize,
+        value: &Value,
+        initial: Cow<Value>,
+        data: &Data,
+    ) -> Result<Value, Value> {
+        let arr = match value {
+            Value::Array(arr) => arr,
+            _ => return Ok(initial.into_owned()),
+        };
+        let mut accumulator = initial.into_owned();
+        for current in arr.iter() {
+            accumulator = self.run(
+                block,
+                &data.nested(&json!({ "current": current, "accumulator": accumulator })),
+            )?;
+        }
+        Ok(accumulator)
+    }
+
+    /// Evaluates an iteration like `map`, with the same semantics as the operators.
+    fn iterate(
+        &self,
+        iteration: Iteration,
+        block: usize,
+        value: Value,
+        data: &Data,
+    ) -> Result<Value, Value> {
+        let arr = match (iteration, value) {
+            (_, Value::Array(arr)) => arr,
+            // Like the operator, `all` also iterates over the characters of a string.
+            (Iteration::All, Value::String(s)) => {
+                s.chars().map(|ch| Value::String(ch.to_string())).collect()
+            }
+            (Iteration::Map, _) | (Iteration::Filter, _) | (Iteration::Sort, _) => vec![],
+            (Iteration::All, _) | (Iteration::Some, _) => return Ok(Value::Bool(false)),
+            (Iteration::None, _) => return Ok(Value::Bool(true)),
+            (Iteration::Find, _) => return Ok(Value::Null),
+            (Iteration::Count, _) => return Ok(Value::from(0)),
+            (Iteration::GroupBy, _) => return Ok(Value::Object(serde_json::Map::new())),
+        };
+        let results = arr.iter().map(|elem| self.run(block, &data.nested(elem)));
+
+        Ok(match iteration {
+            Iteration::Map => Value::Array(results.collect::<Result<_, _>>()?),
+            Iteration::Filter => {
+                let include: Vec<Value> = results.collect::<Result<_, _>>()?;
+                Value::Array(
+                    arr.iter()
+                        .zip(include)
+                        .filter(|(_, include)| logic::is_truthy(include))
+                        .map(|(elem, _)| elem.clone())
+                        .collect(),
+                )
+            }
+            // `all`, `some`, `none` and `find` stop at the first element that decides the result.
+            Iteration::All => {
+                for result in results {
+                    if !logic::is_truthy(&result?) {
+                        return Ok(Value::Bool(false));
+                    }
+                }
+                Value::Bool(!arr.is_empty())
+            }
+            Iteration::Some | Iteration::None => {
+                let mut found = false;
+                for result in results {
+                    if logic::is_truthy(&result?) {
+                        found = true;
+                        break;
+                    }
+                }
+                Value::Bool(found == (iteration == Iteration::Some))
+            }
+            Iteration::Sort => {
+                let keys = results.collect::<Result<_, _>>()?;
+                sort_by_keys(arr, keys)
+            }
+            Iteration::Find => {
+                for (index, result) in results.enumerate() {
+                    if logic::is_truthy(&result?) {
+                        return Ok(arr[index].clone());
+                    }
+                }
+                Value::Null
+            }
+            Iteration::Count => {
+                let mut count = 0;
+                for result in results {
+                    if logic::is_truthy(&result?) {
+                        count += 1;
+                    }
+                }
+                Value::from(count)
+            }
+            Iteration::GroupBy => {
+                let keys = results.collect::<Result<_, _>>()?;
+                group(arr, keys)
+            }
+        })
+    }
+}
+
+/// Returns the most values that the code needs on the stack at once. As jumps only skip code that
+/// pushes values, the stack is never deeper than when the code would run straight through.
+fn stack_depth(code: &[Instruction]) -> usize {
+    let mut len: isize = 0;
+    let mut depth: isize = 0;
+    for instruction in code {
+        len += match *instruction {
+            Instruction::Push(_)
+            | Instruction::LoadData
+            | Instruction::LoadVar { .. }
+            | Instruction::LoadPath { .. }
+            | Instruction::Try(_)
+            | Instruction::Let(_) => 1,
+            Instruction::LoadDynamicVar { .. }
+            | Instruction::Jump(_)
+            | Instruction::Iterate(..) => 0,
+            #[cfg(feature = "regex")]
+            Instruction::Match(..) => 0,
+            Instruction::JumpIfFalsy(_)
+            | Instruction::JumpIfFalsyElsePop(_)
+            | Instruction::JumpIfTruthyElsePop(_)
+            | Instruction::Compare(_)
+            | Instruction::Reduce(_) => -1,
+            Instruction::JumpIfNot(..) => -2,
+            Instruction::Call(_, count) => 1 - count as isize,
+        };
+        depth = depth.max(len);
+    }
+    depth as usize
+}
+
+/// Takes the value out of the stack, which clones it if it is borrowed.
+fn take_value(value: &mut Cow<Value>) -> Value {
+    match value {
+        Cow::Borrowed(value) => (*value).clone(),
+        Cow::Owned(value) => mem::take(value),
+    }
+}
+
+/// Calls a lazy operator like `throw` with the arguments. They have been evaluated already, as
+/// far as the operator evaluates them.
+#[inline(never)]
+fn call_lazy(
+    compute: fn(&[Expression], &Data) -> Result<Value, Value>,
+    args: &[Cow<Value>],
+    data: &Data,
+) -> Result<Value, Value> {
+    let args: Vec<_> = args.iter().map(|arg| Expression::Constant(arg)).collect();
+    compute(&args, data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compile(json_logic: Value) -> BytecodeRule {
+        BytecodeRule::compile(&json_logic).unwrap()
+    }
+
+    #[test]
+    fn short_circuit_jumps() {
+        let rule = compile(json!({ "and": [{ "var": "a" }, { "or": [false, 2] }] }));
+        assert_eq!(
+            rule.blocks,
+            vec![vec![
+                Instruction::LoadPath {
+                    path: 0,
+                    default: None
+                },
+                Instruction::JumpIfFalsyElsePop(5),
+                Instruction::Push(0),
+                Instruction::JumpIfTruthyElsePop(5),
+                Instruction::Push(1),
+            ]]
+        );
+        assert_eq!(rule.apply(&json!({ "a": 0 })), Ok(json!(0)));
+        assert_eq!(rule.apply(&json!({ "a": 1 })), Ok(json!(2)));
+
+        let rule = compile(json!({ "if": [{ "var": "a" }, "a", { "var": "b" }, "b"] }));
+        assert_eq!(
+            rule.blocks,
+            vec![vec![
+                Instruction::LoadPath {
+                    path: 0,
+                    default: None
+                },
+                Instruction::JumpIfFalsy(4),
+                Instruction::Push(0),
+                Instruction::Jump(9),
+                Instruction::LoadPath {
+                    path: 1,
+                    default: None
+                },
+                Instruction::JumpIfFalsy(8),
+                Instruction::Push(1),
+                Instruction::Jump(9),
+                Instruction::Push(2),
+            ]]
+        );
+        assert_eq!(rule.apply(&json!({ "a": true })), Ok(json!("a")));
+        assert_eq!(rule.apply(&json!({ "b": true })), Ok(json!("b")));
+        assert_eq!(rule.apply(&json!({})), Ok(json!(null)));
+    }
+
+    #[test]
+    fn variable_defaults() {
+        let rule = compile(json!({ "var": ["a", { "+": [1, 2] }] }));
+        assert_eq!(
+            rule.blocks,
+            vec![vec![
+                Instruction::LoadPath {
+                    path: 0,
+                    default: Some(2)
+                },
+                Instruction::Jump(5),
+                Instruction::Push(0),
+                Instruction::Push(1),
+                Instruction::Call(Operator::Addition, 2),
+            ]]
+        );
+        assert_eq!(rule.apply(&json!({ "a": "x" })), Ok(json!("x")));
+        assert_eq!(rule.apply(&json!({})), Ok(json!(3)));
+
+        let rule = compile(json!({ "var": [{ "var": "key" }, "none"] }));
+        assert_eq!(rule.apply(&json!({ "key": "a", "a": 1 })), Ok(json!(1)));
+        assert_eq!(
+            rule.apply(&json!({ "key": "b", "a": 1 })),
+            Ok(json!("none"))
+        );
+        assert_eq!(rule.apply(&json!({ "a": 1 })), Ok(json!({ "a": 1 })));
+    }
+
+    #[test]
+    fn blocks() {
+        let rule = compile(json!({ "map": [
+            { "filter": [{ "var": "items" }, { ">": [{ "var": "" }, 1] }] },
+            { "*": [{ "var": "" }, 2] }
+        ]}));
+        assert_eq!(rule.blocks.len(), 3);
+        assert_eq!(
+            rule.apply(&json!({ "items": [1, 2, 3] })),
+            Ok(json!([4, 6]))
+        );
+
+        let rule = compile(json!({ "reduce": [
+            { "var": "items" },
+            { "+": [{ "var": "current" }, { "var": "accumulator" }] },
+            10
+        ]}));
+        assert_eq!(rule.apply(&json!({ "items": [1, 2, 3] })), Ok(json!(16)));
+        assert_eq!(rule.apply(&json!({ "items": "x" })), Ok(json!(10)));
+    }
+
+    #[test]
+    fn numeric_comparisons() {
+        let rule = compile(json!({ "<": [{ "var": "a" }, { "var": "b" }] }));
+        assert_eq!(rule.blocks[0][2], Instruction::Compare(Comparison::Less));
+        assert_eq!(rule.apply(&json!({ "a": 1, "b": 2.5 })), Ok(json!(true)));
+        assert_eq!(rule.apply(&json!({ "a": "10", "b": 9 })), Ok(json!(false)));
+        assert_eq!(rule.apply(&json!({ "a": "a", "b": "b" })), Ok(json!(true)));
+    }
+
+    #[test]
+    fn equality_conditions() {
+        let rule = compile(json!({ "if": [{ "==": [{ "var": "a" }, 1] }, "one", "other"] }));
+        assert_eq!(
+            rule.blocks[0][2],
+            Instruction::JumpIfNot(Comparison::Equal, 5)
+        );
+        assert_eq!(rule.apply(&json!({ "a": "1" })), Ok(json!("one")));
+        assert_eq!(rule.apply(&json!({ "a": 2 })), Ok(json!("other")));
+
+        let rule = compile(json!({ "!==": [{ "var": "a" }, 9007199254740993u64] }));
+        assert_eq!(
+            rule.apply(&json!({ "a": 9007199254740992u64 })),
+            Ok(json!(true))
+        );
+    }
+
+    #[test]
+    fn deep_stack() {
+        let rule = compile(json!({ "+": [1, 2, 3, 4, 5, 6, 7, 8, 9, { "+": [10, 11] }] }));
+        assert_eq!(rule.depths, vec![11]);
+        assert_eq!(rule.apply(&json!(null)), Ok(json!(66)));
+    }
+}
@@ -0,0 +1,34 @@
+use std::time::SystemTime;
+
+/// The source of the current time for the `now` operator of the `datetime` feature. Rules are
+/// applied with the system clock, unless another clock is passed to `apply_with_clock`, e.g. to
+/// evaluate rules at a fixed point in time in tests.
+///
+/// Every function returning a `SystemTime` is a clock.
+///
+/// # Example
+///
+/// ```
+/// use serde_json::json;
+/// use std::time::{Duration, SystemTime};
+/// use jsonlogic::Rule;
+///
+/// let json_logic = json!({ "now": [] });
+/// let rule = Rule::compile(&json_logic).unwrap();
+/// let clock = || SystemTime::UNIX_EPOCH + Duration::from_secs(86400);
+///
+/// assert_eq!(
+///     rule.apply_with_clock(&json!(null), &clock),
+///     Ok(json!("1970-01-02T00:00:00Z"))
+/// );
+/// ```
+pub trait Clock {
+    /// Returns the current time.
+    fn now(&self) -> SystemTime;
+}
+
+impl<F: Fn() -> SystemTime> Clock for F {
+    fn now(&self) -> SystemTime {
+        self()
+    }
+}
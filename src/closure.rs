@@ -0,0 +1,614 @@
+use serde_json::{json, Value};
+use std::cmp::Ordering;
+use std::fmt;
+
+use crate::expression::Expression;
+#[cfg(feature = "regex")]
+use crate::operators::apply_pattern;
+use crate::operators::{
+    evaluate_let, group, logic, sort_by_keys, try_alternatives, Function, Operator,
+};
+use crate::{Data, StringMode};
+
+/// A compiled expression. Errors with the thrown value like `Expression::compute`.
+type Closure = Box<dyn Fn(&Data) -> Result<Value, Value> + Send + Sync>;
+
+/// A compiled condition, which returns whether the expression is truthy without creating its
+/// value if it is a comparison.
+type Predicate = Box<dyn Fn(&Data) -> Result<bool, Value> + Send + Sync>;
+
+/// A comparison operator that has a fast path for comparing numbers with a constant.
+#[derive(Debug, PartialEq, Copy, Clone)]
+enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+}
+
+impl Comparison {
+    fn from_operator(operator: Operator) -> Option<Comparison> {
+        match operator {
+            Operator::Equal => Some(Comparison::Equal),
+            Operator::NotEqual => Some(Comparison::NotEqual),
+            Operator::LessThan => Some(Comparison::Less),
+            Operator::LessEqualThan => Some(Comparison::LessEqual),
+            Operator::GreaterThan => Some(Comparison::Greater),
+            Operator::GreaterEqualThan => Some(Comparison::GreaterEqual),
+            _ => None,
+        }
+    }
+
+    /// Compares the values with the coercion of the operators.
+    fn compare(self, a: &Value, b: &Value) -> bool {
+        match self {
+            Comparison::Equal => logic::is_abstract_equal(a, b),
+            Comparison::NotEqual => !logic::is_abstract_equal(a, b),
+            Comparison::Less => logic::less_than(a, b),
+            Comparison::LessEqual => logic::less_equal_than(a, b),
+            Comparison::Greater => logic::greater_than(a, b),
+            Comparison::GreaterEqual => logic::greater_equal_than(a, b),
+        }
+    }
+
+    /// Derives the result of the comparison from whether `a < b` and `a == b`, the same way the
+    /// operators do. `less` is `None` if `a` and `b` are unordered, because one of them is NaN.
+    fn with_less_and_equal(self, less: Option<bool>, equal: bool) -> bool {
+        match self {
+            Comparison::Equal => equal,
+            Comparison::NotEqual => !equal,
+            Comparison::Less => less == Some(true),
+            Comparison::LessEqual => less == Some(true) || equal,
+            Comparison::Greater => less == Some(false) && !equal,
+            Comparison::GreaterEqual => less == Some(false),
+        }
+    }
+}
+
+/// A JsonLogic rule that has been compiled into a tree of closures, an alternative to `Rule` that
+/// does not dispatch on the operator on every evaluation.
+///
+/// Everything that does not depend on the data is done once when compiling: the function of each
+/// operator is looked up, the paths of `var` are split into their steps and constants that are
+/// compared with `==`, `!=`, `<`, `<=`, `>` or `>=` are converted to numbers. The results are the
+/// same as the ones of `Rule`.
+///
+/// # Example
+///
+/// ```
+/// use serde_json::json;
+/// use jsonlogic::ClosureRule;
+///
+/// let json_logic = json!({ "if": [{ ">": [{ "var": "weather.temp" }, "30"] }, "hot", "fine"] });
+/// let rule = ClosureRule::compile(&json_logic).unwrap();
+///
+/// assert_eq!(rule.apply(&json!({ "weather": { "temp": 35 } })), Ok(json!("hot")));
+/// assert_eq!(rule.apply(&json!({ "weather": { "temp": 20 } })), Ok(json!("fine")));
+/// ```
+pub struct ClosureRule {
+    closure: Closure,
+    string_mode: StringMode,
+}
+
+impl ClosureRule {
+    /// Parses and compiles the given JsonLogic rule. Errors if the rule contains an unrecognized
+    /// operation.
+    pub fn compile(json_logic: &Value) -> Result<ClosureRule, String> {
+        let expr = Expression::from_json(json_logic)?;
+        Ok(ClosureRule {
+            closure: compile(&expr),
+            string_mode: StringMode::default(),
+        })
+    }
+
+    /// Sets how strings are measured and indexed when applying the rule, instead of
+    /// `StringMode::Chars`.
+    pub fn with_string_mode(self, string_mode: StringMode) -> ClosureRule {
+        ClosureRule {
+            string_mode,
+            ..self
+        }
+    }
+
+    fn data<'d>(&self, data: &'d Value) -> Data<'d> {
+        Data::from_json(data).with_string_mode(self.string_mode)
+    }
+
+    /// Applies the rule to the given data. Errors with the thrown value if the rule throws an
+    /// error that is not caught, like `Rule::apply`.
+    pub fn apply(&self, data: &Value) -> Result<Value, Value> {
+        (self.closure)(&self.data(data))
+    }
+
+    /// Applies the rule to the given data, with `clock` as the source of the current time for
+    /// the `now` operator.
+    #[cfg(feature = "datetime")]
+    pub fn apply_with_clock(&self, data: &Value, clock: &dyn crate::Clock) -> Result<Value, Value> {
+        (self.closure)(&self.data(data).with_clock(clock))
+    }
+}
+
+impl fmt::Debug for ClosureRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ClosureRule").finish()
+    }
+}
+
+fn compile(expr: &Expression) -> Closure {
+    match expr {
+        Expression::Constant(value) | Expression::Paths(value, _) => {
+            let value = (*value).clone();
+            Box::new(move |_| Ok(value.clone()))
+        }
+        #[cfg(feature = "regex")]
+        Expression::Pattern(value, _) => {
+            let value = (*value).clone();
+            Box::new(move |_| Ok(value.clone()))
+        }
+        Expression::Reference(_, expr) => compile(expr),
+        Expression::Computed(operator, args) => compile_operation(*operator, args),
+    }
+}
+
+fn compile_all(args: &[Expression]) -> Vec<Closure> {
+    args.iter().map(compile).collect()
+}
+
+fn compile_operation(operator: Operator, args: &[Expression]) -> Closure {
+    if let (Some(comparison), [a, b]) = (Comparison::from_operator(operator), args) {
+        let predicate = compile_comparison(comparison, a, b);
+        return Box::new(move |data| Ok(Value::Bool(predicate(data)?)));
+    }
+
+    match (operator, args.len()) {
+        (Operator::Variable, _) => compile_variable(args),
+        (Operator::If, len) if len >= 2 => {
+            let mut pairs = args.chunks_exact(2);
+            let branches: Vec<(Predicate, Closure)> = (&mut pairs)
+                .map(|pair| (compile_condition(&pair[0]), compile(&pair[1])))
+                .collect();
+            let otherwise = pairs.remainder().first().map(compile);
+            Box::new(move |data| {
+                for (condition, then) in branches.iter() {
+                    if condition(data)? {
+                        return then(data);
+                    }
+                }
+                match &otherwise {
+                    Some(otherwise) => otherwise(data),
+                    None => Ok(Value::Null),
+                }
+            })
+        }
+        (Operator::And, len) | (Operator::Or, len) if len >= 1 => {
+            let stop_on = operator == Operator::Or;
+            let args = compile_all(args);
+            Box::new(move |data| {
+                let (last, rest) = args.split_last().unwrap();
+                for arg in rest.iter() {
+                    let value = arg(data)?;
+                    if logic::is_truthy(&value) == stop_on {
+                        return Ok(value);
+                    }
+                }
+                last(data)
+            })
+        }
+        (Operator::Map, len)
+        | (Operator::Filter, len)
+        | (Operator::All, len)
+        | (Operator::Some, len)
+        | (Operator::None, len)
+        | (Operator::Sort, len)
+        | (Operator::Find, len)
+        | (Operator::Count, len)
+        | (Operator::GroupBy, len)
+            if len >= 2 =>
+        {
+            compile_iteration(operator, compile(&args[0]), compile(&args[1]))
+        }
+        (Operator::Reduce, len) if len >= 2 => {
+            let arr = compile(&args[0]);
+            let reducer = compile(&args[1]);
+            let initial = args.get(2).map(compile);
+            Box::new(move |data| {
+                // Like the operator, the array is evaluated before the initial value.
+                let arr = arr(data)?;
+                let initial = match &initial {
+                    Some(initial) => initial(data)?,
+                    None => Value::Null,
+                };
+                match arr {
+                    Value::Array(arr) => arr.iter().try_fold(initial, |accumulator, current| {
+                        reducer(
+                            &data
+                                .nested(&json!({ "current": current, "accumulator": accumulator })),
+                        )
+                    }),
+                    _ => Ok(initial),
+                }
+            })
+        }
+        (Operator::Try, _) => {
+            let alternatives = compile_all(args);
+            Box::new(move |data| try_alternatives(&alternatives, data, |arg, data| arg(data)))
+        }
+        (Operator::Let, _) => {
+            let args = compile_all(args);
+            Box::new(move |data| evaluate_let(&args, data, &|arg, data| arg(data)))
+        }
+        #[cfg(feature = "regex")]
+        (Operator::Match, 2) | (Operator::Captures, 2) if args[1].pattern().is_some() => {
+            let value = compile(&args[0]);
+            let pattern = args[1].pattern().unwrap().clone();
+            Box::new(move |data| Ok(apply_pattern(operator, &value(data)?, &pattern)))
+        }
+        _ => compile_call(operator, args),
+    }
+}
+
+/// Compiles the call of the function of the operator with the values of its arguments, without
+/// allocating for the common case of one or two arguments of an eager operator.
+fn compile_call(operator: Operator, args: &[Expression]) -> Closure {
+    let compute = match operator.function() {
+        Function::Eager(compute) => compute,
+        Function::Lazy(compute) => {
+            let args = compile_all(args);
+            return Box::new(move |data| {
+                let values = args
+                    .iter()
+                    .map(|arg| arg(data))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let args: Vec<Expression> = values.iter().map(Expression::Constant).collect();
+                compute(&args, data)
+            });
+        }
+    };
+
+    match args {
+        [] => Box::new(move |data| Ok(compute(&[], data))),
+        [arg] => {
+            let arg = compile(arg);
+            Box::new(move |data| Ok(compute(&[arg(data)?], data)))
+        }
+        [a, Expression::Constant(b)] => {
+            let (a, b) = (compile(a), (*b).clone());
+            Box::new(move |data| Ok(compute(&[a(data)?, b.clone()], data)))
+        }
+        [a, b] => {
+            let (a, b) = (compile(a), compile(b));
+            Box::new(move |data| Ok(compute(&[a(data)?, b(data)?], data)))
+        }
+        _ => {
+            let args = compile_all(args);
+            Box::new(move |data| {
+                let values = args
+                    .iter()
+                    .map(|arg| arg(data))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(compute(&values, data))
+            })
+        }
+    }
+}
+
+fn compile_variable(args: &[Expression]) -> Closure {
+    let default = args.get(1).map(compile);
+    let or_default = move |value: Option<Value>, data: &Data| match (value, &default) {
+        (Some(value), _) => Ok(value),
+        (None, Some(default)) => default(data),
+        (None, None) => Ok(Value::Null),
+    };
+
+    match args.first() {
+        None | Some(Expression::Constant(Value::Null)) => {
+            Box::new(|data| Ok(data.get_plain().clone()))
+        }
+        Some(Expression::Constant(Value::String(path))) if path.is_empty() => {
+            Box::new(|data| Ok(data.get_plain().clone()))
+        }
+        Some(Expression::Paths(_, paths)) => {
+            let path = paths[0].clone();
+            Box::new(move |data| or_default(data.get_value_by_path(&path), data))
+        }
+        Some(Expression::Constant(path)) => {
+            let path = (*path).clone();
+            Box::new(move |data| or_default(data.get_value(&path), data))
+        }
+        Some(path) => {
+            let path = compile(path);
+            Box::new(move |data| match path(data)? {
+                Value::Null => Ok(data.get_plain().clone()),
+                Value::String(s) if s.is_empty() => Ok(data.get_plain().clone()),
+                path => or_default(data.get_value(&path), data),
+            })
+        }
+    }
+}
+
+/// Compiles the condition of an `if`. Comparisons return their result directly.
+fn compile_condition(expr: &Expression) -> Predicate {
+    if let Expression::Computed(operator, args) = expr {
+        if let (Some(comparison), [a, b]) = (Comparison::from_operator(*operator), &args[..]) {
+            return compile_comparison(comparison, a, b);
+        }
+    }
+    let closure = compile(expr);
+    Box::new(move |data| Ok(logic::is_truthy(&closure(data)?)))
+}
+
+fn compile_comparison(comparison: Comparison, a: &Expression, b: &Expression) -> Predicate {
+    // Numbers and strings that are compared with a number are converted to numbers. Decimals are
+    // compared by the coercing comparisons.
+    let constant_number = |expr: &Expression| match expr {
+        _ if cfg!(feature = "decimal") => None,
+        Expression::Constant(value @ Value::Number(_))
+        | Expression::Constant(value @ Value::String(_)) => {
+            Some(((*value).clone(), logic::coerce_to_f64(value)))
+        }
+        _ => None,
+    };
+
+    match (constant_number(a), constant_number(b)) {
+        (None, Some((constant, number))) => {
+            let a = compile(a);
+            Box::new(move |data| {
+                let a = a(data)?;
+                Ok(match compare_with_number(&a, &constant, number) {
+                    Some((less, equal, _)) => comparison.with_less_and_equal(less, equal),
+                    None => comparison.compare(&a, &constant),
+                })
+            })
+        }
+        (Some((constant, number)), None) => {
+            let b = compile(b);
+            Box::new(move |data| {
+                let b = b(data)?;
+                Ok(match compare_with_number(&b, &constant, number) {
+                    Some((_, equal, greater)) => comparison.with_less_and_equal(greater, equal),
+                    None => comparison.compare(&constant, &b),
+                })
+            })
+        }
+        _ => {
+            let (a, b) = (compile(a), compile(b));
+            Box::new(move |data| Ok(comparison.compare(&a(data)?, &b(data)?)))
+        }
+    }
+}
+
+/// Returns whether `value < constant`, `value == constant` and `value > constant` if the value is
+/// a number, where `number` is the constant converted to a number. Whether the value is less or
+/// greater is `None` if the numbers are unordered.
+#[allow(clippy::type_complexity)]
+fn compare_with_number(
+    value: &Value,
+    constant: &Value,
+    number: Option<f64>,
+) -> Option<(Option<bool>, bool, Option<bool>)> {
+    let (value_number, number) = match (value, number) {
+        (Value::Number(n), Some(number)) => match n.as_f64() {
+            Some(n) => (n, number),
+            // Like NaN, numbers too large for an `f64` are neither less, equal nor greater.
+            None => return Some((None, false, None)),
+        },
+        (Value::Number(_), None) => return Some((None, false, None)),
+        _ => return None,
+    };
+    let equal = match constant {
+        // Compare the numbers exactly, like `==` does.
+        Value::Number(_) => logic::is_strict_equal(value, constant),
+        _ => value_number == number,
+    };
+    let ordering = match (value, constant) {
+        // Order integers above 2^53 exactly, like the operators.
+        (Value::Number(_), Value::Number(_)) => {
+            match (logic::as_integer(value), logic::as_integer(constant)) {
+                (Some(value), Some(constant)) => Some(value.cmp(&constant)),
+                _ => value_number.partial_cmp(&number),
+            }
+        }
+        _ => value_number.partial_cmp(&number),
+    };
+    Some((
+        ordering.map(|ordering| ordering == Ordering::Less),
+        equal,
+        ordering.map(|ordering| ordering == Ordering::Greater),
+    ))
+}
+
+/// Returns whether the logic of an iteration evaluates to a truthy value for the element.
+fn is_truthy_for(logic: &Closure, elem: &Value, data: &Data) -> Result<bool, Value> {
+    Ok(logic::is_truthy(&logic(&data.nested(elem))?))
+}
+
+fn compile_iteration(operator: Operator, arr: Closure, logic: Closure) -> Closure {
+    match operator {
+        Operator::Map => Box::new(move |data| match arr(data)? {
+            Value::Array(arr) => Ok(Value::Array(
+                arr.iter()
+                    .map(|elem| logic(&data.nested(elem)))
+                    .collect::<Result<_, _>>()?,
+            )),
+            _ => Ok(Value::Array(vec![])),
+        }),
+        Operator::Filter => Box::new(move |data| match arr(data)? {
+            Value::Array(arr) => {
+                let mut result = vec![];
+                for elem in arr {
+                    if is_truthy_for(&logic, &elem, data)? {
+                        result.push(elem);
+                    }
+                }
+                Ok(Value::Array(result))
+            }
+            _ => Ok(Value::Array(vec![])),
+        }),
+        Operator::All => Box::new(move |data| {
+            let arr = match arr(data)? {
+                Value::Array(arr) => arr,
+                // Like the operator, `all` also iterates over the characters of a string.
+                Value::String(s) => s.chars().map(|ch| Value::String(ch.to_string())).collect(),
+                _ => return Ok(Value::Bool(false)),
+            };
+            for elem in arr.iter() {
+                if !is_truthy_for(&logic, elem, data)? {
+                    return Ok(Value::Bool(false));
+                }
+            }
+            Ok(Value::Bool(!arr.is_empty()))
+        }),
+        Operator::Some | Operator::None => {
+            let some = operator == Operator::Some;
+            Box::new(move |data| match arr(data)? {
+                Value::Array(arr) => {
+                    for elem in arr.iter() {
+                        if is_truthy_for(&logic, elem, data)? {
+                            return Ok(Value::Bool(some));
+                        }
+                    }
+                    Ok(Value::Bool(!some))
+                }
+                _ => Ok(Value::Bool(!some)),
+            })
+        }
+        Operator::Sort => Box::new(move |data| match arr(data)? {
+            Value::Array(arr) => {
+                let keys = arr
+                    .iter()
+                    .map(|elem| logic(&data.nested(elem)))
+                    .collect::<Result<_, _>>()?;
+                Ok(sort_by_keys(arr, keys))
+            }
+            _ => Ok(Value::Array(vec![])),
+        }),
+        Operator::Find => Box::new(move |data| match arr(data)? {
+            Value::Array(arr) => {
+                for elem in arr {
+                    if is_truthy_for(&logic, &elem, data)? {
+                        return Ok(elem);
+                    }
+                }
+                Ok(Value::Null)
+            }
+            _ => Ok(Value::Null),
+        }),
+        Operator::Count => Box::new(move |data| match arr(data)? {
+            Value::Array(arr) => {
+                let mut count = 0;
+                for elem in arr.iter() {
+                    if is_truthy_for(&logic, elem, data)? {
+                        count += 1;
+                    }
+                }
+                Ok(Value::from(count))
+            }
+            _ => Ok(Value::from(0)),
+        }),
+        _ => Box::new(move |data| match arr(data)? {
+            Value::Array(arr) => {
+                let keys = arr
+                    .iter()
+                    .map(|elem| logic(&data.nested(elem)))
+                    .collect::<Result<_, _>>()?;
+                Ok(group(arr, keys))
+            }
+            _ => Ok(Value::Object(serde_json::Map::new())),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(json_logic: Value, data: Value) -> Value {
+        ClosureRule::compile(&json_logic)
+            .unwrap()
+            .apply(&data)
+            .unwrap()
+    }
+
+    #[test]
+    fn constant_comparisons() {
+        let cases = vec![
+            (json!(5), json!(5)),
+            (json!(5), json!("5")),
+            (json!(5), json!("abc")),
+            (json!(5), json!("NaN")),
+            (json!(4.5), json!(5)),
+            (json!(6), json!("5.5")),
+            (json!("5"), json!("5")),
+            (json!("10"), json!("9")),
+            (json!(null), json!(0)),
+            (json!(true), json!(1)),
+            (json!([5]), json!(5)),
+            (json!(9007199254740992u64), json!(9007199254740993u64)),
+            (json!(9007199254740993u64), json!(9007199254740992u64)),
+        ];
+        for operator in ["==", "!=", "<", "<=", ">", ">="].iter() {
+            for (a, b) in cases.iter() {
+                let expected = crate::apply(&json!({ *operator: [a, b] }), &json!(null));
+                assert_eq!(
+                    apply(json!({ *operator: [{ "var": "a" }, b] }), json!({ "a": a })),
+                    expected.clone().unwrap(),
+                    "{} {} {}",
+                    a,
+                    operator,
+                    b
+                );
+                assert_eq!(
+                    apply(json!({ *operator: [a, { "var": "b" }] }), json!({ "b": b })),
+                    expected.unwrap(),
+                    "{} {} {}",
+                    a,
+                    operator,
+                    b
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn variables() {
+        let data = json!({ "a": { "b": [1, "xyz"] }, "c": "a.b.0" });
+        assert_eq!(apply(json!({ "var": "a.b.1.2" }), data.clone()), json!("z"));
+        assert_eq!(
+            apply(json!({ "var": ["a.x", { "var": "c" }] }), data.clone()),
+            json!("a.b.0")
+        );
+        assert_eq!(
+            apply(json!({ "var": { "var": "c" } }), data.clone()),
+            json!(1)
+        );
+        assert_eq!(apply(json!({ "var": [true, 2] }), data.clone()), json!(2));
+        assert_eq!(apply(json!({ "var": "" }), data.clone()), data);
+    }
+
+    #[test]
+    fn short_circuit() {
+        assert_eq!(
+            apply(json!({ "and": [1, 0, { "var": "" }] }), json!(null)),
+            json!(0)
+        );
+        assert_eq!(
+            apply(json!({ "or": [0, "", "a"] }), json!(null)),
+            json!("a")
+        );
+        assert_eq!(apply(json!({ "or": [] }), json!(null)), json!(null));
+        assert_eq!(
+            apply(json!({ "if": [false, 1, false, 2] }), json!(null)),
+            json!(null)
+        );
+        assert_eq!(apply(json!({ "if": ["x"] }), json!(null)), json!("x"));
+        assert_eq!(
+            apply(
+                json!({ "if": [{ "<": [{ "var": "a" }, 2] }, "small", "large"] }),
+                json!({ "a": "1" })
+            ),
+            json!("small")
+        );
+    }
+}
@@ -0,0 +1,649 @@
+use serde_json::{json, Number, Value};
+
+use crate::operators::Operator;
+
+#[derive(Debug, PartialEq, Clone)]
+enum Token {
+    Identifier(String),
+    /// A keyword like `AND`, in upper case.
+    Keyword(&'static str),
+    Number(Number),
+    String(String),
+    /// An operator or punctuation like `<=` or `(`.
+    Symbol(&'static str),
+    End,
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Token::Identifier(name) => format!("identifier {}", name),
+            Token::Keyword(keyword) => keyword.to_string(),
+            Token::Number(num) => num.to_string(),
+            Token::String(s) => format!("'{}'", s),
+            Token::Symbol(symbol) => format!("`{}`", symbol),
+            Token::End => String::from("end of input"),
+        }
+    }
+}
+
+const KEYWORDS: [&str; 9] = [
+    "AND", "OR", "NOT", "IN", "IS", "NULL", "TRUE", "FALSE", "BETWEEN",
+];
+
+/// Symbols, longer ones first so they take precedence.
+const SYMBOLS: [&str; 18] = [
+    "<=", ">=", "<>", "!=", "==", "||", "=", "<", ">", "+", "-", "*", "/", "%", "(", ")", ",", "!",
+];
+
+/// A token with the line and column of its first character, both starting at 1.
+#[derive(Debug)]
+struct Positioned {
+    token: Token,
+    line: usize,
+    column: usize,
+}
+
+fn error(message: &str, line: usize, column: usize) -> String {
+    format!("{} at line {}, column {}", message, line, column)
+}
+
+fn tokenize(input: &str) -> Result<Vec<Positioned>, String> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = vec![];
+    let mut i = 0;
+    let (mut line, mut line_start) = (1, 0);
+
+    while i < chars.len() {
+        let ch = chars[i];
+        let column = i - line_start + 1;
+
+        if ch == '\n' {
+            i += 1;
+            line += 1;
+            line_start = i;
+            continue;
+        }
+        if ch.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        // Line comment.
+        if ch == '-' && chars.get(i + 1) == Some(&'-') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+            continue;
+        }
+
+        let start = i;
+        let token = if ch.is_ascii_digit()
+            || (ch == '.' && matches!(chars.get(i + 1), Some(ch) if ch.is_ascii_digit()))
+        {
+            while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().collect();
+            let num = if text.contains('.') {
+                text.parse::<f64>().ok().and_then(Number::from_f64)
+            } else {
+                text.parse::<u64>().ok().map(Number::from)
+            };
+            Token::Number(
+                num.ok_or_else(|| error(&format!("invalid number {}", text), line, column))?,
+            )
+        } else if ch.is_alphabetic() || ch == '_' {
+            // Identifiers may be paths like `address.city` or `items.0`.
+            while i < chars.len()
+                && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '.')
+            {
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().collect();
+            let upper = text.to_uppercase();
+            match KEYWORDS.iter().find(|keyword| **keyword == upper) {
+                Some(keyword) => Token::Keyword(keyword),
+                None => Token::Identifier(text),
+            }
+        } else if ch == '\'' || ch == '"' {
+            // Single quotes delimit strings, double quotes identifiers. A doubled quote inside of
+            // them stands for the quote itself.
+            let mut text = String::new();
+            i += 1;
+            loop {
+                match chars.get(i) {
+                    None => {
+                        return Err(error(
+                            if ch == '\'' {
+                                "unterminated string"
+                            } else {
+                                "unterminated quoted identifier"
+                            },
+                            line,
+                            column,
+                        ))
+                    }
+                    Some(&c) if c == ch && chars.get(i + 1) == Some(&ch) => {
+                        text.push(ch);
+                        i += 2;
+                    }
+                    Some(&c) if c == ch => {
+                        i += 1;
+                        break;
+                    }
+                    Some(&c) => {
+                        if c == '\n' {
+                            line += 1;
+                            line_start = i + 1;
+                        }
+                        text.push(c);
+                        i += 1;
+                    }
+                }
+            }
+            if ch == '\'' {
+                Token::String(text)
+            } else {
+                Token::Identifier(text)
+            }
+        } else {
+            let symbol = SYMBOLS.iter().find(|symbol| {
+                symbol
+                    .chars()
+                    .enumerate()
+                    .all(|(offset, c)| chars.get(i + offset) == Some(&c))
+            });
+            match symbol {
+                Some(symbol) => {
+                    i += symbol.len();
+                    Token::Symbol(symbol)
+                }
+                None => return Err(error(&format!("unexpected character {}", ch), line, column)),
+            }
+        };
+
+        // The line of a token is the line it starts on.
+        let token_line = line - chars[start..i].iter().filter(|c| **c == '\n').count();
+        tokens.push(Positioned {
+            token,
+            line: token_line,
+            column,
+        });
+    }
+
+    tokens.push(Positioned {
+        token: Token::End,
+        line,
+        column: chars.len() - line_start + 1,
+    });
+    Ok(tokens)
+}
+
+/// A recursive descent parser over the tokens of a condition.
+struct Parser {
+    tokens: Vec<Positioned>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.position].token
+    }
+
+    fn next(&mut self) -> Token {
+        let token = self.tokens[self.position].token.clone();
+        if token != Token::End {
+            self.position += 1;
+        }
+        token
+    }
+
+    /// Consumes the next token if it is equal to the given one.
+    fn accept(&mut self, token: &Token) -> bool {
+        if self.peek() == token {
+            self.next();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn accept_keyword(&mut self, keyword: &'static str) -> bool {
+        self.accept(&Token::Keyword(keyword))
+    }
+
+    fn accept_symbol(&mut self, symbol: &'static str) -> bool {
+        self.accept(&Token::Symbol(symbol))
+    }
+
+    /// Returns an error about the next token.
+    fn unexpected<T>(&self, expected: &str) -> Result<T, String> {
+        let Positioned {
+            token,
+            line,
+            column,
+        } = &self.tokens[self.position];
+        Err(error(
+            &format!("expected {}, found {}", expected, token.describe()),
+            *line,
+            *column,
+        ))
+    }
+
+    fn expect_symbol(&mut self, symbol: &'static str) -> Result<(), String> {
+        if self.accept_symbol(symbol) {
+            Ok(())
+        } else {
+            self.unexpected(&format!("`{}`", symbol))
+        }
+    }
+
+    fn condition(&mut self) -> Result<Value, String> {
+        let condition = self.or()?;
+        if *self.peek() != Token::End {
+            return self.unexpected("end of input");
+        }
+        Ok(condition)
+    }
+
+    fn or(&mut self) -> Result<Value, String> {
+        let mut operands = vec![self.and()?];
+        while self.accept_keyword("OR") {
+            operands.push(self.and()?);
+        }
+        Ok(variadic("or", operands))
+    }
+
+    fn and(&mut self) -> Result<Value, String> {
+        let mut operands = vec![self.not()?];
+        while self.accept_keyword("AND") {
+            operands.push(self.not()?);
+        }
+        Ok(variadic("and", operands))
+    }
+
+    fn not(&mut self) -> Result<Value, String> {
+        if self.accept_keyword("NOT") || self.accept_symbol("!") {
+            return Ok(json!({ "!": self.not()? }));
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Value, String> {
+        let left = self.additive()?;
+
+        let operator = match self.peek() {
+            Token::Symbol("=") | Token::Symbol("==") => "==",
+            Token::Symbol("!=") | Token::Symbol("<>") => "!=",
+            Token::Symbol("<") => "<",
+            Token::Symbol("<=") => "<=",
+            Token::Symbol(">") => ">",
+            Token::Symbol(">=") => ">=",
+            Token::Keyword("IS") => {
+                self.next();
+                let operator = if self.accept_keyword("NOT") {
+                    "!="
+                } else {
+                    "=="
+                };
+                if !self.accept_keyword("NULL") {
+                    return self.unexpected("NULL");
+                }
+                return Ok(json!({ operator: [left, null] }));
+            }
+            Token::Keyword("NOT") | Token::Keyword("IN") | Token::Keyword("BETWEEN") => {
+                let negated = self.accept_keyword("NOT");
+                let condition = if self.accept_keyword("IN") {
+                    self.is_in(left)?
+                } else if self.accept_keyword("BETWEEN") {
+                    let lower = self.additive()?;
+                    if !self.accept_keyword("AND") {
+                        return self.unexpected("AND");
+                    }
+                    let upper = self.additive()?;
+                    json!({ "<=": [lower, left, upper] })
+                } else {
+                    return self.unexpected("IN or BETWEEN");
+                };
+                return Ok(if negated {
+                    json!({ "!": condition })
+                } else {
+                    condition
+                });
+            }
+            _ => return Ok(left),
+        };
+        self.next();
+
+        let right = self.additive()?;
+        Ok(json!({ operator: [left, right] }))
+    }
+
+    /// Parses the right hand side of `IN`, which is either a list of constants or an expression
+    /// like a variable that holds an array or a string.
+    fn is_in(&mut self, left: Value) -> Result<Value, String> {
+        if !self.accept_symbol("(") {
+            let right = self.primary()?;
+            return Ok(json!({ "in": [left, right] }));
+        }
+
+        let mut list = vec![];
+        if !self.accept_symbol(")") {
+            loop {
+                list.push(self.constant()?);
+                if self.accept_symbol(")") {
+                    break;
+                }
+                self.expect_symbol(",")?;
+            }
+        }
+        Ok(json!({ "in": [left, list] }))
+    }
+
+    fn constant(&mut self) -> Result<Value, String> {
+        let negative = self.accept_symbol("-");
+        match self.peek().clone() {
+            Token::Number(num) => {
+                self.next();
+                Ok(if negative {
+                    negate(&num)
+                } else {
+                    Value::Number(num)
+                })
+            }
+            Token::String(s) if !negative => {
+                self.next();
+                Ok(Value::String(s))
+            }
+            Token::Keyword("TRUE") if !negative => {
+                self.next();
+                Ok(Value::Bool(true))
+            }
+            Token::Keyword("FALSE") if !negative => {
+                self.next();
+                Ok(Value::Bool(false))
+            }
+            Token::Keyword("NULL") if !negative => {
+                self.next();
+                Ok(Value::Null)
+            }
+            _ => self.unexpected("a constant"),
+        }
+    }
+
+    fn additive(&mut self) -> Result<Value, String> {
+        let mut left = self.multiplicative()?;
+        loop {
+            if self.accept_symbol("+") {
+                left = append("+", left, self.multiplicative()?);
+            } else if self.accept_symbol("||") {
+                left = append("cat", left, self.multiplicative()?);
+            } else if self.accept_symbol("-") {
+                left = json!({ "-": [left, self.multiplicative()?] });
+            } else {
+                return Ok(left);
+            }
+        }
+    }
+
+    fn multiplicative(&mut self) -> Result<Value, String> {
+        let mut left = self.unary()?;
+        loop {
+            if self.accept_symbol("*") {
+                left = append("*", left, self.unary()?);
+            } else if self.accept_symbol("/") {
+                left = json!({ "/": [left, self.unary()?] });
+            } else if self.accept_symbol("%") {
+                left = json!({ "%": [left, self.unary()?] });
+            } else {
+                return Ok(left);
+            }
+        }
+    }
+
+    fn unary(&mut self) -> Result<Value, String> {
+        if self.accept_symbol("-") {
+            return Ok(match self.unary()? {
+                Value::Number(num) => negate(&num),
+                operand => json!({ "-": operand }),
+            });
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Value, String> {
+        match self.peek().clone() {
+            Token::Identifier(name) => {
+                self.next();
+                if *self.peek() == Token::Symbol("(") {
+                    return self.call(&name);
+                }
+                Ok(json!({ "var": name }))
+            }
+            Token::Symbol("(") => {
+                self.next();
+                let expr = self.or()?;
+                self.expect_symbol(")")?;
+                Ok(expr)
+            }
+            Token::Symbol("-") => self.unary(),
+            _ => self.constant(),
+        }
+    }
+
+    /// Parses a function call, which is translated to the JsonLogic operation of the same name.
+    fn call(&mut self, name: &str) -> Result<Value, String> {
+        let Positioned { line, column, .. } = self.tokens[self.position - 1];
+        if Operator::from_str(name).is_none() {
+            return Err(error(&format!("unknown function {}", name), line, column));
+        }
+
+        self.expect_symbol("(")?;
+        let mut args = vec![];
+        if !self.accept_symbol(")") {
+            loop {
+                args.push(self.or()?);
+                if self.accept_symbol(")") {
+                    break;
+                }
+                self.expect_symbol(",")?;
+            }
+        }
+        Ok(json!({ name: args }))
+    }
+}
+
+/// Returns the operation with the given operands, or the operand itself if there is only one.
+fn variadic(operator: &str, mut operands: Vec<Value>) -> Value {
+    if operands.len() == 1 {
+        operands.pop().unwrap()
+    } else {
+        json!({ operator: operands })
+    }
+}
+
+/// Adds an operand to an associative operation, so `a + b + c` becomes a single `+` operation.
+fn append(operator: &str, left: Value, right: Value) -> Value {
+    match left {
+        Value::Object(mut object) if object.len() == 1 && object.contains_key(operator) => {
+            if let Some(Value::Array(operands)) = object.get_mut(operator) {
+                operands.push(right);
+            }
+            Value::Object(object)
+        }
+        left => json!({ operator: [left, right] }),
+    }
+}
+
+fn negate(num: &Number) -> Value {
+    if let Some(num) = num.as_i64() {
+        json!(-num)
+    } else {
+        num.as_f64().map_or(Value::Null, |num| json!(-num))
+    }
+}
+
+/// Parses a condition in a SQL `WHERE`-like syntax into a JsonLogic rule.
+///
+/// The syntax supports
+///
+/// - variables, which may be paths like `address.city` or `items.0`, and which may be quoted with
+///   double quotes (`"first name"`),
+/// - numbers, strings in single quotes, `TRUE`, `FALSE` and `NULL`,
+/// - `=` (or `==`) and `!=` (or `<>`), which are translated to `==` and `!=`, and `<`, `<=`, `>`,
+///   `>=`,
+/// - `x IN (1, 2)` with a list of constants, `'a' IN tags` with an expression,
+///   `x BETWEEN 1 AND 5`, `x IS NULL`, and their negations `NOT IN`, `NOT BETWEEN` and
+///   `IS NOT NULL`,
+/// - `AND`, `OR` and `NOT` (or `!`), where `NOT` binds stronger than `AND`, which binds stronger
+///   than `OR`,
+/// - the arithmetic operators `+`, `-`, `*`, `/`, `%` and `||` for string concatenation,
+/// - calls of JsonLogic operations like `max(a, b)` or `substr(name, 0, 3)`,
+/// - and line comments starting with `--`.
+///
+/// Keywords are case-insensitive. Errors contain the line and column of the offending token.
+///
+/// # Example
+///
+/// ```
+/// use serde_json::json;
+///
+/// assert_eq!(
+///     jsonlogic::parse_condition("age >= 18 AND country IN ('US', 'CA')"),
+///     Ok(json!({ "and": [
+///         { ">=": [{ "var": "age" }, 18] },
+///         { "in": [{ "var": "country" }, ["US", "CA"]] }
+///     ]}))
+/// );
+/// assert_eq!(
+///     jsonlogic::parse_condition("age >= 18 AND\ncountry IN ('US' 'CA')"),
+///     Err(String::from("expected `,`, found 'CA' at line 2, column 18"))
+/// );
+/// ```
+pub fn parse_condition(input: &str) -> Result<Value, String> {
+    let mut parser = Parser {
+        tokens: tokenize(input)?,
+        position: 0,
+    };
+    parser.condition()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn precedence() {
+        assert_eq!(
+            parse_condition("a OR b AND NOT c OR d"),
+            Ok(json!({ "or": [
+                { "var": "a" },
+                { "and": [{ "var": "b" }, { "!": { "var": "c" } }] },
+                { "var": "d" }
+            ]}))
+        );
+        assert_eq!(
+            parse_condition("(a OR b) AND c"),
+            Ok(json!({ "and": [{ "or": [{ "var": "a" }, { "var": "b" }] }, { "var": "c" }] }))
+        );
+        assert_eq!(
+            parse_condition("a + b * c - d - -2 = 1 + 2 + 3"),
+            Ok(json!({ "==": [
+                { "-": [
+                    { "-": [{ "+": [{ "var": "a" }, { "*": [{ "var": "b" }, { "var": "c" }] }] }, { "var": "d" }] },
+                    -2
+                ]},
+                { "+": [1, 2, 3] }
+            ]}))
+        );
+        assert_eq!(
+            parse_condition("first || ' ' || last <> 'a b'"),
+            Ok(json!({ "!=": [
+                { "cat": [{ "var": "first" }, " ", { "var": "last" }] },
+                "a b"
+            ]}))
+        );
+    }
+
+    #[test]
+    fn special_forms() {
+        assert_eq!(
+            parse_condition("x not between 1 and 2.5"),
+            Ok(json!({ "!": { "<=": [1, { "var": "x" }, 2.5] } }))
+        );
+        assert_eq!(
+            parse_condition("x IS NOT NULL AND y is null"),
+            Ok(json!({ "and": [
+                { "!=": [{ "var": "x" }, null] },
+                { "==": [{ "var": "y" }, null] }
+            ]}))
+        );
+        assert_eq!(
+            parse_condition("x NOT IN (1, -2, 'a', TRUE, NULL) OR 'beta' IN tags OR x IN ()"),
+            Ok(json!({ "or": [
+                { "!": { "in": [{ "var": "x" }, [1, -2, "a", true, null]] } },
+                { "in": ["beta", { "var": "tags" }] },
+                { "in": [{ "var": "x" }, []] }
+            ]}))
+        );
+        assert_eq!(
+            parse_condition("max(a, 2) > \"b c\" -- comment\n AND missing()"),
+            Ok(json!({ "and": [
+                { ">": [{ "max": [{ "var": "a" }, 2] }, { "var": "b c" }] },
+                { "missing": [] }
+            ]}))
+        );
+        assert_eq!(
+            parse_condition("name = 'it''s'"),
+            Ok(json!({ "==": [{ "var": "name" }, "it's"] }))
+        );
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            parse_condition("a = "),
+            Err(String::from(
+                "expected a constant, found end of input at line 1, column 5"
+            ))
+        );
+        assert_eq!(
+            parse_condition("a = 1\n  AND (b > 2"),
+            Err(String::from(
+                "expected `)`, found end of input at line 2, column 13"
+            ))
+        );
+        assert_eq!(
+            parse_condition("a = 1 b"),
+            Err(String::from(
+                "expected end of input, found identifier b at line 1, column 7"
+            ))
+        );
+        assert_eq!(
+            parse_condition("a IN (1, b)"),
+            Err(String::from(
+                "expected a constant, found identifier b at line 1, column 10"
+            ))
+        );
+        assert_eq!(
+            parse_condition("a IS 1"),
+            Err(String::from("expected NULL, found 1 at line 1, column 6"))
+        );
+        assert_eq!(
+            parse_condition("\n\n  a = 'abc"),
+            Err(String::from("unterminated string at line 3, column 7"))
+        );
+        assert_eq!(
+            parse_condition("a ; b"),
+            Err(String::from("unexpected character ; at line 1, column 3"))
+        );
+        assert_eq!(
+            parse_condition("a = 1.2.3"),
+            Err(String::from("invalid number 1.2.3 at line 1, column 5"))
+        );
+        assert_eq!(
+            parse_condition("x AND foo(1)"),
+            Err(String::from("unknown function foo at line 1, column 7"))
+        );
+    }
+}
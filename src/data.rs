@@ -1,24 +1,235 @@
 use serde_json::{Number, Value};
+use std::cell::RefCell;
+#[cfg(feature = "regex")]
+use std::collections::HashMap;
 use std::convert::TryFrom;
+#[cfg(feature = "datetime")]
+use std::time::SystemTime;
+
+#[cfg(feature = "regex")]
+use crate::operators::Pattern;
+#[cfg(feature = "datetime")]
+use crate::Clock;
+use crate::StringMode;
 
 /// Contains a JSON value that is passed as data for the evaluation JsonLogic expression.
-pub struct Data<'a>(&'a Value);
+pub struct Data<'a> {
+    value: &'a Value,
+    /// If set, the paths of all values that are read from this struct are appended to it.
+    reads: Option<&'a RefCell<Vec<String>>>,
+    /// The clock of the `now` operator. The system clock is used if not set.
+    #[cfg(feature = "datetime")]
+    clock: Option<&'a dyn Clock>,
+    /// How strings are measured and indexed.
+    string_mode: StringMode,
+    /// The innermost name bound by the `let` operator, see `bind`.
+    bindings: Option<&'a Binding<'a>>,
+    /// The patterns that were compiled during this evaluation, see `pattern`.
+    #[cfg(feature = "regex")]
+    patterns: Patterns<'a>,
+}
+
+/// The most patterns that are kept compiled during an evaluation. Every pattern may take up to
+/// twice `SIZE_LIMIT` of memory, so the cache is emptied once it is full.
+#[cfg(feature = "regex")]
+const CACHED_PATTERNS: usize = 16;
+
+/// The patterns of `match` and `captures` that are computed from the data, by their source, or
+/// `None` if the source is invalid.
+#[cfg(feature = "regex")]
+type PatternCache = HashMap<String, Option<Pattern>>;
+
+/// The cached patterns, which are owned by the data an evaluation starts with and shared with the
+/// data derived from it, like the nested data of `map`.
+#[cfg(feature = "regex")]
+enum Patterns<'a> {
+    Owned(RefCell<PatternCache>),
+    Shared(&'a RefCell<PatternCache>),
+}
+
+#[cfg(feature = "regex")]
+impl<'a> Patterns<'a> {
+    fn new() -> Patterns<'a> {
+        Patterns::Owned(RefCell::new(HashMap::new()))
+    }
+
+    fn share(&self) -> Patterns<'_> {
+        match self {
+            Patterns::Owned(patterns) => Patterns::Shared(patterns),
+            Patterns::Shared(patterns) => Patterns::Shared(patterns),
+        }
+    }
+
+    fn cell(&self) -> &RefCell<PatternCache> {
+        match self {
+            Patterns::Owned(patterns) => patterns,
+            Patterns::Shared(patterns) => patterns,
+        }
+    }
+}
+
+/// A name bound by the `let` operator, which links to the names bound before it.
+struct Binding<'a> {
+    name: String,
+    value: Value,
+    parent: Option<&'a Binding<'a>>,
+}
 
 impl<'a> Data<'a> {
     /// Creates a new struct from the given json value.
     pub fn from_json(data: &Value) -> Data {
-        Data(data)
+        Data {
+            value: data,
+            reads: None,
+            #[cfg(feature = "datetime")]
+            clock: None,
+            string_mode: StringMode::default(),
+            bindings: None,
+            #[cfg(feature = "regex")]
+            patterns: Patterns::new(),
+        }
+    }
+
+    /// Creates a new struct from the given json value that appends the path of every value read
+    /// from it to `reads`. Accessing the plain data is recorded as the empty path.
+    pub fn recording(data: &'a Value, reads: &'a RefCell<Vec<String>>) -> Data<'a> {
+        Data {
+            value: data,
+            reads: Some(reads),
+            #[cfg(feature = "datetime")]
+            clock: None,
+            string_mode: StringMode::default(),
+            bindings: None,
+            #[cfg(feature = "regex")]
+            patterns: Patterns::new(),
+        }
+    }
+
+    /// Sets the clock that the `now` operator reads the current time from.
+    #[cfg(feature = "datetime")]
+    pub fn with_clock(self, clock: &'a dyn Clock) -> Data<'a> {
+        Data {
+            clock: Some(clock),
+            ..self
+        }
+    }
+
+    /// Sets how strings are measured and indexed.
+    pub fn with_string_mode(self, string_mode: StringMode) -> Data<'a> {
+        Data {
+            string_mode,
+            ..self
+        }
+    }
+
+    /// Returns how strings are measured and indexed.
+    pub fn string_mode(&self) -> StringMode {
+        self.string_mode
+    }
+
+    /// Creates a new struct for evaluating an expression on a nested value, like the elements of
+    /// the array in `map`. Reads are not recorded, since their paths are relative to the nested
+    /// value.
+    pub fn nested<'b>(&'b self, value: &'b Value) -> Data<'b>
+    where
+        'a: 'b,
+    {
+        Data {
+            value,
+            reads: None,
+            #[cfg(feature = "datetime")]
+            clock: self.clock,
+            string_mode: self.string_mode,
+            bindings: self.bindings,
+            #[cfg(feature = "regex")]
+            patterns: self.patterns.share(),
+        }
+    }
+
+    /// Evaluates `compute` with data in which `name` is bound to the value, in addition to the
+    /// names that are already bound. The name shadows an equal name that is already bound.
+    pub fn bind<T>(&self, name: String, value: Value, compute: impl FnOnce(&Data) -> T) -> T {
+        let binding = Binding {
+            name,
+            value,
+            parent: self.bindings,
+        };
+        compute(&Data {
+            value: self.value,
+            reads: self.reads,
+            #[cfg(feature = "datetime")]
+            clock: self.clock,
+            string_mode: self.string_mode,
+            bindings: Some(&binding),
+            #[cfg(feature = "regex")]
+            patterns: self.patterns.share(),
+        })
+    }
+
+    /// Gets the value bound to a name by the `let` operator. Like the paths of `get_value`, the
+    /// name may be followed by the steps of a path into the bound value, like `"user.name"`.
+    pub fn get_binding(&self, path: &str) -> Option<Value> {
+        let (name, steps) = match path.find('.') {
+            Some(index) => (&path[..index], Some(&path[index + 1..])),
+            None => (path, None),
+        };
+        let mut binding = self.bindings;
+        while let Some(Binding {
+            name: bound_name,
+            value,
+            parent,
+        }) = binding
+        {
+            if bound_name == name {
+                return match steps {
+                    Some(steps) => self.nested(value).get_value(&Value::from(steps)),
+                    None => Some(value.clone()),
+                };
+            }
+            binding = *parent;
+        }
+        None
+    }
+
+    /// Compiles a pattern of `match` or `captures` that is computed from the data. Up to
+    /// `CACHED_PATTERNS` sources are compiled only once per evaluation, even if the pattern is used
+    /// for every element of an array. Returns `None` if the pattern is invalid.
+    #[cfg(feature = "regex")]
+    pub fn pattern(&self, source: &str) -> Option<Pattern> {
+        let mut patterns = self.patterns.cell().borrow_mut();
+        if let Some(pattern) = patterns.get(source) {
+            return pattern.clone();
+        }
+        if patterns.len() == CACHED_PATTERNS {
+            patterns.clear();
+        }
+        let pattern = Pattern::new(source).ok();
+        patterns.insert(source.to_owned(), pattern.clone());
+        pattern
+    }
+
+    /// Returns the number of patterns compiled during this evaluation.
+    #[cfg(all(test, feature = "regex"))]
+    pub fn compiled_patterns(&self) -> usize {
+        self.patterns.cell().borrow().len()
+    }
+
+    /// Returns the current time of the clock.
+    #[cfg(feature = "datetime")]
+    pub fn now(&self) -> SystemTime {
+        self.clock.map_or_else(SystemTime::now, |clock| clock.now())
     }
 
     /// Creates an empty struct, encapsulating a null value.
     #[allow(dead_code)]
     pub fn empty() -> Data<'static> {
-        Data(&Value::Null)
+        Data::from_json(&Value::Null)
     }
 
     /// Gets the plain json data that is encapsulated by this struct.
     pub fn get_plain(&self) -> &Value {
-        self.0
+        self.record("");
+        self.value
     }
 
     /// Tries to get part of the encapsulate data by the given path.
@@ -30,24 +241,56 @@ impl<'a> Data<'a> {
     /// TODO: Would it be possible to clone only if necessary?
     pub fn get_value(&self, path: &Value) -> Option<Value> {
         match path {
-            Value::String(path) => self.by_string(path),
-            Value::Number(number) => self.by_number(number),
+            Value::String(path) => {
+                self.record(path);
+                self.by_steps(
+                    path.split('.')
+                        .map(|step| (step, step.parse::<usize>().ok())),
+                )
+            }
+            Value::Number(number) => {
+                if self.reads.is_some() {
+                    self.record(&number.to_string());
+                }
+                self.by_number(number)
+            }
             _ => None,
         }
     }
 
-    /// Trys to get a value from the given data by the path. This can be a simple key or a
-    /// stringified index for strings and arrays but complex dot-notation access paths are also
-    /// supported.
-    fn by_string(&self, path: &str) -> Option<Value> {
-        let mut data_part = self.0;
+    /// Like `get_value` with a string path, but with a path that has already been parsed, so
+    /// rules that are compiled once do not parse the path on every access.
+    pub fn get_value_by_path(&self, path: &Path) -> Option<Value> {
+        self.record(&path.path);
+        self.by_steps(path.steps.iter().map(|(key, index)| (key.as_str(), *index)))
+    }
+
+    /// Like `get_value`, but uses the already parsed path if there is one.
+    pub fn get_value_or_by_path(&self, path: &Value, parsed: Option<&Path>) -> Option<Value> {
+        match parsed {
+            Some(parsed) => self.get_value_by_path(parsed),
+            None => self.get_value(path),
+        }
+    }
+
+    fn record(&self, path: &str) {
+        if let Some(reads) = self.reads {
+            reads.borrow_mut().push(path.to_owned());
+        }
+    }
+
+    /// Trys to get a value from the given data by the steps of a path. A step can be a simple key
+    /// or a stringified index for strings and arrays, which allows complex dot-notation access
+    /// paths. Every step is given together with its parsed index, if it is one.
+    fn by_steps<'s>(&self, steps: impl Iterator<Item = (&'s str, Option<usize>)>) -> Option<Value> {
+        let mut data_part = self.value;
 
         // While we can traverse through arrays and objects, we can't for a characters. Character
         // access in a string must therefore be the last step in the given path. To handle that
         // properly, we save an accessed char in this option.
-        let mut prev_step_char: Option<char> = None;
+        let mut prev_step_char: Option<String> = None;
 
-        for step in path.split('.') {
+        for (step, index) in steps {
             // In the previous step an character from a string was accessed, which must be the last
             // step since a character is considered a primitive here.
             if prev_step_char.is_some() {
@@ -59,17 +302,13 @@ impl<'a> Data<'a> {
             let option = match data_part {
                 // If the current data_part is an array, try to interpret the current step as an
                 // index.
-                Value::Array(arr) => step.parse::<usize>().ok().and_then(|index| arr.get(index)),
+                Value::Array(arr) => index.and_then(|index| arr.get(index)),
                 // If the current data_part is an object, interpret current step as a key.
                 Value::Object(obj) => obj.get(step),
                 // If the current data_part is a string, interpret current step as index of a
                 // character. This must be the last step.
                 Value::String(s) => {
-                    if let Some(ch) = step
-                        .parse::<usize>()
-                        .ok()
-                        .and_then(|index| s.chars().nth(index))
-                    {
+                    if let Some(ch) = index.and_then(|index| self.string_mode.char_at(s, index)) {
                         prev_step_char = Some(ch);
                         Some(data_part)
                     } else {
@@ -92,7 +331,7 @@ impl<'a> Data<'a> {
 
         // If in the last step a character from a string was accessed, return it.
         if let Some(ch) = prev_step_char {
-            return Some(Value::String(ch.to_string()));
+            return Some(Value::String(ch));
         }
 
         // TODO: Could we avoid cloning?
@@ -102,7 +341,7 @@ impl<'a> Data<'a> {
     /// Extracts a value from the given data by index. Data can either be an array, a string or an
     /// object containing the stringified index as a key. Otherwise returns `None`.
     fn by_number(&self, num: &Number) -> Option<Value> {
-        match self.0 {
+        match self.value {
             Value::Array(arr) => num
                 .as_u64()
                 .and_then(|index| usize::try_from(index).ok())
@@ -111,11 +350,31 @@ impl<'a> Data<'a> {
             Value::String(s) => num
                 .as_u64()
                 .and_then(|index| usize::try_from(index).ok())
-                .and_then(|index| s.chars().nth(index))
-                .map(|ch| Value::String(ch.to_string())),
+                .and_then(|index| self.string_mode.char_at(s, index))
+                .map(Value::String),
             _ => None,
         }
     }
 }
 
+/// A string path to a value in the data, like the argument of `var`, that has been split into its
+/// steps, with the steps that are indices already parsed.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Path {
+    path: String,
+    steps: Vec<(String, Option<usize>)>,
+}
+
+impl Path {
+    pub fn parse(path: &str) -> Path {
+        Path {
+            path: path.to_owned(),
+            steps: path
+                .split('.')
+                .map(|step| (step.to_owned(), step.parse::<usize>().ok()))
+                .collect(),
+        }
+    }
+}
+
 // TODO: Move tests from variable operator to this file.
@@ -0,0 +1,327 @@
+use serde_json::Value;
+use std::str::FromStr;
+
+use crate::operators::logic;
+use crate::Rule;
+
+/// Decides which rows of a `DecisionTable` contribute to its result, following the hit policies
+/// of DMN decision tables.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum HitPolicy {
+    /// The output of the first row whose condition matches is returned.
+    First,
+    /// At most one row may match. If more than one row matches, the evaluation errors.
+    Unique,
+    /// The outputs of all matching rows are returned as an array, in the order of the rows.
+    Collect,
+}
+
+impl FromStr for HitPolicy {
+    type Err = String;
+
+    /// Returns the hit policy matching the given string representation.
+    fn from_str(s: &str) -> Result<HitPolicy, String> {
+        match s {
+            "first" => Ok(HitPolicy::First),
+            "unique" => Ok(HitPolicy::Unique),
+            "collect" => Ok(HitPolicy::Collect),
+            _ => Err(format!("Unrecognized hit policy {}", s)),
+        }
+    }
+}
+
+/// A single row of a decision table.
+#[derive(Debug, PartialEq)]
+struct Row<'a> {
+    condition: Rule<'a>,
+    output: Rule<'a>,
+}
+
+/// An ordered list of rows, each consisting of a JsonLogic condition and a JsonLogic output
+/// expression. Which rows contribute to the result is decided by the table's `HitPolicy`.
+///
+/// # Example
+///
+/// ```
+/// use serde_json::json;
+/// use jsonlogic::DecisionTable;
+///
+/// let table_json = json!({
+///     "hit_policy": "first",
+///     "rows": [
+///         { "condition": { ">=": [{ "var": "score" }, 700] }, "output": "gold" },
+///         { "condition": { ">=": [{ "var": "score" }, 500] }, "output": "silver" },
+///         { "condition": true, "output": "bronze" }
+///     ]
+/// });
+/// let table = DecisionTable::from_json(&table_json).unwrap();
+///
+/// assert_eq!(table.evaluate(&json!({ "score": 720 })), Ok(json!("gold")));
+/// assert_eq!(table.evaluate(&json!({ "score": 600 })), Ok(json!("silver")));
+/// assert_eq!(table.evaluate(&json!({ "score": 100 })), Ok(json!("bronze")));
+/// ```
+#[derive(Debug, PartialEq)]
+pub struct DecisionTable<'a> {
+    hit_policy: HitPolicy,
+    rows: Vec<Row<'a>>,
+}
+
+impl<'a> DecisionTable<'a> {
+    /// Creates a new table without any rows.
+    pub fn new(hit_policy: HitPolicy) -> DecisionTable<'a> {
+        DecisionTable {
+            hit_policy,
+            rows: vec![],
+        }
+    }
+
+    /// Loads a decision table from a JSON document of the form
+    ///
+    /// ```ignore
+    /// {
+    ///     "hit_policy": "first" | "unique" | "collect", // optional, defaults to "first"
+    ///     "rows": [
+    ///         { "condition": <JsonLogic>, "output": <JsonLogic> },
+    ///         ...
+    ///     ]
+    /// }
+    /// ```
+    pub fn from_json(json: &'a Value) -> Result<DecisionTable<'a>, String> {
+        let object = json.as_object().ok_or("decision table must be an object")?;
+
+        let hit_policy = match object.get("hit_policy") {
+            None => HitPolicy::First,
+            Some(Value::String(s)) => s.parse()?,
+            Some(_) => return Err(String::from("hit_policy must be a string")),
+        };
+
+        let rows = object
+            .get("rows")
+            .and_then(|rows| rows.as_array())
+            .ok_or("decision table must have a rows array")?;
+
+        let mut table = DecisionTable::new(hit_policy);
+        for (index, row) in rows.iter().enumerate() {
+            let condition = row
+                .get("condition")
+                .ok_or_else(|| format!("row {} has no condition", index))?;
+            let output = row
+                .get("output")
+                .ok_or_else(|| format!("row {} has no output", index))?;
+            table
+                .add_row(condition, output)
+                .map_err(|err| format!("row {}: {}", index, err))?;
+        }
+
+        Ok(table)
+    }
+
+    /// Appends a row to the table. Errors if the condition or the output is not a valid
+    /// JsonLogic rule.
+    pub fn add_row(&mut self, condition: &'a Value, output: &'a Value) -> Result<(), String> {
+        self.rows.push(Row {
+            condition: Rule::compile(condition)?,
+            output: Rule::compile(output)?,
+        });
+        Ok(())
+    }
+
+    /// Returns how the rows that match are combined into the result.
+    pub fn hit_policy(&self) -> HitPolicy {
+        self.hit_policy
+    }
+
+    /// Returns the number of rows.
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Returns whether the table has no rows.
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Returns the indices of all rows whose condition is truthy for the given data, regardless
+    /// of the hit policy. Conditions that throw an error do not match.
+    pub fn matching_rows(&self, data: &Value) -> Vec<usize> {
+        self.rows
+            .iter()
+            .enumerate()
+            .filter(|(_, row)| row.matches(data))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Evaluates the table against the given data according to its hit policy.
+    ///
+    /// - `First` returns the output of the first matching row, or `Value::Null` if no row
+    ///   matches.
+    /// - `Unique` returns the output of the only matching row, or `Value::Null` if no row
+    ///   matches. Errors with the indices of the overlapping rows if more than one row matches.
+    /// - `Collect` returns an array with the outputs of all matching rows.
+    ///
+    /// Errors with the index of the row if the output of a row throws an error that is not
+    /// caught.
+    pub fn evaluate(&self, data: &Value) -> Result<Value, String> {
+        match self.hit_policy {
+            HitPolicy::First => match self.rows.iter().position(|row| row.matches(data)) {
+                Some(index) => self.output(index, data),
+                None => Ok(Value::Null),
+            },
+            HitPolicy::Unique => match &self.matching_rows(data)[..] {
+                [] => Ok(Value::Null),
+                [index] => self.output(*index, data),
+                indices => Err(format!(
+                    "rows {} overlap, but the hit policy is unique",
+                    indices
+                        .iter()
+                        .map(|index| index.to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )),
+            },
+            HitPolicy::Collect => Ok(Value::Array(
+                self.matching_rows(data)
+                    .into_iter()
+                    .map(|index| self.output(index, data))
+                    .collect::<Result<_, _>>()?,
+            )),
+        }
+    }
+
+    fn output(&self, index: usize, data: &Value) -> Result<Value, String> {
+        self.rows[index]
+            .output
+            .apply(data)
+            .map_err(|error| format!("row {} threw the uncaught error {}", index, error))
+    }
+}
+
+impl<'a> Row<'a> {
+    fn matches(&self, data: &Value) -> bool {
+        match self.condition.apply(data) {
+            Ok(value) => logic::is_truthy(&value),
+            Err(_) => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn table_json(hit_policy: &str) -> Value {
+        json!({
+            "hit_policy": hit_policy,
+            "rows": [
+                { "condition": { "<": [{ "var": "age" }, 18] }, "output": "minor" },
+                { "condition": { ">=": [{ "var": "age" }, 65] }, "output": "senior" },
+                { "condition": { ">=": [{ "var": "age" }, 18] }, "output": { "cat": ["adult ", { "var": "age" }] } }
+            ]
+        })
+    }
+
+    #[test]
+    fn first() {
+        let json = table_json("first");
+        let table = DecisionTable::from_json(&json).unwrap();
+
+        assert_eq!(table.hit_policy(), HitPolicy::First);
+        assert_eq!(table.len(), 3);
+        assert_eq!(table.evaluate(&json!({ "age": 10 })), Ok(json!("minor")));
+        assert_eq!(table.evaluate(&json!({ "age": 70 })), Ok(json!("senior")));
+        assert_eq!(table.evaluate(&json!({ "age": 30 })), Ok(json!("adult 30")));
+    }
+
+    #[test]
+    fn unique() {
+        let json = table_json("unique");
+        let table = DecisionTable::from_json(&json).unwrap();
+
+        assert_eq!(table.evaluate(&json!({ "age": 10 })), Ok(json!("minor")));
+        assert_eq!(table.evaluate(&json!({ "age": 30 })), Ok(json!("adult 30")));
+        assert_eq!(
+            table.evaluate(&json!({ "age": 70 })),
+            Err(String::from(
+                "rows 1, 2 overlap, but the hit policy is unique"
+            ))
+        );
+    }
+
+    #[test]
+    fn collect() {
+        let json = table_json("collect");
+        let table = DecisionTable::from_json(&json).unwrap();
+
+        assert_eq!(table.evaluate(&json!({ "age": 10 })), Ok(json!(["minor"])));
+        assert_eq!(
+            table.evaluate(&json!({ "age": 70 })),
+            Ok(json!(["senior", "adult 70"]))
+        );
+        assert_eq!(table.matching_rows(&json!({ "age": 70 })), vec![1, 2]);
+    }
+
+    #[test]
+    fn add_row() {
+        let conditions = [json!({ "var": "a" }), json!({ "var": "b" })];
+        let outputs = [json!(1), json!(2)];
+        let mut table = DecisionTable::new(HitPolicy::First);
+        assert!(table.is_empty());
+
+        table.add_row(&conditions[0], &outputs[0]).unwrap();
+        table.add_row(&conditions[1], &outputs[1]).unwrap();
+        assert_eq!(
+            table.evaluate(&json!({ "a": true, "b": true })),
+            Ok(json!(1))
+        );
+        assert_eq!(
+            table.evaluate(&json!({ "a": false, "b": true })),
+            Ok(json!(2))
+        );
+        assert_eq!(table.evaluate(&json!({})), Ok(Value::Null));
+    }
+
+    #[test]
+    fn thrown_errors() {
+        let json = json!({ "hit_policy": "collect", "rows": [
+            { "condition": { "throw": "condition" }, "output": 1 },
+            { "condition": { "var": "a" }, "output": { "throw": "output" } },
+            { "condition": true, "output": 3 }
+        ]});
+        let table = DecisionTable::from_json(&json).unwrap();
+
+        assert_eq!(table.matching_rows(&json!({})), vec![2]);
+        assert_eq!(table.evaluate(&json!({})), Ok(json!([3])));
+        assert_eq!(
+            table.evaluate(&json!({ "a": true })),
+            Err(String::from(r#"row 1 threw the uncaught error "output""#))
+        );
+    }
+
+    #[test]
+    fn from_json_errors() {
+        assert_eq!(
+            DecisionTable::from_json(&json!([])),
+            Err(String::from("decision table must be an object"))
+        );
+        assert_eq!(
+            DecisionTable::from_json(&json!({ "hit_policy": "any", "rows": [] })),
+            Err(String::from("Unrecognized hit policy any"))
+        );
+        assert_eq!(
+            DecisionTable::from_json(&json!({ "hit_policy": "first" })),
+            Err(String::from("decision table must have a rows array"))
+        );
+        assert_eq!(
+            DecisionTable::from_json(&json!({ "rows": [{ "output": 1 }] })),
+            Err(String::from("row 0 has no condition"))
+        );
+        assert_eq!(
+            DecisionTable::from_json(
+                &json!({ "rows": [{ "condition": { "foo": 1 }, "output": 1 }] })
+            ),
+            Err(String::from("row 0: Unrecognized operation foo"))
+        );
+    }
+}
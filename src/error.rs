@@ -0,0 +1,22 @@
+use serde_json::Value;
+use std::fmt;
+
+/// An error of `apply`.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Error {
+    /// The rule cannot be parsed, e.g. because it contains an unrecognized operation.
+    Compile(String),
+    /// The rule threw the value with the `throw` operator and it was not caught by `try`.
+    Thrown(Value),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Compile(message) => write!(f, "{}", message),
+            Error::Thrown(value) => write!(f, "Uncaught error {}", value),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
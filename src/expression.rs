@@ -1,18 +1,68 @@
+use crate::data::Path;
 use crate::operators::Operator;
+#[cfg(feature = "regex")]
+use crate::operators::Pattern;
 use crate::Data;
 use serde_json::Value;
 use std::collections::HashSet;
+use std::sync::Arc;
 
-#[derive(Debug, PartialEq)]
+/// Parses an operation that is not a JsonLogic operator, like a `{"rule": "<name>"}` reference,
+/// given the operation key and its value. Returns `None` if the operation is unknown.
+pub type ParseExtension<'r, 'a> =
+    dyn FnMut(&str, &'a Value) -> Option<Result<Expression<'a>, String>> + 'r;
+
+#[derive(Debug, PartialEq, Clone)]
 pub enum Expression<'a> {
     Constant(&'a Value),
     Computed(Operator, Vec<Expression<'a>>),
+    /// A reference to a named rule, see `RuleRegistry`. The expression of the named rule is shared
+    /// by all references to it.
+    Reference(String, Arc<Expression<'a>>),
+    /// A constant string, or array of strings, that are paths to values in the data, like the
+    /// argument of `var` or the keys of `missing`. Evaluates to the constant, but the paths are
+    /// parsed only once when parsing the rule.
+    Paths(&'a Value, Vec<Path>),
+    /// A constant regular expression, like the pattern of `match`. Evaluates to the constant, but
+    /// the pattern is compiled only once when parsing the rule.
+    #[cfg(feature = "regex")]
+    Pattern(&'a Value, Pattern),
 }
 
 impl<'a> Expression<'a> {
     pub fn from_json(json: &Value) -> Result<Expression, String> {
+        Expression::parse(json, &mut None, "")
+    }
+
+    /// Like `from_json`, but operations that are no JsonLogic operators are passed to `extension`
+    /// instead of being rejected right away.
+    pub fn from_json_with_extension(
+        json: &'a Value,
+        extension: &mut ParseExtension<'_, 'a>,
+    ) -> Result<Expression<'a>, String> {
+        Expression::parse(json, &mut Some(extension), "")
+    }
+
+    /// Returns the name of an operation that refers to something by name, like
+    /// `{"rule": "<name>"}` or `{"rule": ["<name>"]}`.
+    pub fn reference_name<'v>(operation: &str, value: &'v Value) -> Result<&'v str, String> {
+        match value {
+            Value::String(name) => Some(name.as_str()),
+            Value::Array(arr) if arr.len() == 1 => arr[0].as_str(),
+            _ => None,
+        }
+        .ok_or_else(|| format!("{} reference must be a constant string", operation))
+    }
+
+    /// Parses the expression at the given position in the rule, a JSON pointer like
+    /// `/and/0/match/1`, which locates errors in the rule.
+    fn parse(
+        json: &'a Value,
+        extension: &mut Option<&mut ParseExtension<'_, 'a>>,
+        pointer: &str,
+    ) -> Result<Expression<'a>, String> {
         if !json.is_object() {
-            return Ok(Expression::Constant(&json));
+            return Ok(Expression::Constant(json));
         }
 
         let object = json.as_object().unwrap();
@@ -23,29 +73,149 @@ impl<'a> Expression<'a> {
         }
 
         let entry: Vec<(&String, &serde_json::Value)> = object.iter().collect();
-        let &(operator_key, value) = entry.get(0).unwrap();
-        let operator = Operator::from_str(operator_key)
-            .ok_or_else(|| format!("Unrecognized operation {}", operator_key))?;
+        let &(operator_key, value) = entry.first().unwrap();
+        let operator = match Operator::from_str(operator_key) {
+            Some(operator) => operator,
+            None => {
+                return extension
+                    .as_mut()
+                    .and_then(|extension| extension(operator_key, value))
+                    .unwrap_or_else(|| Err(format!("Unrecognized operation {}", operator_key)))
+            }
+        };
 
+        let pointer = format!(
+            "{}/{}",
+            pointer,
+            operator_key.replace('~', "~0").replace('/', "~1")
+        );
         let arguments: Vec<_> = match value {
-            Value::Array(arr) => arr.iter().map(|expr| Expression::from_json(expr)).collect(),
+            Value::Array(arr) => arr
+                .iter()
+                .enumerate()
+                .map(|(i, expr)| Expression::parse(expr, extension, &format!("{}/{}", pointer, i)))
+                .collect(),
             // Interpret as an empty array.
             Value::Null => Ok(vec![]),
             // If the value is not an array we can only assume that this is a shorthand.
-            _ => Expression::from_json(value).and_then(|expr| Ok(vec![expr])),
+            _ => Expression::parse(value, extension, &pointer).map(|expr| vec![expr]),
         }?;
 
-        Ok(Expression::Computed(operator, arguments))
+        #[cfg(feature = "regex")]
+        let arguments = Expression::parse_pattern(operator, arguments, &pointer)?;
+
+        Ok(Expression::Computed(
+            operator,
+            Expression::parse_paths(operator, arguments),
+        ))
+    }
+
+    /// Replaces the constant pattern in the arguments of `match` and `captures` by
+    /// `Expression::Pattern`, given the position of the operation. Errors if the pattern is
+    /// invalid.
+    #[cfg(feature = "regex")]
+    fn parse_pattern(
+        operator: Operator,
+        mut args: Vec<Expression<'a>>,
+        pointer: &str,
+    ) -> Result<Vec<Expression<'a>>, String> {
+        if let Some(pattern) = crate::operators::parse_pattern(operator, &args, pointer) {
+            let pattern = pattern?;
+            if let Some(value) = args[1].as_constant() {
+                args[1] = Expression::Pattern(value, pattern);
+            }
+        }
+        Ok(args)
+    }
+
+    /// Replaces the constant paths in the arguments of `var`, `missing` and `missing_some` by
+    /// `Expression::Paths`.
+    fn parse_paths(operator: Operator, mut args: Vec<Expression<'a>>) -> Vec<Expression<'a>> {
+        let to_paths = |arg: &Expression<'a>, arrays: bool| match arg {
+            Expression::Constant(value @ Value::String(path)) => {
+                Some(Expression::Paths(value, vec![Path::parse(path)]))
+            }
+            Expression::Constant(value @ Value::Array(keys)) if arrays => keys
+                .iter()
+                .map(|key| key.as_str().map(Path::parse))
+                .collect::<Option<Vec<_>>>()
+                .map(|paths| Expression::Paths(value, paths)),
+            _ => None,
+        };
+
+        let paths: Vec<(usize, bool)> = match operator {
+            // The empty path refers to the data itself.
+            Operator::Variable => match args.first() {
+                Some(Expression::Constant(Value::String(path))) if path.is_empty() => vec![],
+                _ => vec![(0, false)],
+            },
+            // The keys of `missing` are either the arguments or an array as the first argument.
+            Operator::Missing => (0..args.len()).map(|i| (i, i == 0)).collect(),
+            Operator::MissingSome => vec![(1, true)],
+            _ => vec![],
+        };
+        for (i, arrays) in paths {
+            if let Some(paths) = args.get(i).and_then(|arg| to_paths(arg, arrays)) {
+                args[i] = paths;
+            }
+        }
+
+        args
+    }
+
+    /// Returns the parsed path if this is a constant string path, see `Expression::Paths`.
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            Expression::Paths(Value::String(_), paths) => paths.first(),
+            _ => None,
+        }
+    }
+
+    /// Returns the constant keys together with their parsed paths if this is a constant array
+    /// of string paths, see `Expression::Paths`.
+    pub fn array_paths(&self) -> Option<(&'a [Value], &[Path])> {
+        match self {
+            Expression::Paths(Value::Array(keys), paths) => Some((keys, paths)),
+            _ => None,
+        }
+    }
+
+    /// Returns the compiled pattern if this is a constant pattern, see `Expression::Pattern`.
+    #[cfg(feature = "regex")]
+    pub fn pattern(&self) -> Option<&Pattern> {
+        match self {
+            Expression::Pattern(_, pattern) => Some(pattern),
+            _ => None,
+        }
     }
 
-    /// Computes the expression and returns value it evaluates to.
-    pub fn compute(&self, data: &Data) -> Value {
+    /// Returns the value of this expression if it is a constant, including constant paths.
+    pub fn as_constant(&self) -> Option<&'a Value> {
         match self {
-            Expression::Constant(value) => (*value).clone(),
+            Expression::Constant(value) | Expression::Paths(value, _) => Some(value),
+            #[cfg(feature = "regex")]
+            Expression::Pattern(value, _) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Computes the expression and returns value it evaluates to. Errors with the thrown value if
+    /// the expression throws an error that is not caught.
+    pub fn compute(&self, data: &Data) -> Result<Value, Value> {
+        match self {
+            Expression::Constant(value) | Expression::Paths(value, _) => Ok(clone_value(value)),
+            #[cfg(feature = "regex")]
+            Expression::Pattern(value, _) => Ok(clone_value(value)),
             Expression::Computed(operator, args) => operator.compute(args, data),
+            Expression::Reference(_, expr) => expr.compute(data),
         }
     }
 
+    /// Computes the expressions from left to right. Errors with the first thrown value.
+    pub fn compute_all(exprs: &[Expression], data: &Data) -> Result<Vec<Value>, Value> {
+        exprs.iter().map(|expr| expr.compute(data)).collect()
+    }
+
     /// Returns a set that contains all variable names that occure in this expression and its child
     /// expressions. Errors if a variable operator
     ///
@@ -63,13 +233,16 @@ impl<'a> Expression<'a> {
 
     fn insert_var_names(&self, names: &mut HashSet<String>) -> Result<(), String> {
         match self {
-            Expression::Constant(_) => Ok(()),
+            Expression::Constant(_) | Expression::Paths(_, _) => Ok(()),
+            #[cfg(feature = "regex")]
+            Expression::Pattern(_, _) => Ok(()),
+            Expression::Reference(_, expr) => expr.insert_var_names(names),
             Expression::Computed(operator, args) => {
                 if let Operator::Variable = operator {
                     let first_expr = args
                         .get(0)
                         .ok_or("found Variable operator without arguments")?;
-                    if let Expression::Constant(name_value) = first_expr {
+                    if let Some(name_value) = first_expr.as_constant() {
                         let name = name_value
                             .as_str()
                             .ok_or("found Variable operator with non string argument")?;
@@ -91,6 +264,15 @@ impl<'a> Expression<'a> {
     }
 }
 
+/// Clones the value of a constant. Not inlining the clone lets it write the value straight into
+/// the result of `Expression::compute`, instead of copying it there. In `benches/backends.rs`,
+/// `Rule` takes about 290 instead of 380 ns for fizzbuzz and 3.6 instead of 4.8 µs for the deep
+/// `and`/`or` with it.
+#[inline(never)]
+fn clone_value(value: &Value) -> Value {
+    value.clone()
+}
+
 #[cfg(test)]
 mod tests {
     use super::Expression::*;
@@ -132,7 +314,10 @@ mod tests {
 
         assert_eq!(
             Expression::from_json(&json!({"var": ["foo"]})).unwrap(),
-            Expression::Computed(Operator::Variable, vec![Constant(&json!("foo"))])
+            Expression::Computed(
+                Operator::Variable,
+                vec![Paths(&json!("foo"), vec![Path::parse("foo")])]
+            )
         );
 
         assert_eq!(
@@ -140,13 +325,62 @@ mod tests {
             Expression::Computed(
                 Operator::Equal,
                 vec![
-                    Expression::Computed(Operator::Variable, vec![Constant(&json!("foo"))]),
+                    Expression::Computed(
+                        Operator::Variable,
+                        vec![Paths(&json!("foo"), vec![Path::parse("foo")])]
+                    ),
                     Expression::Constant(&json!("foo"))
                 ]
             )
         );
     }
 
+    #[test]
+    fn parse_paths() {
+        assert_eq!(
+            Expression::from_json(&json!({"var": ["", 1]})).unwrap(),
+            Expression::Computed(
+                Operator::Variable,
+                vec![Constant(&json!("")), Constant(&json!(1))]
+            )
+        );
+        assert_eq!(
+            Expression::from_json(&json!({"var": [1, "a"]})).unwrap(),
+            Expression::Computed(
+                Operator::Variable,
+                vec![Constant(&json!(1)), Constant(&json!("a"))]
+            )
+        );
+        assert_eq!(
+            Expression::from_json(&json!({"missing": ["a.b", 1, ["c"]]})).unwrap(),
+            Expression::Computed(
+                Operator::Missing,
+                vec![
+                    Paths(&json!("a.b"), vec![Path::parse("a.b")]),
+                    Constant(&json!(1)),
+                    Constant(&json!(["c"]))
+                ]
+            )
+        );
+        assert_eq!(
+            Expression::from_json(&json!({"missing": [["a", "b.0"]]})).unwrap(),
+            Expression::Computed(
+                Operator::Missing,
+                vec![Paths(
+                    &json!(["a", "b.0"]),
+                    vec![Path::parse("a"), Path::parse("b.0")]
+                )]
+            )
+        );
+        assert_eq!(
+            Expression::from_json(&json!({"missing_some": [1, ["a", 2]]})).unwrap(),
+            Expression::Computed(
+                Operator::MissingSome,
+                vec![Constant(&json!(1)), Constant(&json!(["a", 2]))]
+            )
+        );
+    }
+
     #[test]
     fn get_variable_names_error() {
         assert_eq!(
@@ -253,18 +487,18 @@ mod tests {
 
         #[test]
         fn constant_expression() {
-            assert_eq!(Constant(&json!(1)).compute(&Data::empty()), json!(1));
+            assert_eq!(Constant(&json!(1)).compute(&Data::empty()), Ok(json!(1)));
         }
 
         #[test]
         fn equal() {
             assert_eq!(
                 Computed(Operator::Equal, vec![]).compute(&Data::empty()),
-                json!(true)
+                Ok(json!(true))
             );
             assert_eq!(
                 Computed(Operator::Equal, vec![Constant(&json!(null))]).compute(&Data::empty()),
-                json!(true)
+                Ok(json!(true))
             );
             assert_eq!(
                 Computed(
@@ -272,7 +506,7 @@ mod tests {
                     vec![Constant(&json!(1)), Constant(&json!(1))]
                 )
                 .compute(&Data::empty()),
-                json!(true)
+                Ok(json!(true))
             );
             assert_eq!(
                 Computed(
@@ -280,7 +514,7 @@ mod tests {
                     vec![Constant(&json!(1)), Constant(&json!(2))]
                 )
                 .compute(&Data::empty()),
-                json!(false)
+                Ok(json!(false))
             );
         }
     }
@@ -0,0 +1,384 @@
+use serde_json::Value;
+use std::cell::RefCell;
+
+use crate::expression::Expression;
+use crate::operators::Operator;
+use crate::Data;
+
+/// An argument of a computed node.
+#[derive(Debug)]
+enum Argument<'a> {
+    /// An argument that is evaluated against the same data as its operator.
+    Node(Node<'a>),
+    /// An argument that its operator evaluates against each element of an array, like the logic
+    /// of `map`. The paths it reads are relative to these elements, so they are not tracked.
+    Scoped(Expression<'a>),
+}
+
+#[derive(Debug)]
+enum NodeKind<'a> {
+    Constant(&'a Value),
+    Computed(Operator, Vec<Argument<'a>>),
+}
+
+/// A node of an expression tree that remembers its value and the data paths it read during the
+/// last evaluation.
+#[derive(Debug)]
+struct Node<'a> {
+    kind: NodeKind<'a>,
+    /// The paths the operator of this node read from the data during the last evaluation, not
+    /// including the paths read by its arguments.
+    reads: Vec<String>,
+    /// The result of the last evaluation, `None` if the node was not evaluated yet.
+    value: Option<Result<Value, Value>>,
+}
+
+impl<'a> Node<'a> {
+    fn from_expression(expr: &Expression<'a>) -> Node<'a> {
+        let kind = match expr {
+            Expression::Constant(value) | Expression::Paths(value, _) => NodeKind::Constant(value),
+            #[cfg(feature = "regex")]
+            Expression::Pattern(value, _) => NodeKind::Constant(value),
+            // References are tracked like an inlined copy of the referenced expression.
+            Expression::Reference(_, expr) => return Node::from_expression(expr),
+            Expression::Computed(operator, args) => NodeKind::Computed(
+                *operator,
+                args.iter()
+                    .enumerate()
+                    .map(|(index, arg)| {
+                        if is_scoped_argument(*operator, index) {
+                            Argument::Scoped(arg.clone())
+                        } else {
+                            Argument::Node(Node::from_expression(arg))
+                        }
+                    })
+                    .collect(),
+            ),
+        };
+
+        Node {
+            kind,
+            reads: vec![],
+            value: None,
+        }
+    }
+
+    /// Evaluates this node again if it was not evaluated yet, if it read one of the changed
+    /// paths or if the value of one of its arguments changed. Returns whether the value of this
+    /// node changed.
+    fn update(&mut self, data: &Value, changed_paths: &[&str]) -> bool {
+        let Node { kind, reads, value } = self;
+
+        let (operator, args) = match kind {
+            NodeKind::Constant(constant) => {
+                if value.is_some() {
+                    return false;
+                }
+                *value = Some(Ok((*constant).clone()));
+                return true;
+            }
+            NodeKind::Computed(operator, args) => (*operator, args),
+        };
+
+        let mut dirty = value.is_none()
+            || reads.iter().any(|read| {
+                changed_paths
+                    .iter()
+                    .any(|changed| paths_overlap(read, changed))
+            });
+        for arg in args.iter_mut() {
+            if let Argument::Node(node) = arg {
+                // Every argument is updated, even if this node is already known to be dirty, so
+                // all of them are up to date for the next update.
+                dirty |= node.update(data, changed_paths);
+            }
+        }
+
+        if !dirty {
+            return false;
+        }
+
+        let arg_expressions: Vec<Expression> = args
+            .iter()
+            .map(|arg| match arg {
+                // Every argument node was updated above, so it has a value. Errors are thrown
+                // again only if the operator evaluates the argument, like `apply` does.
+                Argument::Node(node) => match node.value.as_ref().unwrap() {
+                    Ok(value) => Expression::Constant(value),
+                    Err(error) => {
+                        Expression::Computed(Operator::Throw, vec![Expression::Constant(error)])
+                    }
+                },
+                Argument::Scoped(expr) => expr.clone(),
+            })
+            .collect();
+        let recorded_reads = RefCell::new(vec![]);
+        let new_value = operator.compute(&arg_expressions, &Data::recording(data, &recorded_reads));
+        *reads = recorded_reads.into_inner();
+
+        let changed = value.as_ref() != Some(&new_value);
+        *value = Some(new_value);
+        changed
+    }
+}
+
+/// Evaluates a list of rules and, after the data changed, evaluates only the parts of the rules
+/// that depend on the changed data again.
+///
+/// Every subexpression records the data paths its operator read during the last evaluation (see
+/// the `var`, `missing` and `missing_some` operators). If one of the changed paths is equal to,
+/// a parent of or a child of a recorded path, the subexpression is evaluated again, as well as
+/// every expression whose arguments change by that. Expressions inside the logic of `map`,
+/// `filter`, `reduce`, `all`, `some`, `none`, `sort`, `find`, `count` and `group_by` and the
+/// arguments of `try` and `let` are evaluated as a whole by their operator.
+///
+/// Note that, unlike `apply`, all arguments of `if`, `and` and `or` are evaluated, so their
+/// results are available once the condition changes. Errors thrown in arguments that are not
+/// needed for the result are ignored, like by `apply`.
+///
+/// # Example
+///
+/// ```
+/// use serde_json::json;
+/// use jsonlogic::IncrementalEvaluator;
+///
+/// let rules = vec![
+///     json!({ ">=": [{ "var": "age" }, 18] }),
+///     json!({ "missing": ["name", "email"] }),
+/// ];
+/// let mut evaluator = IncrementalEvaluator::new();
+/// for rule in rules.iter() {
+///     evaluator.add(rule).unwrap();
+/// }
+///
+/// // The first update evaluates all rules.
+/// let data = json!({ "age": 17, "name": "Jane" });
+/// assert_eq!(evaluator.update(&data, &[]), vec![0, 1]);
+/// assert_eq!(evaluator.result(1), Some(&Ok(json!(["email"]))));
+///
+/// // Only the first rule reads `age`.
+/// let data = json!({ "age": 18, "name": "Jane" });
+/// assert_eq!(evaluator.update(&data, &["age"]), vec![0]);
+/// assert_eq!(evaluator.result(0), Some(&Ok(json!(true))));
+/// ```
+#[derive(Debug, Default)]
+pub struct IncrementalEvaluator<'a> {
+    rules: Vec<Node<'a>>,
+}
+
+impl<'a> IncrementalEvaluator<'a> {
+    /// Creates an evaluator without any rules.
+    pub fn new() -> IncrementalEvaluator<'a> {
+        IncrementalEvaluator::default()
+    }
+
+    /// Parses the given JsonLogic rule and adds it to the evaluator. The rule is evaluated on the
+    /// next call of `update`. Returns the id of the rule, which is the number of rules added
+    /// before it.
+    pub fn add(&mut self, json_logic: &'a Value) -> Result<usize, String> {
+        let expression = Expression::from_json(json_logic)?;
+        self.rules.push(Node::from_expression(&expression));
+        Ok(self.rules.len() - 1)
+    }
+
+    /// Evaluates all rules that were not evaluated yet and all parts of the other rules that read
+    /// one of the changed paths. Paths use the same dot-notation as the `var` operator, the empty
+    /// path stands for the whole data.
+    ///
+    /// Returns the ids of all rules whose result changed, in ascending order. Rules that were
+    /// evaluated for the first time count as changed.
+    pub fn update(&mut self, data: &Value, changed_paths: &[&str]) -> Vec<usize> {
+        self.rules
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(id, node)| {
+                if node.update(data, changed_paths) {
+                    Some(id)
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the result of the last evaluation of the rule with the given id, or `None` if the
+    /// rule does not exist or was not evaluated yet. The result is the thrown value if the rule
+    /// threw an error that is not caught.
+    pub fn result(&self, id: usize) -> Option<&Result<Value, Value>> {
+        self.rules.get(id).and_then(|node| node.value.as_ref())
+    }
+}
+
+/// Returns whether the operator evaluates its argument at the given position itself, like the
+/// logic of `map` against each element of an array, the alternatives of `try`, which catch the
+/// errors thrown by them, or the arguments of `let`, which see the names bound before them.
+fn is_scoped_argument(operator: Operator, index: usize) -> bool {
+    match operator {
+        Operator::Map
+        | Operator::Filter
+        | Operator::Reduce
+        | Operator::All
+        | Operator::Some
+        | Operator::None
+        | Operator::Sort
+        | Operator::Find
+        | Operator::Count
+        | Operator::GroupBy => index == 1,
+        Operator::Try | Operator::Let => true,
+        _ => false,
+    }
+}
+
+/// Returns whether a change of the value at one path can change the value at the other path,
+/// which is the case if one of the paths is a prefix of the other one.
+fn paths_overlap(a: &str, b: &str) -> bool {
+    if a.is_empty() || b.is_empty() {
+        return true;
+    }
+
+    a.split('.').zip(b.split('.')).all(|(a, b)| a == b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn overlap() {
+        assert!(paths_overlap("", "a"));
+        assert!(paths_overlap("a.b", ""));
+        assert!(paths_overlap("a", "a"));
+        assert!(paths_overlap("a", "a.b"));
+        assert!(paths_overlap("a.b.1", "a.b"));
+        assert!(!paths_overlap("a.b", "a.c"));
+        assert!(!paths_overlap("a", "ab"));
+    }
+
+    #[test]
+    fn records_reads() {
+        let json_logic = json!({ "and": [
+            { "var": "a" },
+            { "missing": ["b", "c.d"] },
+            { "map": [{ "var": "e" }, { "var": "f" }] }
+        ]});
+        let expression = Expression::from_json(&json_logic).unwrap();
+        let mut node = Node::from_expression(&expression);
+        assert!(node.update(&json!({ "a": 1 }), &[]));
+
+        let reads: Vec<_> = match &node.kind {
+            NodeKind::Computed(_, args) => args
+                .iter()
+                .map(|arg| match arg {
+                    Argument::Node(node) => node.reads.clone(),
+                    Argument::Scoped(_) => unreachable!(),
+                })
+                .collect(),
+            NodeKind::Constant(_) => unreachable!(),
+        };
+        assert_eq!(
+            reads,
+            vec![
+                vec![String::from("a")],
+                vec![String::from("b"), String::from("c.d")],
+                // `e` is read by the `var` argument of `map`, `f` is relative to the elements.
+                vec![],
+            ]
+        );
+        assert!(node.reads.is_empty());
+    }
+
+    #[test]
+    fn only_changed_paths_are_evaluated() {
+        let rules = [
+            json!({ "+": [{ "var": "a" }, { "var": "b.c" }] }),
+            json!({ "if": [{ "var": "flag" }, { "var": "a" }, "off"] }),
+            json!({ "var": "" }),
+            json!(5),
+        ];
+        let mut evaluator = IncrementalEvaluator::new();
+        for rule in rules.iter() {
+            evaluator.add(rule).unwrap();
+        }
+        assert_eq!(evaluator.result(0), None);
+
+        let data = json!({ "a": 1, "b": { "c": 2 }, "flag": false });
+        assert_eq!(evaluator.update(&data, &[]), vec![0, 1, 2, 3]);
+        assert_eq!(evaluator.result(0), Some(&Ok(json!(3))));
+        assert_eq!(evaluator.result(1), Some(&Ok(json!("off"))));
+
+        // Unchanged paths are not read again, even if the data differs.
+        let data = json!({ "a": 1, "b": { "c": 5 }, "flag": false });
+        assert_eq!(evaluator.update(&data, &[]), Vec::<usize>::new());
+        assert_eq!(evaluator.result(0), Some(&Ok(json!(3))));
+
+        assert_eq!(evaluator.update(&data, &["b"]), vec![0, 2]);
+        assert_eq!(evaluator.result(0), Some(&Ok(json!(6))));
+
+        // `a` is read by the second rule as well, but its result stays the same.
+        let data = json!({ "a": 2, "b": { "c": 5 }, "flag": false });
+        assert_eq!(evaluator.update(&data, &["a"]), vec![0, 2]);
+
+        let data = json!({ "a": 2, "b": { "c": 5 }, "flag": true });
+        assert_eq!(evaluator.update(&data, &["flag"]), vec![1, 2]);
+        assert_eq!(evaluator.result(1), Some(&Ok(json!(2))));
+        assert_eq!(evaluator.result(2), Some(&Ok(data)));
+    }
+
+    #[test]
+    fn thrown_errors() {
+        let json_logic = json!({ "if": [
+            { "var": "flag" },
+            { "throw": { "var": "reason" } },
+            "off"
+        ]});
+        let mut evaluator = IncrementalEvaluator::new();
+        evaluator.add(&json_logic).unwrap();
+
+        let data = json!({ "flag": true, "reason": "x" });
+        assert_eq!(evaluator.update(&data, &[]), vec![0]);
+        assert_eq!(evaluator.result(0), Some(&Err(json!("x"))));
+
+        // The thrown branch is evaluated as well, but not used.
+        let data = json!({ "flag": false, "reason": "x" });
+        assert_eq!(evaluator.update(&data, &["flag"]), vec![0]);
+        assert_eq!(evaluator.result(0), Some(&Ok(json!("off"))));
+    }
+
+    #[test]
+    fn scoped_arguments() {
+        let json_logic = json!({ "filter": [{ "var": "items" }, { ">": [{ "var": "" }, 1] }] });
+        let mut evaluator = IncrementalEvaluator::new();
+        evaluator.add(&json_logic).unwrap();
+
+        assert_eq!(
+            evaluator.update(&json!({ "items": [1, 2, 3] }), &[]),
+            vec![0]
+        );
+        assert_eq!(evaluator.result(0), Some(&Ok(json!([2, 3]))));
+
+        assert_eq!(
+            evaluator.update(&json!({ "items": [1, 2, 4] }), &["items.2"]),
+            vec![0]
+        );
+        assert_eq!(evaluator.result(0), Some(&Ok(json!([2, 4]))));
+
+        // The paths read by the arguments of `let` are recorded by the `let` node.
+        let json_logic =
+            json!({ "let": ["x", { "var": "a" }, { "+": [{ "ref": "x" }, { "var": "b" }] }] });
+        let mut evaluator = IncrementalEvaluator::new();
+        evaluator.add(&json_logic).unwrap();
+
+        assert_eq!(evaluator.update(&json!({ "a": 1, "b": 2 }), &[]), vec![0]);
+        assert_eq!(evaluator.result(0), Some(&Ok(json!(3))));
+        assert_eq!(
+            evaluator.update(&json!({ "a": 1, "b": 2, "c": 3 }), &["c"]),
+            Vec::<usize>::new()
+        );
+        assert_eq!(
+            evaluator.update(&json!({ "a": 5, "b": 2 }), &["a"]),
+            vec![0]
+        );
+        assert_eq!(evaluator.result(0), Some(&Ok(json!(7))));
+    }
+}
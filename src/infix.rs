@@ -0,0 +1,526 @@
+use serde_json::Value;
+use std::fmt;
+
+use crate::expression::Expression;
+use crate::operators::Operator;
+
+// Precedences of the infix forms, higher binds stronger.
+const IF: u8 = 1;
+const DEFAULT: u8 = 2;
+const OR: u8 = 3;
+const AND: u8 = 4;
+const EQUALITY: u8 = 5;
+const RELATIONAL: u8 = 6;
+const ADDITIVE: u8 = 7;
+const MULTIPLICATIVE: u8 = 8;
+const UNARY: u8 = 9;
+const PRIMARY: u8 = 10;
+
+/// What the data of `var` is in the current part of an expression.
+#[derive(Debug, Copy, Clone)]
+enum Scope {
+    /// The data the rule is applied to.
+    Data,
+    /// The element of an array, inside of the logic of `map`, `filter`, `all`, `some`, `none`,
+    /// `sort`, `find`, `count` or `group_by`. Contains the number of enclosing element scopes, to
+    /// name nested elements differently.
+    Element(usize),
+    /// The `current` and `accumulator` object inside of the logic of `reduce`.
+    Reduce,
+}
+
+impl Scope {
+    fn element_name(depth: usize) -> String {
+        if depth == 0 {
+            String::from("it")
+        } else {
+            format!("it{}", depth + 1)
+        }
+    }
+
+    /// Returns the scope of the logic of an array operation inside of this scope.
+    fn element(self) -> Scope {
+        match self {
+            Scope::Element(depth) => Scope::Element(depth + 1),
+            _ => Scope::Element(0),
+        }
+    }
+}
+
+/// Formats an expression as infix text, which is meant to be read by humans and is not parseable.
+struct Printer {
+    scope: Scope,
+}
+
+impl Printer {
+    /// Returns the text of the given expression and its precedence.
+    fn print(&self, expr: &Expression) -> (String, u8) {
+        match expr {
+            Expression::Constant(value) | Expression::Paths(value, _) => (constant(value), PRIMARY),
+            #[cfg(feature = "regex")]
+            Expression::Pattern(value, _) => (constant(value), PRIMARY),
+            Expression::Reference(name, _) => {
+                (format!("rule({})", Value::String(name.clone())), PRIMARY)
+            }
+            Expression::Computed(operator, args) => self.operation(*operator, args),
+        }
+    }
+
+    /// Returns the text of the given expression, in parentheses if it binds weaker than
+    /// `precedence`.
+    fn operand(&self, expr: &Expression, precedence: u8) -> String {
+        let (text, expr_precedence) = self.print(expr);
+        if expr_precedence < precedence {
+            format!("({})", text)
+        } else {
+            text
+        }
+    }
+
+    fn operation(&self, operator: Operator, args: &[Expression]) -> (String, u8) {
+        match (operator, args.len()) {
+            (Operator::Variable, _) => self.variable(args),
+            (Operator::If, 0) => (String::from("null"), PRIMARY),
+            (Operator::If, 1) => self.print(&args[0]),
+            (Operator::If, _) => {
+                let mut text = String::new();
+                let mut has_else = false;
+                for pair in args.chunks(2) {
+                    match pair {
+                        [condition, then] => text.push_str(&format!(
+                            "if ({}) {} else ",
+                            self.print(condition).0,
+                            self.operand(then, IF + 1)
+                        )),
+                        [otherwise] => {
+                            text.push_str(&self.operand(otherwise, IF));
+                            has_else = true;
+                        }
+                        _ => unreachable!(),
+                    }
+                }
+                // Without an else branch, `if` results in `null`.
+                if !has_else {
+                    text.push_str("null");
+                }
+                (text, IF)
+            }
+            (Operator::Or, len) if len >= 2 => self.chain(args, " or ", OR),
+            (Operator::And, len) if len >= 2 => self.chain(args, " and ", AND),
+            (Operator::Negation, len) if len >= 1 => {
+                (format!("!{}", self.operand(&args[0], UNARY)), UNARY)
+            }
+            (Operator::DoubleNegation, len) if len >= 1 => {
+                (format!("!!{}", self.operand(&args[0], UNARY)), UNARY)
+            }
+            (Operator::Equal, 2) => self.binary(args, " == ", EQUALITY),
+            (Operator::StrictEqual, 2) => self.binary(args, " === ", EQUALITY),
+            (Operator::NotEqual, 2) => self.binary(args, " != ", EQUALITY),
+            (Operator::StrictNotEqual, 2) => self.binary(args, " !== ", EQUALITY),
+            (Operator::LessThan, 2) => self.binary(args, " < ", RELATIONAL),
+            (Operator::LessEqualThan, 2) => self.binary(args, " <= ", RELATIONAL),
+            (Operator::GreaterThan, 2) => self.binary(args, " > ", RELATIONAL),
+            (Operator::GreaterEqualThan, 2) => self.binary(args, " >= ", RELATIONAL),
+            // Between.
+            (Operator::LessThan, 3) | (Operator::LessEqualThan, 3) => {
+                let op = if operator == Operator::LessThan {
+                    " < "
+                } else {
+                    " <= "
+                };
+                let operands: Vec<_> = args
+                    .iter()
+                    .map(|arg| self.operand(arg, RELATIONAL + 1))
+                    .collect();
+                (operands.join(op), RELATIONAL)
+            }
+            (Operator::In, 2) => self.binary(args, " in ", RELATIONAL),
+            (Operator::Addition, 1) => (format!("+{}", self.operand(&args[0], UNARY)), UNARY),
+            (Operator::Addition, len) if len >= 2 => self.chain(args, " + ", ADDITIVE),
+            (Operator::Subtraction, 1) => (format!("-{}", self.operand(&args[0], UNARY)), UNARY),
+            (Operator::Subtraction, 2) => self.binary(args, " - ", ADDITIVE),
+            (Operator::Multiplication, len) if len >= 2 => self.chain(args, " * ", MULTIPLICATIVE),
+            (Operator::Division, 2) => self.binary(args, " / ", MULTIPLICATIVE),
+            (Operator::Modulo, 2) => self.binary(args, " % ", MULTIPLICATIVE),
+            // The key of `sort` and the condition of `count` are optional, without them the
+            // operators are printed as plain function calls.
+            (Operator::Map, 2)
+            | (Operator::Filter, 2)
+            | (Operator::All, 2)
+            | (Operator::Some, 2)
+            | (Operator::None, 2)
+            | (Operator::Sort, 2)
+            | (Operator::Find, 2)
+            | (Operator::Count, 2)
+            | (Operator::GroupBy, 2) => {
+                let scope = self.scope.element();
+                let name = match scope {
+                    Scope::Element(depth) => Scope::element_name(depth),
+                    _ => unreachable!(),
+                };
+                let logic = Printer { scope }.print(&args[1]).0;
+                (
+                    format!(
+                        "{}({}, {} => {})",
+                        operator.as_str(),
+                        self.print(&args[0]).0,
+                        name,
+                        logic
+                    ),
+                    PRIMARY,
+                )
+            }
+            (Operator::Reduce, len) if len >= 2 => {
+                let logic = Printer {
+                    scope: Scope::Reduce,
+                }
+                .print(&args[1])
+                .0;
+                let initial = args
+                    .get(2)
+                    .map_or_else(|| String::from("null"), |arg| self.print(arg).0);
+                (
+                    format!(
+                        "reduce({}, (current, accumulator) => {}, {})",
+                        self.print(&args[0]).0,
+                        logic,
+                        initial
+                    ),
+                    PRIMARY,
+                )
+            }
+            // All remaining forms are function calls, including operators with an unusual number
+            // of arguments.
+            (Operator::Or, _)
+            | (Operator::And, _)
+            | (Operator::Negation, _)
+            | (Operator::DoubleNegation, _)
+            | (Operator::Equal, _)
+            | (Operator::StrictEqual, _)
+            | (Operator::NotEqual, _)
+            | (Operator::StrictNotEqual, _)
+            | (Operator::LessThan, _)
+            | (Operator::LessEqualThan, _)
+            | (Operator::GreaterThan, _)
+            | (Operator::GreaterEqualThan, _)
+            | (Operator::In, _)
+            | (Operator::Addition, _)
+            | (Operator::Subtraction, _)
+            | (Operator::Multiplication, _)
+            | (Operator::Division, _)
+            | (Operator::Modulo, _)
+            | (Operator::Map, _)
+            | (Operator::Filter, _)
+            | (Operator::All, _)
+            | (Operator::Some, _)
+            | (Operator::None, _)
+            | (Operator::Reduce, _)
+            | (Operator::Missing, _)
+            | (Operator::MissingSome, _)
+            | (Operator::Min, _)
+            | (Operator::Max, _)
+            | (Operator::Cat, _)
+            | (Operator::Substr, _)
+            | (Operator::Log, _)
+            | (Operator::Merge, _)
+            | (Operator::Upper, _)
+            | (Operator::Lower, _)
+            | (Operator::Trim, _)
+            | (Operator::Split, _)
+            | (Operator::Join, _)
+            | (Operator::Replace, _)
+            | (Operator::StartsWith, _)
+            | (Operator::EndsWith, _)
+            | (Operator::Length, _)
+            | (Operator::PadStart, _)
+            | (Operator::IndexOf, _)
+            | (Operator::Sort, _)
+            | (Operator::Unique, _)
+            | (Operator::Flatten, _)
+            | (Operator::Slice, _)
+            | (Operator::Find, _)
+            | (Operator::Count, _)
+            | (Operator::Sum, _)
+            | (Operator::GroupBy, _)
+            | (Operator::Zip, _)
+            | (Operator::Reverse, _)
+            | (Operator::Range, _)
+            | (Operator::Keys, _)
+            | (Operator::Values, _)
+            | (Operator::Entries, _)
+            | (Operator::Get, _)
+            | (Operator::Pick, _)
+            | (Operator::Omit, _)
+            | (Operator::MergeObjects, _)
+            | (Operator::Object, _)
+            | (Operator::Abs, _)
+            | (Operator::Floor, _)
+            | (Operator::Ceil, _)
+            | (Operator::Round, _)
+            | (Operator::Trunc, _)
+            | (Operator::Pow, _)
+            | (Operator::Sqrt, _)
+            | (Operator::Ln, _)
+            | (Operator::Exp, _)
+            | (Operator::Clamp, _)
+            | (Operator::TypeOf, _)
+            | (Operator::IsNumber, _)
+            | (Operator::IsString, _)
+            | (Operator::IsArray, _)
+            | (Operator::ToNumber, _)
+            | (Operator::ParseFloat, _)
+            | (Operator::ToString, _)
+            | (Operator::ToBool, _)
+            | (Operator::Throw, _)
+            | (Operator::Try, _)
+            | (Operator::Let, _)
+            | (Operator::Ref, _) => self.call(operator.as_str(), args),
+            #[cfg(feature = "datetime")]
+            (Operator::Now, _)
+            | (Operator::DateTime, _)
+            | (Operator::DateAdd, _)
+            | (Operator::DateDiff, _)
+            | (Operator::DateTrunc, _)
+            | (Operator::DatePart, _)
+            | (Operator::DateBefore, _)
+            | (Operator::DateAfter, _) => self.call(operator.as_str(), args),
+            #[cfg(feature = "regex")]
+            (Operator::Match, _) | (Operator::Captures, _) => self.call(operator.as_str(), args),
+        }
+    }
+
+    fn call(&self, name: &str, args: &[Expression]) -> (String, u8) {
+        let args: Vec<_> = args.iter().map(|arg| self.print(arg).0).collect();
+        (format!("{}({})", name, args.join(", ")), PRIMARY)
+    }
+
+    /// Formats a left-associative binary operation.
+    fn binary(&self, args: &[Expression], op: &str, precedence: u8) -> (String, u8) {
+        let left_precedence = match precedence {
+            // Comparisons are not associative.
+            EQUALITY | RELATIONAL => precedence + 1,
+            _ => precedence,
+        };
+        (
+            format!(
+                "{}{}{}",
+                self.operand(&args[0], left_precedence),
+                op,
+                self.operand(&args[1], precedence + 1)
+            ),
+            precedence,
+        )
+    }
+
+    /// Formats an operation with any number of operands, like `a + b + c`.
+    fn chain(&self, args: &[Expression], op: &str, precedence: u8) -> (String, u8) {
+        let operands: Vec<_> = args
+            .iter()
+            .enumerate()
+            .map(|(index, arg)| {
+                let operand_precedence = if index == 0 {
+                    precedence
+                } else {
+                    precedence + 1
+                };
+                self.operand(arg, operand_precedence)
+            })
+            .collect();
+        (operands.join(op), precedence)
+    }
+
+    fn variable(&self, args: &[Expression]) -> (String, u8) {
+        let path = match args.first().and_then(Expression::as_constant) {
+            Some(Value::String(path)) => self.path(path),
+            Some(Value::Number(index)) => self.path(&index.to_string()),
+            _ => None,
+        };
+
+        match (path, args.get(1)) {
+            (Some(path), None) => (path, PRIMARY),
+            (Some(path), Some(default)) => (
+                format!("{} ?? {}", path, self.operand(default, DEFAULT + 1)),
+                DEFAULT,
+            ),
+            (None, _) => self.call("var", args),
+        }
+    }
+
+    /// Returns the text of the given `var` path in the current scope, or `None` if it cannot be
+    /// written as a plain path.
+    fn path(&self, path: &str) -> Option<String> {
+        let is_plain = path.split('.').all(|step| {
+            !step.is_empty()
+                && step
+                    .chars()
+                    .all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+        });
+
+        match self.scope {
+            Scope::Element(depth) if path.is_empty() => Some(Scope::element_name(depth)),
+            Scope::Element(depth) if is_plain => {
+                Some(format!("{}.{}", Scope::element_name(depth), path))
+            }
+            // A path that starts with a digit would read like a number.
+            Scope::Data | Scope::Reduce
+                if is_plain && !path.starts_with(|ch: char| ch.is_ascii_digit()) =>
+            {
+                Some(path.to_owned())
+            }
+            _ => None,
+        }
+    }
+}
+
+fn constant(value: &Value) -> String {
+    match value {
+        Value::Array(arr) => {
+            let elements: Vec<_> = arr.iter().map(constant).collect();
+            format!("[{}]", elements.join(", "))
+        }
+        _ => value.to_string(),
+    }
+}
+
+/// Formats the expression as readable infix text, like
+/// `if (i % 15 == 0) "fizzbuzz" else if (i % 3 == 0) "fizz" else i`.
+///
+/// Comparisons and arithmetic are written as infix operators with JavaScript-like precedence,
+/// `if` as `if (condition) then else otherwise`, `var` as its path and `var` with a default value
+/// as `path ?? default`. Inside of the logic of `map`, `filter`, `all`, `some` and `none`, the
+/// current element is called `it` (`it2`, `it3`, ... for nested operations), the logic of
+/// `reduce` reads `current` and `accumulator`. All other operations are written as function
+/// calls. The text is not meant to be parsed again.
+impl<'a> fmt::Display for Expression<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let printer = Printer { scope: Scope::Data };
+        write!(f, "{}", printer.print(self).0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn infix(json_logic: Value) -> String {
+        Expression::from_json(&json_logic).unwrap().to_string()
+    }
+
+    #[test]
+    fn fizzbuzz() {
+        assert_eq!(
+            infix(json!({ "if": [
+                { "==": [{ "%": [{ "var": "i" }, 15] }, 0] }, "fizzbuzz",
+                { "==": [{ "%": [{ "var": "i" }, 3] }, 0] }, "fizz",
+                { "==": [{ "%": [{ "var": "i" }, 5] }, 0] }, "buzz",
+                { "var": "i" }
+            ]})),
+            "if (i % 15 == 0) \"fizzbuzz\" else if (i % 3 == 0) \"fizz\" else if (i % 5 == 0) \"buzz\" else i"
+        );
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(
+            infix(json!({ "*": [{ "+": [1, 2] }, { "-": [3, { "-": [4, 5] }] }, 6] })),
+            "(1 + 2) * (3 - (4 - 5)) * 6"
+        );
+        assert_eq!(
+            infix(json!({ "-": [{ "-": [1, 2] }, { "/": [{ "%": [3, 4] }, 5] }] })),
+            "1 - 2 - 3 % 4 / 5"
+        );
+        assert_eq!(
+            infix(json!({ "or": [
+                { "and": [{ "var": "a" }, { "or": [{ "var": "b" }, { "var": "c" }] }] },
+                { "!": { "==": [{ "var": "d" }, { "!!": [{ "var": "e" }] }] } },
+                { "==": [{ "==": [1, 1] }, true] }
+            ]})),
+            "a and (b or c) or !(d == !!e) or (1 == 1) == true"
+        );
+        assert_eq!(
+            infix(
+                json!({ "+": [{ "-": { "var": "a" } }, { "+": { "var": "b" } }, { "if": [true, 1, 2] }] })
+            ),
+            "-a + +b + (if (true) 1 else 2)"
+        );
+        assert_eq!(
+            infix(
+                json!({ "if": [{ "if": [{ "var": "a" }, true] }, { "if": [{ "var": "b" }, 1, 2] }] })
+            ),
+            "if (if (a) true else null) (if (b) 1 else 2) else null"
+        );
+    }
+
+    #[test]
+    fn variables() {
+        assert_eq!(
+            infix(json!({ "<=": [1, { "var": ["a.b", 5] }, { "var": "c.0" }] })),
+            "1 <= (a.b ?? 5) <= c.0"
+        );
+        assert_eq!(
+            infix(
+                json!({ "cat": [{ "var": "first name" }, { "var": "" }, { "var": 0 }, { "var": { "var": "key" } }] })
+            ),
+            "cat(var(\"first name\"), var(\"\"), var(0), var(key))"
+        );
+    }
+
+    #[test]
+    fn lambdas() {
+        assert_eq!(
+            infix(json!({ "map": [
+                { "filter": [{ "var": "groups" }, { "some": [{ "var": "members" }, { "==": [{ "var": "" }, "x"] }] }] },
+                { "var": "name" }
+            ]})),
+            "map(filter(groups, it => some(it.members, it2 => it2 == \"x\")), it => it.name)"
+        );
+        assert_eq!(
+            infix(json!({ "reduce": [
+                { "var": "items" },
+                { "+": [{ "var": "current.price" }, { "var": "accumulator" }] },
+                0
+            ]})),
+            "reduce(items, (current, accumulator) => current.price + accumulator, 0)"
+        );
+        assert_eq!(
+            infix(json!({ "all": [[1, 2], { ">": [{ "var": "" }, 0] }] })),
+            "all([1, 2], it => it > 0)"
+        );
+        assert_eq!(
+            infix(json!({ "find": [{ "var": "xs" }, { ">": [{ "var": "a" }, 1] }] })),
+            "find(xs, it => it.a > 1)"
+        );
+        assert_eq!(
+            infix(json!({ "group_by": [
+                { "sort": [{ "var": "xs" }, { "var": "name" }] },
+                { "count": [{ "var": "tags" }, { "var": "" }] }
+            ]})),
+            "group_by(sort(xs, it => it.name), it => count(it.tags, it2 => it2))"
+        );
+        assert_eq!(
+            infix(json!({ "+": [{ "sort": [{ "var": "xs" }] }, { "count": [[0, 1]] }] })),
+            "sort(xs) + count([0, 1])"
+        );
+    }
+
+    #[test]
+    fn function_calls() {
+        assert_eq!(
+            infix(json!({ "merge": [
+                { "missing": ["a", "b"] },
+                { "missing_some": [1, ["c"]] },
+                { "min": [1, 2] },
+                { "max": [] },
+                { "substr": ["abc", 1] },
+                { "log": { "in": ["a", "abc"] } },
+                { "==": [1] },
+                { "and": [true] },
+                { "map": [[1]] },
+                { "reduce": [[1], { "var": "current" }] }
+            ]})),
+            "merge(missing(\"a\", \"b\"), missing_some(1, [\"c\"]), min(1, 2), max(), substr(\"abc\", 1), log(\"a\" in \"abc\"), ==(1), and(true), map([1]), reduce([1], (current, accumulator) => current, null))"
+        );
+        assert_eq!(infix(json!({ "a": 1, "b": 2 })), "{\"a\":1,\"b\":2}");
+    }
+}
@@ -1,16 +1,48 @@
 extern crate serde_json;
 
+mod bytecode;
+#[cfg(feature = "datetime")]
+mod clock;
+mod closure;
+mod condition_parser;
 mod data;
+mod decision_table;
+mod error;
 mod expression;
+mod incremental;
+mod infix;
 mod operators;
+mod query_dsl;
+mod registry;
+mod rule;
+mod rule_set;
+mod sql;
+mod string_mode;
+mod template;
 
 use serde_json::Value;
 use std::collections::HashSet;
 
+pub use bytecode::BytecodeRule;
+#[cfg(feature = "datetime")]
+pub use clock::Clock;
+pub use closure::ClosureRule;
+pub use condition_parser::parse_condition;
 use data::Data;
+pub use decision_table::{DecisionTable, HitPolicy};
+pub use error::Error;
+pub use incremental::IncrementalEvaluator;
+pub use query_dsl::{to_elasticsearch_query, to_mongodb_query};
+pub use registry::RuleRegistry;
+pub use rule::Rule;
+pub use rule_set::RuleSet;
+pub use sql::{SqlDialect, SqlQuery, SqlTranslator};
+pub use string_mode::StringMode;
+pub use template::{ParamType, RuleTemplate};
 
 /// Applies the given JsonLogic rule to the specified data.
 /// If the rule does not use any variables, you may pass `&Value::Null` as the second argument.
+/// Errors if the rule cannot be parsed or throws an error that is not caught, see `Error`.
 ///
 /// # Example
 ///
@@ -30,10 +62,9 @@ use data::Data;
 /// let data = json!({ "foo": 3 });
 /// assert_eq!(jsonlogic::apply(&rule, &data), Ok(Value::Bool(false)));
 /// ```
-pub fn apply(json_logic: &Value, data: &Value) -> Result<Value, String> {
-    let ast = expression::Expression::from_json(json_logic)?;
-    let data = Data::from_json(data);
-    Ok(ast.compute(&data))
+pub fn apply(json_logic: &Value, data: &Value) -> Result<Value, Error> {
+    let rule = Rule::compile(json_logic).map_err(Error::Compile)?;
+    rule.apply(data).map_err(Error::Thrown)
 }
 
 // TODO: Add to public api when ready.
@@ -58,4 +89,31 @@ mod tests {
             .collect();
         assert_eq!(get_variable_names(&json_logic).unwrap(), names);
     }
+
+    #[test]
+    fn var_names_without_bound_names() {
+        // Names bound by `let` are read with `ref`, so they are not variables of the data.
+        let json_logic = json!({ "let": [
+            "discount", { "*": [{ "var": "price" }, 0.1] },
+            { ">": [{ "ref": "discount" }, { "var": "limit" }] }
+        ]});
+        let names: HashSet<_> = [String::from("price"), String::from("limit")]
+            .iter()
+            .cloned()
+            .collect();
+        assert_eq!(get_variable_names(&json_logic).unwrap(), names);
+    }
+
+    #[test]
+    fn send_and_sync() {
+        // Compiled rules are shared between threads, e.g. behind an `Arc` in a server.
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Rule>();
+        assert_send_sync::<BytecodeRule>();
+        assert_send_sync::<ClosureRule>();
+        assert_send_sync::<RuleSet>();
+        assert_send_sync::<DecisionTable>();
+        assert_send_sync::<RuleRegistry>();
+        assert_send_sync::<RuleTemplate>();
+    }
 }
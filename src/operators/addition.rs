@@ -1,23 +1,29 @@
-use serde_json::{Number, Value};
+use serde_json::Value;
 
-use super::{logic, Data, Expression};
+use super::{logic, Data};
 
 /// +, takes an arbitrary number of arguments and sums them up. If just one argument is passed, it
 /// will be cast to a number. Returns `Value::Null` if one argument cannot be coerced into a
-/// number.
-pub fn compute(args: &[Expression], data: &Data) -> Value {
+/// number or the sum is not finite, like `1e308 + 1e308`. The sum is an integer if all arguments
+/// are integers, unless it overflows.
+pub fn compute(args: &[Value], _data: &Data) -> Value {
     let mut result = 0f64;
+    let mut int_result = Some(0i128);
 
     for arg in args.iter() {
+        int_result = int_result.and_then(|sum| sum.checked_add(logic::as_integer(arg)?));
         // Use parseFloat like in the javascript implementation.
         // parseFloat(null) is NaN, whereas coerce_to_f64 would return 0.
-        match logic::parse_float(&arg.compute(data)) {
+        match logic::parse_float(arg) {
             Some(num) => result += num,
             None => return Value::Null,
         }
     }
 
-    Value::Number(Number::from_f64(result).unwrap())
+    match int_result.and_then(logic::integer_to_number) {
+        Some(num) => Value::Number(num),
+        None => logic::f64_to_value(result),
+    }
 }
 
 #[cfg(test)]
@@ -28,14 +34,34 @@ mod tests {
 
     #[test]
     fn test() {
-        assert_eq!(compute_const!(), json!(0.0));
+        assert_eq!(compute_const!(), json!(0));
         assert_eq!(compute_const!(Value::Null), Value::Null);
         assert_eq!(compute_const!(json!("foo")), Value::Null);
-        assert_eq!(compute_const!(json!("6")), json!(6.0));
-        assert_eq!(compute_const!(json!(4), json!(2)), json!(6.0));
+        assert_eq!(compute_const!(json!("6")), json!(6));
+        assert_eq!(compute_const!(json!(4), json!(2)), json!(6));
         assert_eq!(
             compute_const!(json!(4), json!(2), json!(2), json!(2)),
-            json!(10.0)
+            json!(10)
+        );
+    }
+
+    #[test]
+    fn integers() {
+        assert_eq!(compute_const!(json!(4.5), json!(2)), json!(6.5));
+        assert_eq!(compute_const!(json!(4.0), json!(2)), json!(6.0));
+        assert_eq!(compute_const!(json!("4.5"), json!(2)), json!(6.5));
+        assert_eq!(compute_const!(json!(true), json!(2)), Value::Null);
+        assert_eq!(
+            compute_const!(json!(9007199254740993u64), json!(2)),
+            json!(9007199254740995u64)
+        );
+        assert_eq!(
+            compute_const!(json!(u64::MAX), json!(i64::MIN)),
+            json!(i64::MAX)
+        );
+        assert_eq!(
+            compute_const!(json!(u64::MAX), json!(1)),
+            json!(u64::MAX as f64 + 1.0)
         );
     }
 }
@@ -7,31 +7,31 @@ use super::{logic, Data, Expression};
 ///
 /// `var` operations inside the second argument expression are relative to the array element
 /// being tested.
-pub fn compute(args: &[Expression], data: &Data) -> Value {
-    let arr = match args.get(0).map(|arg| arg.compute(data)) {
+pub fn compute(args: &[Expression], data: &Data) -> Result<Value, Value> {
+    let arr = match args.get(0).map(|arg| arg.compute(data)).transpose()? {
         Some(Value::Array(arr)) => arr,
         // Due to an implementation detail `all` also works on strings. Applying the condition on
         // each character on the string.
         Some(Value::String(s)) => s.chars().map(|ch| Value::String(ch.to_string())).collect(),
-        _ => return Value::Bool(false),
+        _ => return Ok(Value::Bool(false)),
     };
 
     if arr.len() == 0 {
-        return Value::Bool(false);
+        return Ok(Value::Bool(false));
     }
 
     let condition = match args.get(1) {
         Some(expr) => expr,
-        None => return Value::Bool(false),
+        None => return Ok(Value::Bool(false)),
     };
 
     for elem in arr.iter() {
-        let result = condition.compute(&Data::from_json(&elem));
+        let result = condition.compute(&data.nested(&elem))?;
         if !logic::is_truthy(&result) {
-            return Value::Bool(false);
+            return Ok(Value::Bool(false));
         }
     }
 
     // Condition is truthy for all elements.
-    Value::Bool(true)
+    Ok(Value::Bool(true))
 }
@@ -4,17 +4,18 @@ use super::{logic, Data, Expression};
 
 /// Takes an arbitrary number of arguments. Returns the first falsy argument or the last
 /// argument.
-pub fn compute(args: &[Expression], data: &Data) -> Value {
+pub fn compute(args: &[Expression], data: &Data) -> Result<Value, Value> {
     let args = args.iter().map(|arg| arg.compute(data));
     let mut last = None;
 
     for arg in args {
+        let arg = arg?;
         if !logic::is_truthy(&arg) {
-            return arg;
+            return Ok(arg);
         }
 
         last = Some(arg);
     }
 
-    last.unwrap_or(Value::Null)
+    Ok(last.unwrap_or(Value::Null))
 }
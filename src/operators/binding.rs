@@ -0,0 +1,121 @@
+//! Local names bound by `let` and read by `ref`. Bound names live next to the data, so they never
+//! shadow a key of the data that is read with `var`.
+
+use serde_json::Value;
+
+use super::{logic, Data, Expression};
+
+/// Binds names to values for the evaluation of the last argument, the body. The other arguments
+/// are pairs of a name and an expression whose value is bound to it, like in
+/// `{"let": ["total", {"*": [{"var": "price"}, 2]}, {"ref": "total"}]}`. Every value can refer to
+/// the names bound before it. Returns `Value::Null` if there is no body.
+pub fn compute_let(args: &[Expression], data: &Data) -> Result<Value, Value> {
+    evaluate_let(args, data, &|arg, data| arg.compute(data))
+}
+
+/// Evaluates the arguments with `compute` like the `let` operator, for the other backends.
+pub fn evaluate_let<T>(
+    args: &[T],
+    data: &Data,
+    compute: &impl Fn(&T, &Data) -> Result<Value, Value>,
+) -> Result<Value, Value> {
+    match args {
+        [] | [_, _] => Ok(Value::Null),
+        [body] => compute(body, data),
+        [name, value, rest @ ..] => {
+            let name = logic::coerce_to_str(&compute(name, data)?);
+            let value = compute(value, data)?;
+            data.bind(name, value, |data| evaluate_let(rest, data, compute))
+        }
+    }
+}
+
+/// Returns the value bound to the name of the first argument by an enclosing `let`, which may be
+/// followed by a path into the value, like `"user.name"`. Returns the second argument if the name
+/// is not bound, or `Value::Null` if there is no second argument.
+pub fn compute_ref(args: &[Value], data: &Data) -> Value {
+    let value = args
+        .first()
+        .map(logic::coerce_to_str)
+        .and_then(|name| data.get_binding(&name));
+    match value {
+        Some(value) => value,
+        None => args.get(1).cloned().unwrap_or(Value::Null),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::apply;
+    use serde_json::{json, Value};
+
+    #[test]
+    fn let_operator() {
+        assert_eq!(apply(&json!({"let": []}), &Value::Null), Ok(Value::Null));
+        assert_eq!(apply(&json!({"let": [1]}), &Value::Null), Ok(json!(1)));
+        assert_eq!(
+            apply(&json!({"let": ["a", 1]}), &Value::Null),
+            Ok(Value::Null)
+        );
+        assert_eq!(
+            apply(&json!({"let": ["a", 1, {"ref": "a"}]}), &Value::Null),
+            Ok(json!(1))
+        );
+        assert_eq!(
+            apply(
+                &json!({"let": [
+                    "a", 1,
+                    "b", {"+": [{"ref": "a"}, 1]},
+                    {"merge": [{"ref": "a"}, {"ref": "b"}]}
+                ]}),
+                &Value::Null
+            ),
+            Ok(json!([1, 2]))
+        );
+    }
+
+    #[test]
+    fn shadowing() {
+        let rule = json!({"let": ["a", 1, {"let": ["a", 2, {"ref": "a"}]}]});
+        assert_eq!(apply(&rule, &Value::Null), Ok(json!(2)));
+
+        // Bound names and keys of the data are separate.
+        let rule = json!({"let": ["a", 1, {"merge": [{"ref": "a"}, {"var": "a"}]}]});
+        assert_eq!(apply(&rule, &json!({"a": "data"})), Ok(json!([1, "data"])));
+    }
+
+    #[test]
+    fn ref_operator() {
+        assert_eq!(apply(&json!({"ref": "a"}), &Value::Null), Ok(Value::Null));
+        assert_eq!(
+            apply(&json!({"ref": ["a", "default"]}), &Value::Null),
+            Ok(json!("default"))
+        );
+        assert_eq!(
+            apply(
+                &json!({"let": ["user", {"var": ""}, {"ref": "user.names.1"}]}),
+                &json!({"names": ["Jane", "John"]})
+            ),
+            Ok(json!("John"))
+        );
+        assert_eq!(
+            apply(
+                &json!({"let": ["user", {"var": ""}, {"ref": ["user.age", 0]}]}),
+                &json!({"names": ["Jane"]})
+            ),
+            Ok(json!(0))
+        );
+
+        // Bound names are visible in the logic of `map`.
+        assert_eq!(
+            apply(
+                &json!({"let": [
+                    "factor", 3,
+                    {"map": [[1, 2], {"*": [{"var": ""}, {"ref": "factor"}]}]}
+                ]}),
+                &Value::Null
+            ),
+            Ok(json!([3, 6]))
+        );
+    }
+}
@@ -1,13 +1,12 @@
 use serde_json::Value;
 
-use super::{logic, Data, Expression};
+use super::{logic, Data};
 
-pub fn compute(args: &[Expression], data: &Data) -> Value {
+pub fn compute(args: &[Value], _data: &Data) -> Value {
     let mut result = String::new();
 
     for arg in args {
-        let val = arg.compute(data);
-        result.push_str(&logic::coerce_to_str(&val));
+        result.push_str(&logic::coerce_to_str(arg));
     }
 
     Value::String(result)
@@ -0,0 +1,52 @@
+use serde_json::Value;
+
+use super::{logic, Data, Expression};
+
+/// Takes an array as the first argument and a condition as the second argument. Returns the
+/// number of elements for which the condition evaluates to a truthy value. Without a condition,
+/// counts the truthy elements. Returns 0 if the first argument is not an array.
+///
+/// `var` operations inside the second argument expression are relative to the array element
+/// being tested.
+pub fn compute(args: &[Expression], data: &Data) -> Result<Value, Value> {
+    let arr = match args.first().map(|arg| arg.compute(data)).transpose()? {
+        Some(Value::Array(arr)) => arr,
+        _ => return Ok(Value::from(0)),
+    };
+
+    let count = match args.get(1) {
+        Some(condition) => {
+            let mut count = 0;
+            for elem in arr.iter() {
+                if logic::is_truthy(&condition.compute(&data.nested(elem))?) {
+                    count += 1;
+                }
+            }
+            count
+        }
+        None => arr.iter().filter(|elem| logic::is_truthy(elem)).count(),
+    };
+    Ok(Value::from(count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute_lazy;
+    use serde_json::json;
+
+    #[test]
+    fn test() {
+        assert_eq!(compute_lazy!(), Ok(json!(0)));
+        assert_eq!(compute_lazy!(json!("abc")), Ok(json!(0)));
+        assert_eq!(compute_lazy!(json!([1, 0, "", "a", null])), Ok(json!(2)));
+        assert_eq!(compute_lazy!(json!([1, 0]), json!(true)), Ok(json!(2)));
+        assert_eq!(
+            crate::apply(
+                &json!({"count": [{"var": "scores"}, {">=": [{"var": ""}, 50]}]}),
+                &json!({"scores": [10, 50, 70, 20]})
+            ),
+            Ok(json!(2))
+        );
+    }
+}
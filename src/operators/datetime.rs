@@ -0,0 +1,682 @@
+//! The date and time operators of the `datetime` feature. Timestamps are RFC 3339 strings like
+//! `"2024-03-31T12:00:00+02:00"`, which the operators also return. Time zones are fixed offsets
+//! from UTC, like `"+02:00"` or `"Z"`, or IANA time zone names, like `"Europe/Berlin"`, whose
+//! offset changes with daylight saving time. Arguments that are not timestamps or that are out of
+//! range make the operators return `Value::Null`.
+
+use chrono::{
+    DateTime, Datelike, Duration, FixedOffset, LocalResult, Months, NaiveDate, NaiveDateTime,
+    Offset, SecondsFormat, TimeZone, Timelike, Utc,
+};
+use chrono_tz::Tz;
+use serde_json::Value;
+use std::convert::TryFrom;
+
+use super::{logic, Data};
+
+/// Coerces the value into a timestamp, similar to `logic::coerce_to_f64`. Strings are parsed as
+/// RFC 3339 timestamps, ISO 8601 date-times without offset or ISO 8601 dates, where the latter two
+/// are taken as UTC. Numbers are milliseconds since the Unix epoch, like in JavaScript. An array
+/// with a single element is coerced into the timestamp of that element.
+pub fn coerce_to_datetime(val: &Value) -> Option<DateTime<FixedOffset>> {
+    match val {
+        Value::Array(arr) => match &arr[..] {
+            [el] => coerce_to_datetime(el),
+            _ => None,
+        },
+        Value::Number(num) => num
+            .as_i64()
+            .or_else(|| {
+                num.as_f64()
+                    .filter(|num| num.is_finite())
+                    .map(|num| num as i64)
+            })
+            .and_then(|millis| Utc.timestamp_millis_opt(millis).single())
+            .map(|datetime| datetime.with_timezone(&utc())),
+        Value::String(s) => parse(s.trim()),
+        Value::Bool(_) | Value::Null | Value::Object(_) => None,
+    }
+}
+
+fn parse(s: &str) -> Option<DateTime<FixedOffset>> {
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(s) {
+        return Some(datetime);
+    }
+    let naive = NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f")
+        .or_else(|_| NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M"))
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })?;
+    utc().from_local_datetime(&naive).single()
+}
+
+fn utc() -> FixedOffset {
+    FixedOffset::east_opt(0).unwrap()
+}
+
+/// A time zone argument.
+#[derive(Debug, Clone, Copy)]
+enum Zone {
+    /// A fixed offset from UTC.
+    Fixed(FixedOffset),
+    /// An IANA time zone, whose offset depends on the time.
+    Named(Tz),
+}
+
+impl Zone {
+    /// Converts the timestamp into the zone, with the offset the zone has at that time. Returns
+    /// `None` if the local time in the zone is out of the supported range.
+    fn convert(self, datetime: DateTime<FixedOffset>) -> Option<DateTime<FixedOffset>> {
+        let offset = match self {
+            Zone::Fixed(offset) => offset,
+            Zone::Named(tz) => tz.offset_from_utc_datetime(&datetime.naive_utc()).fix(),
+        };
+        with_offset(datetime, offset)
+    }
+
+    /// Returns the timestamp of the local date and time in the zone. A local time that occurs
+    /// twice, when daylight saving time ends, has the offset `preferred` if possible, or else the
+    /// earlier one. A local time that is skipped, when daylight saving time starts, is moved
+    /// forward by the length of the gap, so the start of a day that begins with the gap is the
+    /// end of the gap.
+    fn resolve_local(
+        self,
+        naive: &NaiveDateTime,
+        preferred: FixedOffset,
+    ) -> Option<DateTime<FixedOffset>> {
+        let tz = match self {
+            Zone::Fixed(offset) => return offset.from_local_datetime(naive).single(),
+            Zone::Named(tz) => tz,
+        };
+        let datetime = match tz.from_local_datetime(naive) {
+            LocalResult::Single(datetime) => datetime,
+            LocalResult::Ambiguous(earlier, later) => {
+                if later.offset().fix() == preferred {
+                    later
+                } else {
+                    earlier
+                }
+            }
+            LocalResult::None => {
+                // The offset before the gap maps the local time to a time after it.
+                let before = tz
+                    .offset_from_utc_datetime(&naive.checked_sub_signed(Duration::days(1))?)
+                    .fix();
+                let utc = naive
+                    .checked_sub_signed(Duration::seconds(i64::from(before.local_minus_utc())))?;
+                tz.from_utc_datetime(&utc)
+            }
+        };
+        Some(datetime.with_timezone(&datetime.offset().fix()))
+    }
+}
+
+/// Converts the timestamp into the offset. Returns `None` if the local time in the offset is out of
+/// the supported range, which `DateTime` cannot format or take apart.
+fn with_offset(
+    datetime: DateTime<FixedOffset>,
+    offset: FixedOffset,
+) -> Option<DateTime<FixedOffset>> {
+    datetime.naive_utc().checked_add_offset(offset)?;
+    Some(datetime.with_timezone(&offset))
+}
+
+/// Coerces the value into a time zone, which is either an offset from UTC, like `"+02:00"`,
+/// `"-0530"`, `"Z"` or `"UTC"`, or an IANA time zone name, like `"America/New_York"`.
+fn coerce_to_zone(val: &Value) -> Option<Zone> {
+    match logic::coerce_to_str(val).trim() {
+        "Z" | "UTC" => Some(Zone::Fixed(utc())),
+        zone => match zone.parse() {
+            Ok(offset) => Some(Zone::Fixed(offset)),
+            Err(_) => zone.parse().ok().map(Zone::Named),
+        },
+    }
+}
+
+fn to_value(datetime: Option<DateTime<FixedOffset>>) -> Value {
+    datetime.map_or(Value::Null, |datetime| {
+        Value::String(datetime.to_rfc3339_opts(SecondsFormat::AutoSi, true))
+    })
+}
+
+/// Coerces the argument at the index into a timestamp.
+fn datetime_arg(args: &[Value], index: usize) -> Option<DateTime<FixedOffset>> {
+    coerce_to_datetime(args.get(index)?)
+}
+
+/// Like `datetime_arg`, but converts the timestamp into the time zone of the argument at
+/// `zone_index`, if it is present. Returns the timestamp together with its zone, which is the
+/// offset of the timestamp if there is no such argument.
+fn datetime_arg_in_zone(
+    args: &[Value],
+    index: usize,
+    zone_index: usize,
+) -> Option<(DateTime<FixedOffset>, Zone)> {
+    let datetime = datetime_arg(args, index)?;
+    match args.get(zone_index) {
+        Some(zone) => {
+            let zone = coerce_to_zone(zone)?;
+            Some((zone.convert(datetime)?, zone))
+        }
+        None => Some((datetime, Zone::Fixed(*datetime.offset()))),
+    }
+}
+
+/// A unit of time for `date_add`, `date_diff` and `date_trunc`.
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Unit {
+    Year,
+    Month,
+    Week,
+    Day,
+    Hour,
+    Minute,
+    Second,
+    Millisecond,
+}
+
+impl Unit {
+    /// Parses the singular or plural name of the unit, like `"day"` or `"days"`.
+    fn parse(val: &Value) -> Option<Unit> {
+        let name = logic::coerce_to_str(val);
+        match name.strip_suffix('s').unwrap_or(&name) {
+            "year" => Some(Unit::Year),
+            "month" => Some(Unit::Month),
+            "week" => Some(Unit::Week),
+            "day" => Some(Unit::Day),
+            "hour" => Some(Unit::Hour),
+            "minute" => Some(Unit::Minute),
+            "second" => Some(Unit::Second),
+            "millisecond" => Some(Unit::Millisecond),
+            _ => None,
+        }
+    }
+
+    fn length(self) -> Length {
+        match self {
+            Unit::Year => Length::Months(12),
+            Unit::Month => Length::Months(1),
+            Unit::Week => Length::Millis(7 * 24 * 60 * 60 * 1000),
+            Unit::Day => Length::Millis(24 * 60 * 60 * 1000),
+            Unit::Hour => Length::Millis(60 * 60 * 1000),
+            Unit::Minute => Length::Millis(60 * 1000),
+            Unit::Second => Length::Millis(1000),
+            Unit::Millisecond => Length::Millis(1),
+        }
+    }
+}
+
+/// The length of a unit. Years and months are calendar units, whose length in milliseconds
+/// varies.
+enum Length {
+    Months(i64),
+    Millis(i64),
+}
+
+fn add_months(datetime: DateTime<FixedOffset>, months: i64) -> Option<DateTime<FixedOffset>> {
+    let abs = Months::new(u32::try_from(months.checked_abs()?).ok()?);
+    if months >= 0 {
+        datetime.checked_add_months(abs)
+    } else {
+        datetime.checked_sub_months(abs)
+    }
+}
+
+/// Returns the current time from the clock of the data, see `Clock`.
+pub fn now(_args: &[Value], data: &Data) -> Value {
+    to_value(Some(
+        DateTime::<Utc>::from(data.now()).with_timezone(&utc()),
+    ))
+}
+
+/// Coerces the first argument into a timestamp, see `coerce_to_datetime`. If a time zone is
+/// passed as the second argument, the timestamp is converted into that zone.
+pub fn datetime(args: &[Value], _data: &Data) -> Value {
+    to_value(datetime_arg_in_zone(args, 0, 1).map(|(datetime, _)| datetime))
+}
+
+/// Adds the amount of the second argument in the unit of the third argument to the timestamp,
+/// e.g. `{"date_add": [{"var": "created"}, 30, "days"]}`. Negative amounts subtract. Years and
+/// months are added to the calendar date, which is clamped to the end of the month.
+pub fn date_add(args: &[Value], _data: &Data) -> Value {
+    let compute = || {
+        let datetime = datetime_arg(args, 0)?;
+        let amount = logic::coerce_to_f64(args.get(1)?)?;
+        let unit = Unit::parse(args.get(2)?)?;
+        match unit.length() {
+            Length::Months(months) => {
+                add_months(datetime, (amount.trunc() as i64).checked_mul(months)?)
+            }
+            Length::Millis(unit_millis) => {
+                let millis = (amount * unit_millis as f64).round();
+                if !millis.is_finite() || millis.abs() >= i64::MAX as f64 {
+                    return None;
+                }
+                datetime.checked_add_signed(Duration::try_milliseconds(millis as i64)?)
+            }
+        }
+    };
+    to_value(compute())
+}
+
+/// Returns the number of whole units of the third argument from the timestamp of the second
+/// argument to the timestamp of the first argument, e.g. the age of an account in days with
+/// `{"date_diff": [{"now": []}, {"var": "created"}, "days"]}`. The result is negative if the first
+/// timestamp is earlier.
+pub fn date_diff(args: &[Value], _data: &Data) -> Value {
+    let compute = || {
+        let a = datetime_arg(args, 0)?;
+        let b = datetime_arg(args, 1)?;
+        let unit = Unit::parse(args.get(2)?)?;
+        match unit.length() {
+            Length::Months(months) => Some(diff_months(with_offset(a, *b.offset())?, b)? / months),
+            Length::Millis(millis) => Some((a - b).num_milliseconds() / millis),
+        }
+    };
+    compute().map_or(Value::Null, Value::from)
+}
+
+/// The number of whole months from `b` to `a`.
+fn diff_months(a: DateTime<FixedOffset>, b: DateTime<FixedOffset>) -> Option<i64> {
+    let months = |datetime: DateTime<FixedOffset>| {
+        i64::from(datetime.year()) * 12 + i64::from(datetime.month0())
+    };
+    let mut diff = months(a) - months(b);
+    // The last month is not complete if adding the months to `b` goes past `a`.
+    let shifted = add_months(b, diff)?;
+    if diff > 0 && shifted > a {
+        diff -= 1;
+    } else if diff < 0 && shifted < a {
+        diff += 1;
+    }
+    Some(diff)
+}
+
+/// Truncates the timestamp to the start of the unit of the second argument, e.g. to the first day
+/// of the month with `{"date_trunc": [{"var": "date"}, "month"]}`. Weeks start on Monday. The
+/// timestamp is truncated in its own offset, unless a time zone is passed as the third argument,
+/// in which the start of the unit may have another offset than the timestamp, see
+/// `Zone::resolve_local`.
+pub fn date_trunc(args: &[Value], _data: &Data) -> Value {
+    let compute = || {
+        let (datetime, zone) = datetime_arg_in_zone(args, 0, 2)?;
+        let unit = Unit::parse(args.get(1)?)?;
+        let date = datetime.date_naive();
+        let time = datetime.time();
+        let naive = match unit {
+            Unit::Year => date.with_day(1)?.with_month(1)?.and_hms_opt(0, 0, 0)?,
+            Unit::Month => date.with_day(1)?.and_hms_opt(0, 0, 0)?,
+            Unit::Week => date
+                .checked_sub_signed(Duration::days(i64::from(
+                    date.weekday().num_days_from_monday(),
+                )))?
+                .and_hms_opt(0, 0, 0)?,
+            Unit::Day => date.and_hms_opt(0, 0, 0)?,
+            Unit::Hour => date.and_hms_opt(time.hour(), 0, 0)?,
+            Unit::Minute => date.and_hms_opt(time.hour(), time.minute(), 0)?,
+            Unit::Second => date.and_hms_opt(time.hour(), time.minute(), time.second())?,
+            Unit::Millisecond => date.and_hms_milli_opt(
+                time.hour(),
+                time.minute(),
+                time.second(),
+                time.nanosecond() / 1_000_000,
+            )?,
+        };
+        zone.resolve_local(&naive, *datetime.offset())
+    };
+    to_value(compute())
+}
+
+/// Extracts a part of the timestamp, named by the second argument: `year`, `month`, `day`,
+/// `weekday` (1 for Monday to 7 for Sunday), `day_of_year`, `hour`, `minute`, `second` or
+/// `millisecond`. The part is taken in the offset of the timestamp, unless a time zone is passed
+/// as the third argument, e.g. `{"date_part": [{"var": "date"}, "hour", "America/New_York"]}`.
+pub fn date_part(args: &[Value], _data: &Data) -> Value {
+    let compute = || {
+        let (datetime, _) = datetime_arg_in_zone(args, 0, 2)?;
+        let part = match logic::coerce_to_str(args.get(1)?).as_str() {
+            "year" => datetime.year(),
+            "month" => datetime.month() as i32,
+            "day" => datetime.day() as i32,
+            "weekday" => datetime.weekday().number_from_monday() as i32,
+            "day_of_year" => datetime.ordinal() as i32,
+            "hour" => datetime.hour() as i32,
+            "minute" => datetime.minute() as i32,
+            "second" => datetime.second() as i32,
+            "millisecond" => (datetime.nanosecond() / 1_000_000) as i32,
+            _ => return None,
+        };
+        Some(part)
+    };
+    compute().map_or(Value::Null, Value::from)
+}
+
+/// Tests whether the first timestamp is before the second one. Returns `false` if one argument is
+/// not a timestamp.
+pub fn date_before(args: &[Value], _data: &Data) -> Value {
+    let a = datetime_arg(args, 0);
+    let b = datetime_arg(args, 1);
+    Value::Bool(matches!((a, b), (Some(a), Some(b)) if a < b))
+}
+
+/// Tests whether the first timestamp is after the second one. Returns `false` if one argument is
+/// not a timestamp.
+pub fn date_after(args: &[Value], _data: &Data) -> Value {
+    let a = datetime_arg(args, 0);
+    let b = datetime_arg(args, 1);
+    Value::Bool(matches!((a, b), (Some(a), Some(b)) if a > b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Rule;
+    use serde_json::json;
+    use std::time::{Duration, SystemTime};
+
+    fn apply(json_logic: Value, data: Value) -> Value {
+        crate::apply(&json_logic, &data).unwrap()
+    }
+
+    #[test]
+    fn coercion() {
+        let expected = "2024-03-31T10:30:00Z";
+        for value in [
+            json!("2024-03-31T10:30:00Z"),
+            json!("2024-03-31T12:30:00+02:00"),
+            json!(" 2024-03-31T10:30:00 "),
+            json!("2024-03-31T10:30"),
+            json!(["2024-03-31T10:30:00.000Z"]),
+            json!(1711881000000u64),
+        ]
+        .iter()
+        {
+            assert_eq!(
+                coerce_to_datetime(value).map(|datetime| datetime.with_timezone(&Utc)),
+                Some(expected.parse::<DateTime<Utc>>().unwrap()),
+                "{}",
+                value
+            );
+        }
+        assert_eq!(
+            apply(json!({"datetime": "2024-03-31"}), json!(null)),
+            json!("2024-03-31T00:00:00Z")
+        );
+        assert_eq!(
+            apply(
+                json!({"datetime": ["2024-03-31T23:30:00Z", "+02:00"]}),
+                json!(null)
+            ),
+            json!("2024-04-01T01:30:00+02:00")
+        );
+        for value in [
+            json!("31.03.2024"),
+            json!(null),
+            json!(true),
+            json!([]),
+            json!({}),
+        ]
+        .iter()
+        {
+            assert_eq!(coerce_to_datetime(value), None, "{}", value);
+        }
+        assert_eq!(
+            apply(json!({"datetime": ["2024-03-31", "Berlin"]}), json!(null)),
+            Value::Null
+        );
+    }
+
+    #[test]
+    fn now() {
+        let json_logic = json!({"now": []});
+        let rule = Rule::compile(&json_logic).unwrap();
+        let clock = || SystemTime::UNIX_EPOCH + Duration::from_millis(1711881000500);
+        assert_eq!(
+            rule.apply_with_clock(&json!(null), &clock),
+            Ok(json!("2024-03-31T10:30:00.500Z"))
+        );
+
+        // The clock is also used inside of iterations.
+        let json_logic =
+            json!({"map": [[1, 2], {"date_diff": [{"now": []}, "2024-03-01", "days"]}]});
+        let rule = Rule::compile(&json_logic).unwrap();
+        assert_eq!(
+            rule.apply_with_clock(&json!(null), &clock),
+            Ok(json!([30, 30]))
+        );
+    }
+
+    #[test]
+    fn date_add() {
+        let data = json!({"date": "2024-01-31T12:00:00+01:00"});
+        let add = |amount: Value, unit: &str| {
+            apply(
+                json!({"date_add": [{"var": "date"}, amount, unit]}),
+                data.clone(),
+            )
+        };
+        assert_eq!(add(json!(30), "days"), json!("2024-03-01T12:00:00+01:00"));
+        assert_eq!(add(json!(-1), "day"), json!("2024-01-30T12:00:00+01:00"));
+        assert_eq!(add(json!(1.5), "hours"), json!("2024-01-31T13:30:00+01:00"));
+        assert_eq!(add(json!("2"), "weeks"), json!("2024-02-14T12:00:00+01:00"));
+        assert_eq!(add(json!(1), "month"), json!("2024-02-29T12:00:00+01:00"));
+        assert_eq!(
+            add(json!(-13), "months"),
+            json!("2022-12-31T12:00:00+01:00")
+        );
+        assert_eq!(add(json!(1), "year"), json!("2025-01-31T12:00:00+01:00"));
+        assert_eq!(
+            add(json!(250), "milliseconds"),
+            json!("2024-01-31T12:00:00.250+01:00")
+        );
+        assert_eq!(add(json!(1), "fortnight"), Value::Null);
+        assert_eq!(add(json!("a"), "days"), Value::Null);
+        assert_eq!(add(json!(1e300), "days"), Value::Null);
+        assert_eq!(add(json!(1e18), "years"), Value::Null);
+    }
+
+    #[test]
+    fn date_diff() {
+        let diff =
+            |a: &str, b: &str, unit: &str| apply(json!({"date_diff": [a, b, unit]}), json!(null));
+        assert_eq!(diff("2024-03-31", "2024-03-01", "days"), json!(30));
+        assert_eq!(diff("2024-03-01", "2024-03-31", "days"), json!(-30));
+        assert_eq!(diff("2024-03-01T23:59:59Z", "2024-03-01", "days"), json!(0));
+        assert_eq!(
+            diff("2024-03-01T12:00:00+02:00", "2024-03-01T12:00:00Z", "hours"),
+            json!(-2)
+        );
+        assert_eq!(diff("2024-03-30", "2024-01-31", "months"), json!(1));
+        assert_eq!(diff("2024-03-31", "2024-01-31", "months"), json!(2));
+        assert_eq!(diff("2024-01-31", "2024-03-31", "months"), json!(-2));
+        assert_eq!(diff("2024-01-30", "2024-03-31", "months"), json!(-2));
+        assert_eq!(diff("2024-03-31", "2021-04-01", "years"), json!(2));
+        assert_eq!(diff("2024-03-31", "invalid", "years"), Value::Null);
+    }
+
+    #[test]
+    fn date_trunc() {
+        let trunc = |unit: &str| {
+            apply(
+                json!({"date_trunc": ["2024-03-27T15:45:30.250+02:00", unit]}),
+                json!(null),
+            )
+        };
+        assert_eq!(trunc("year"), json!("2024-01-01T00:00:00+02:00"));
+        assert_eq!(trunc("month"), json!("2024-03-01T00:00:00+02:00"));
+        assert_eq!(trunc("week"), json!("2024-03-25T00:00:00+02:00"));
+        assert_eq!(trunc("day"), json!("2024-03-27T00:00:00+02:00"));
+        assert_eq!(trunc("hour"), json!("2024-03-27T15:00:00+02:00"));
+        assert_eq!(trunc("minute"), json!("2024-03-27T15:45:00+02:00"));
+        assert_eq!(trunc("second"), json!("2024-03-27T15:45:30+02:00"));
+        assert_eq!(trunc("millisecond"), json!("2024-03-27T15:45:30.250+02:00"));
+        assert_eq!(trunc("quarter"), Value::Null);
+        assert_eq!(
+            apply(
+                json!({"date_trunc": ["2024-03-31T23:30:00Z", "day", "+02:00"]}),
+                json!(null)
+            ),
+            json!("2024-04-01T00:00:00+02:00")
+        );
+    }
+
+    #[test]
+    fn date_part() {
+        let part = |part: &str| {
+            apply(
+                json!({"date_part": ["2024-03-31T23:30:15.5Z", part]}),
+                json!(null),
+            )
+        };
+        assert_eq!(part("year"), json!(2024));
+        assert_eq!(part("month"), json!(3));
+        assert_eq!(part("day"), json!(31));
+        assert_eq!(part("weekday"), json!(7));
+        assert_eq!(part("day_of_year"), json!(91));
+        assert_eq!(part("hour"), json!(23));
+        assert_eq!(part("minute"), json!(30));
+        assert_eq!(part("second"), json!(15));
+        assert_eq!(part("millisecond"), json!(500));
+        assert_eq!(part("century"), Value::Null);
+        assert_eq!(
+            apply(
+                json!({"date_part": ["2024-03-31T23:30:00Z", "weekday", "+02:00"]}),
+                json!(null)
+            ),
+            json!(1)
+        );
+    }
+
+    #[test]
+    fn time_zones() {
+        let datetime =
+            |datetime: &str, zone: &str| apply(json!({"datetime": [datetime, zone]}), json!(null));
+        // Daylight saving time starts at 01:00 UTC in Berlin.
+        assert_eq!(
+            datetime("2024-03-31T00:30:00Z", "Europe/Berlin"),
+            json!("2024-03-31T01:30:00+01:00")
+        );
+        assert_eq!(
+            datetime("2024-03-31T01:30:00Z", "Europe/Berlin"),
+            json!("2024-03-31T03:30:00+02:00")
+        );
+        assert_eq!(
+            datetime("2024-03-31T01:30:00Z", "Mars/Olympus"),
+            Value::Null
+        );
+        assert_eq!(datetime("2024-03-31T01:30:00Z", "utc"), Value::Null);
+    }
+
+    #[test]
+    fn date_part_across_daylight_saving_time() {
+        let part = |datetime: &str, part: &str, zone: &str| {
+            apply(json!({"date_part": [datetime, part, zone]}), json!(null))
+        };
+        assert_eq!(
+            part("2024-03-31T00:59:00Z", "hour", "Europe/Berlin"),
+            json!(1)
+        );
+        assert_eq!(
+            part("2024-03-31T01:00:00Z", "hour", "Europe/Berlin"),
+            json!(3)
+        );
+        assert_eq!(
+            part("2024-10-27T00:30:00Z", "hour", "Europe/Berlin"),
+            json!(2)
+        );
+        assert_eq!(
+            part("2024-10-27T01:30:00Z", "hour", "Europe/Berlin"),
+            json!(2)
+        );
+        assert_eq!(
+            part("2024-10-27T02:30:00Z", "hour", "Europe/Berlin"),
+            json!(3)
+        );
+
+        // Daylight saving time ends at 06:00 UTC in New York, so 04:30 UTC on the next day is
+        // still Sunday there, which it would not be with the summer offset.
+        assert_eq!(
+            part("2024-11-04T04:30:00Z", "weekday", "America/New_York"),
+            json!(7)
+        );
+        assert_eq!(part("2024-11-04T04:30:00Z", "weekday", "-04:00"), json!(1));
+        assert_eq!(
+            part("2024-03-10T04:30:00Z", "weekday", "America/New_York"),
+            json!(6)
+        );
+        assert_eq!(
+            part("2024-03-11T03:30:00Z", "weekday", "America/New_York"),
+            json!(7)
+        );
+    }
+
+    #[test]
+    fn date_trunc_across_daylight_saving_time() {
+        let trunc = |datetime: &str, unit: &str, zone: &str| {
+            apply(json!({"date_trunc": [datetime, unit, zone]}), json!(null))
+        };
+        // The day starts with the winter offset on the day daylight saving time starts and with
+        // the summer offset on the day it ends.
+        assert_eq!(
+            trunc("2024-03-31T12:00:00Z", "day", "Europe/Berlin"),
+            json!("2024-03-31T00:00:00+01:00")
+        );
+        assert_eq!(
+            trunc("2024-10-27T12:00:00Z", "day", "Europe/Berlin"),
+            json!("2024-10-27T00:00:00+02:00")
+        );
+        // Daylight saving time starts at midnight in Santiago, so the day starts at 01:00.
+        assert_eq!(
+            trunc("2024-09-08T12:00:00Z", "day", "America/Santiago"),
+            json!("2024-09-08T01:00:00-03:00")
+        );
+        assert_eq!(
+            trunc("2024-04-15T12:00:00Z", "month", "Europe/Berlin"),
+            json!("2024-04-01T00:00:00+02:00")
+        );
+        assert_eq!(
+            trunc("2024-11-15T12:00:00Z", "month", "Europe/Berlin"),
+            json!("2024-11-01T00:00:00+01:00")
+        );
+        assert_eq!(
+            trunc("2024-03-31T22:30:00Z", "month", "Europe/Berlin"),
+            json!("2024-04-01T00:00:00+02:00")
+        );
+        // 01:45 occurs twice in New York when daylight saving time ends, the hour starts with
+        // the offset of the time.
+        assert_eq!(
+            trunc("2024-11-03T05:45:00Z", "hour", "America/New_York"),
+            json!("2024-11-03T01:00:00-04:00")
+        );
+        assert_eq!(
+            trunc("2024-11-03T06:45:00Z", "hour", "America/New_York"),
+            json!("2024-11-03T01:00:00-05:00")
+        );
+    }
+
+    #[test]
+    fn comparisons() {
+        let data = json!({"a": "2024-03-31T12:00:00+02:00", "b": "2024-03-31T11:00:00Z"});
+        assert_eq!(
+            apply(
+                json!({"date_before": [{"var": "a"}, {"var": "b"}]}),
+                data.clone()
+            ),
+            json!(true)
+        );
+        assert_eq!(
+            apply(
+                json!({"date_after": [{"var": "a"}, {"var": "b"}]}),
+                data.clone()
+            ),
+            json!(false)
+        );
+        assert_eq!(
+            apply(json!({"date_after": [{"var": "a"}, "invalid"]}), data),
+            json!(false)
+        );
+    }
+}
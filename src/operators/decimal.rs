@@ -0,0 +1,279 @@
+//! The numeric operators for the `decimal` feature, which compute with decimals instead of `f64`.
+//! They coerce their arguments the same way as the `f64` versions do. Results that are not
+//! representable as a decimal, like a division by zero or an overflow, are `Value::Null`.
+
+use rust_decimal::{Decimal, RoundingStrategy};
+use serde_json::{Number, Value};
+use std::str::FromStr;
+
+use super::{logic, Data};
+
+/// Converts a JSON number into a decimal. The number is parsed from its shortest text that
+/// converts back to the same `f64`, which is the text it was written as if it has no more than 15
+/// significant digits. With the `arbitrary_precision` feature, the number is parsed from its
+/// original text and therefore never rounded through `f64`.
+pub fn from_number(num: &Number) -> Option<Decimal> {
+    parse(&num.to_string())
+}
+
+fn parse(s: &str) -> Option<Decimal> {
+    if s.contains(['e', 'E']) {
+        Decimal::from_scientific(s).ok()
+    } else {
+        Decimal::from_str(s).ok()
+    }
+}
+
+/// Like `logic::coerce_to_f64`, but coerces into a decimal.
+pub fn coerce_to_decimal(val: &Value) -> Option<Decimal> {
+    match val {
+        Value::Array(arr) => match &arr[..] {
+            [] => Some(Decimal::ZERO),
+            [el] => match el {
+                Value::Array(_) | Value::Null | Value::Number(_) | Value::String(_) => {
+                    coerce_to_decimal(el)
+                }
+                _ => None,
+            },
+            _ => None,
+        },
+        Value::Bool(true) => Some(Decimal::ONE),
+        Value::Bool(false) | Value::Null => Some(Decimal::ZERO),
+        Value::Number(num) => from_number(num),
+        Value::Object(_) => None,
+        Value::String(s) => {
+            let s = s.trim();
+            if s.is_empty() {
+                Some(Decimal::ZERO)
+            } else {
+                parse(s)
+            }
+        }
+    }
+}
+
+/// Like `logic::parse_float`, but parses into a decimal.
+pub fn parse_decimal(val: &Value) -> Option<Decimal> {
+    match val {
+        Value::Number(num) => from_number(num),
+        Value::String(s) => parse(logic::float_prefix(s)),
+        _ => None,
+    }
+}
+
+/// Converts the decimal into a JSON number, without trailing zeros after the decimal point.
+fn to_value(decimal: Option<Decimal>) -> Value {
+    decimal
+        .and_then(|decimal| Number::from_str(&decimal.normalize().to_string()).ok())
+        .map_or(Value::Null, Value::Number)
+}
+
+/// Like `math::round`, but rounds the decimal, which has no binary representation that could be
+/// slightly less than a half.
+pub fn round(args: &[Value], _data: &Data) -> Value {
+    let num = args.first().and_then(coerce_to_decimal);
+    // The places are a count, not an amount, so they are coerced like in the `f64` version.
+    let places = match args.get(1) {
+        Some(arg) => logic::coerce_to_f64(arg).map(|places| places.trunc() as i64),
+        None => Some(0),
+    };
+    match (num, places) {
+        (Some(num), Some(places)) => to_value(round_half_away_from_zero(num, places)),
+        _ => Value::Null,
+    }
+}
+
+/// Rounds the decimal to the places, where negative places round to tens, hundreds and so on.
+/// Returns `None` if the result is too large for a decimal.
+fn round_half_away_from_zero(num: Decimal, places: i64) -> Option<Decimal> {
+    let strategy = RoundingStrategy::MidpointAwayFromZero;
+    if places >= 0 {
+        // Decimals have at most 28 places.
+        return Some(num.round_dp_with_strategy(places.min(28) as u32, strategy));
+    }
+    // Decimals are less than 10^29, so rounding to more places than that is always 0.
+    let shift = places.unsigned_abs().min(30);
+    let mut shifted = num;
+    for _ in 0..shift {
+        shifted /= Decimal::TEN;
+    }
+    let mut rounded = shifted.round_dp_with_strategy(0, strategy);
+    if rounded.is_zero() {
+        return Some(Decimal::ZERO);
+    }
+    for _ in 0..shift {
+        rounded = rounded.checked_mul(Decimal::TEN)?;
+    }
+    Some(rounded)
+}
+
+/// Like `types::to_number`, but converts into a decimal, so `"0.1"` is not rounded through `f64`.
+/// Returns `Value::Null` if the argument cannot be converted.
+pub fn to_number(args: &[Value], _data: &Data) -> Value {
+    match args.first().unwrap_or(&Value::Null) {
+        num @ Value::Number(_) => num.clone(),
+        value => to_value(coerce_to_decimal(value)),
+    }
+}
+
+/// Like `types::parse_float`, but parses into a decimal.
+pub fn parse_float(args: &[Value], _data: &Data) -> Value {
+    match args.first().unwrap_or(&Value::Null) {
+        num @ Value::Number(_) => num.clone(),
+        value => to_value(parse_decimal(value)),
+    }
+}
+
+/// The two arguments coerced into decimals.
+fn two_args(args: &[Value]) -> Option<(Decimal, Decimal)> {
+    let a = coerce_to_decimal(args.first()?)?;
+    let b = coerce_to_decimal(args.get(1)?)?;
+    Some((a, b))
+}
+
+pub fn addition(args: &[Value], _data: &Data) -> Value {
+    let mut result = Some(Decimal::ZERO);
+    for arg in args {
+        // Like the `f64` version, this uses parseFloat.
+        match parse_decimal(arg) {
+            Some(num) => result = result.and_then(|result| result.checked_add(num)),
+            None => return Value::Null,
+        }
+    }
+    to_value(result)
+}
+
+pub fn subtraction(args: &[Value], _data: &Data) -> Value {
+    match args {
+        [] => Value::Null,
+        [a] => to_value(coerce_to_decimal(a).map(|a| -a)),
+        _ => to_value(two_args(args).and_then(|(a, b)| a.checked_sub(b))),
+    }
+}
+
+pub fn multiplication(args: &[Value], _data: &Data) -> Value {
+    match args {
+        [] => Value::Null,
+        [arg] => arg.clone(),
+        _ => {
+            let mut result = Some(Decimal::ONE);
+            for arg in args {
+                match parse_decimal(arg) {
+                    Some(num) => result = result.and_then(|result| result.checked_mul(num)),
+                    None => return Value::Null,
+                }
+            }
+            to_value(result)
+        }
+    }
+}
+
+pub fn division(args: &[Value], _data: &Data) -> Value {
+    to_value(two_args(args).and_then(|(a, b)| a.checked_div(b)))
+}
+
+pub fn modulo(args: &[Value], _data: &Data) -> Value {
+    to_value(two_args(args).and_then(|(a, b)| a.checked_rem(b)))
+}
+
+pub fn min(args: &[Value], _data: &Data) -> Value {
+    to_value(extremum(args, Decimal::min))
+}
+
+pub fn max(args: &[Value], _data: &Data) -> Value {
+    to_value(extremum(args, Decimal::max))
+}
+
+fn extremum(args: &[Value], pick: fn(Decimal, Decimal) -> Decimal) -> Option<Decimal> {
+    let mut result = None;
+    for arg in args {
+        let num = coerce_to_decimal(arg)?;
+        result = Some(result.map_or(num, |result| pick(result, num)));
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn apply(json_logic: &str) -> Value {
+        let json_logic: Value = serde_json::from_str(json_logic).unwrap();
+        crate::apply(&json_logic, &Value::Null).unwrap()
+    }
+
+    fn number(s: &str) -> Value {
+        Value::Number(Number::from_str(s).unwrap())
+    }
+
+    #[test]
+    fn exact_arithmetic() {
+        assert_eq!(apply(r#"{"+": [0.1, 0.2]}"#), number("0.3"));
+        assert_eq!(apply(r#"{"==": [{"+": [0.1, 0.2]}, 0.3]}"#), json!(true));
+        assert_eq!(apply(r#"{"-": [0.3, "0.1"]}"#), number("0.2"));
+        assert_eq!(apply(r#"{"*": [19.99, 3]}"#), number("59.97"));
+        assert_eq!(apply(r#"{"/": [1, 4]}"#), number("0.25"));
+        assert_eq!(apply(r#"{"%": [5.5, 2]}"#), number("1.5"));
+        assert_eq!(apply(r#"{"+": [4, 2]}"#), number("6"));
+        assert_eq!(apply(r#"{"-": [2]}"#), number("-2"));
+        assert_eq!(apply(r#"{"min": [0.3, "0.25", [0.4]]}"#), number("0.25"));
+        assert_eq!(apply(r#"{"max": [0.3, true]}"#), number("1"));
+    }
+
+    #[test]
+    #[cfg(feature = "arbitrary_precision")]
+    fn precise_literals() {
+        assert_eq!(
+            apply(r#"{"+": [12345678901234567890.12, 0.01]}"#),
+            number("12345678901234567890.13")
+        );
+        assert_eq!(
+            apply(r#"{"<": [0.30000000000000000001, 0.3]}"#),
+            json!(false)
+        );
+        assert_eq!(
+            apply(r#"{"==": [0.30000000000000000001, "0.3"]}"#),
+            json!(false)
+        );
+        assert_eq!(apply(r#"{"==": [1.50, "1.5"]}"#), json!(true));
+        assert_eq!(apply(r#"{"===": [1.50, 1.5]}"#), json!(true));
+        assert_eq!(apply(r#"{"+": [1e2, "2.5e1"]}"#), number("125"));
+    }
+
+    #[test]
+    fn rounding() {
+        assert_eq!(apply(r#"{"round": [2.675, 2]}"#), number("2.68"));
+        assert_eq!(apply(r#"{"round": ["1.005", 2]}"#), number("1.01"));
+        assert_eq!(apply(r#"{"round": [-2.5]}"#), number("-3"));
+        assert_eq!(apply(r#"{"round": [1250, -2]}"#), number("1300"));
+        assert_eq!(apply(r#"{"round": [-1250, -2]}"#), number("-1300"));
+        assert_eq!(apply(r#"{"round": [49, -2]}"#), number("0"));
+        assert_eq!(apply(r#"{"round": [5, -40]}"#), number("0"));
+        assert_eq!(apply(r#"{"round": [0.125, 40]}"#), number("0.125"));
+        assert_eq!(apply(r#"{"round": ["a"]}"#), Value::Null);
+        assert_eq!(apply(r#"{"round": [1, "a"]}"#), Value::Null);
+    }
+
+    #[test]
+    fn conversions() {
+        assert_eq!(
+            apply(r#"{"+": [{"to_number": "0.1"}, {"to_number": "0.2"}]}"#),
+            number("0.3")
+        );
+        assert_eq!(apply(r#"{"to_number": " -0 "}"#), number("0"));
+        assert_eq!(apply(r#"{"to_number": [["7"]]}"#), number("7"));
+        assert_eq!(apply(r#"{"to_number": "12px"}"#), Value::Null);
+        assert_eq!(apply(r#"{"parse_float": "0.10px"}"#), number("0.1"));
+        assert_eq!(apply(r#"{"parse_float": "px"}"#), Value::Null);
+    }
+
+    #[test]
+    fn not_representable() {
+        assert_eq!(apply(r#"{"/": [1, 0]}"#), Value::Null);
+        assert_eq!(apply(r#"{"%": [1, 0]}"#), Value::Null);
+        assert_eq!(apply(r#"{"+": ["a", 1]}"#), Value::Null);
+        assert_eq!(apply(r#"{"*": [1e28, 1e28]}"#), Value::Null);
+        assert_eq!(apply(r#"{"min": []}"#), Value::Null);
+    }
+}
@@ -1,32 +1,21 @@
-use serde_json::{Number, Value};
+use serde_json::Value;
 
-use super::{logic, Data, Expression};
+use super::{logic, Data};
 
 /// "/", takes two arguments that are coerced into numbers. Returns `Value::Null` if the divisor is
-/// coerced to `0` or one argument cannot be coerced into a number.
-pub fn compute(args: &[Expression], data: &Data) -> Value {
-    let a = match args
-        .get(0)
-        .map(|arg| arg.compute(data))
-        .and_then(|a| logic::coerce_to_f64(&a))
-    {
+/// coerced to `0`, one argument cannot be coerced into a number or the quotient is not finite.
+pub fn compute(args: &[Value], _data: &Data) -> Value {
+    let a = match args.get(0).and_then(logic::coerce_to_f64) {
         Some(a) => a,
         None => return Value::Null,
     };
 
-    let b = match args
-        .get(1)
-        .map(|arg| arg.compute(data))
-        .and_then(|b| logic::coerce_to_f64(&b))
-    {
+    let b = match args.get(1).and_then(logic::coerce_to_f64) {
         Some(b) => b,
         None => return Value::Null,
     };
 
-    match Number::from_f64(a / b) {
-        Some(num) => Value::Number(num),
-        None => Value::Null,
-    }
+    logic::f64_to_value(a / b)
 }
 
 #[cfg(test)]
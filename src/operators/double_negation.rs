@@ -1,21 +1,19 @@
-use serde_json::{json, Value};
+use serde_json::Value;
 
-use super::{logic, Data, Expression};
+use super::{logic, Data};
 
 /// Double negation, or "cast to a boolean". Takes a single argument.
-pub fn compute(args: &[Expression], data: &Data) -> Value {
-    let a = args
-        .get(0)
-        .map(|arg| arg.compute(data))
-        .unwrap_or(json!(null));
+pub fn compute(args: &[Value], _data: &Data) -> Value {
+    let a = args.get(0).unwrap_or(&Value::Null);
 
-    Value::Bool(logic::is_truthy(&a))
+    Value::Bool(logic::is_truthy(a))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::compute_const;
+    use serde_json::json;
 
     #[test]
     fn test() {
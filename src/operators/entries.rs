@@ -0,0 +1,43 @@
+use serde_json::Value;
+
+use super::Data;
+
+/// Returns the key-value pairs of the object of the first argument as arrays of two elements,
+/// like `Object.entries` in javascript. The keys of arrays are their indices as strings. Returns
+/// an empty array for any other value.
+pub fn compute(args: &[Value], _data: &Data) -> Value {
+    let entries = match args.first() {
+        Some(Value::Object(obj)) => obj
+            .iter()
+            .map(|(key, value)| Value::Array(vec![Value::String(key.clone()), value.clone()]))
+            .collect(),
+        Some(Value::Array(arr)) => arr
+            .iter()
+            .enumerate()
+            .map(|(index, value)| {
+                Value::Array(vec![Value::String(index.to_string()), value.clone()])
+            })
+            .collect(),
+        _ => vec![],
+    };
+
+    Value::Array(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute_const;
+    use serde_json::json;
+
+    #[test]
+    fn test() {
+        assert_eq!(compute_const!(), json!([]));
+        assert_eq!(compute_const!(json!(null)), json!([]));
+        assert_eq!(
+            compute_const!(json!({"b": 1, "a": {"c": 2}})),
+            json!([["a", {"c": 2}], ["b", 1]])
+        );
+        assert_eq!(compute_const!(json!(["x"])), json!([["0", "x"]]));
+    }
+}
@@ -9,8 +9,8 @@ use super::{logic, Data, Expression};
 /// Also note, the returned array will have contiguous indexes starting at zero (typical for
 /// JavaScript, Python and Ruby) it will not preserve the source indexes (making it unlike
 /// PHP’s array_filter).
-pub fn compute(args: &[Expression], data: &Data) -> Value {
-    let arr = match args.get(0).map(|arg| arg.compute(data)) {
+pub fn compute(args: &[Expression], data: &Data) -> Result<Value, Value> {
+    let arr = match args.get(0).map(|arg| arg.compute(data)).transpose()? {
         Some(Value::Array(arr)) => arr,
         _ => Vec::with_capacity(0),
     };
@@ -21,11 +21,11 @@ pub fn compute(args: &[Expression], data: &Data) -> Value {
 
     let mut result = Vec::new();
     for elem in arr.iter() {
-        let include = op.compute(&Data::from_json(elem));
+        let include = op.compute(&data.nested(elem))?;
         if logic::is_truthy(&include) {
             result.push(elem.clone());
         }
     }
 
-    Value::Array(result)
+    Ok(Value::Array(result))
 }
@@ -0,0 +1,54 @@
+use serde_json::Value;
+
+use super::{logic, Data, Expression};
+
+/// Takes an array as the first argument and a condition as the second argument. Returns the
+/// first element for which the condition evaluates to a truthy value, or `Value::Null` if there
+/// is none.
+///
+/// `var` operations inside the second argument expression are relative to the array element
+/// being tested.
+pub fn compute(args: &[Expression], data: &Data) -> Result<Value, Value> {
+    let arr = match args.first().map(|arg| arg.compute(data)).transpose()? {
+        Some(Value::Array(arr)) => arr,
+        _ => return Ok(Value::Null),
+    };
+    let condition = match args.get(1) {
+        Some(expr) => expr,
+        None => return Ok(Value::Null),
+    };
+
+    for elem in arr.into_iter() {
+        if logic::is_truthy(&condition.compute(&data.nested(&elem))?) {
+            return Ok(elem);
+        }
+    }
+    Ok(Value::Null)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute_lazy;
+    use serde_json::json;
+
+    #[test]
+    fn test() {
+        assert_eq!(compute_lazy!(), Ok(Value::Null));
+        assert_eq!(compute_lazy!(json!([1, 2])), Ok(Value::Null));
+        assert_eq!(compute_lazy!(json!([0, 2]), json!(true)), Ok(json!(0)));
+
+        let rule = json!({"find": [{"var": "items"}, {">": [{"var": "price"}, 10]}]});
+        assert_eq!(
+            crate::apply(
+                &rule,
+                &json!({"items": [{"price": 5}, {"price": 15}, {"price": 20}]})
+            ),
+            Ok(json!({"price": 15}))
+        );
+        assert_eq!(
+            crate::apply(&rule, &json!({"items": [{"price": 5}]})),
+            Ok(Value::Null)
+        );
+    }
+}
@@ -0,0 +1,53 @@
+use serde_json::Value;
+
+use super::{logic, Data};
+
+/// Flattens the nested arrays of the array of the first argument into it, as deep as the second
+/// argument, which defaults to 1. Returns an empty array if the first argument is not an array.
+pub fn compute(args: &[Value], _data: &Data) -> Value {
+    let arr = match args.first().cloned() {
+        Some(Value::Array(arr)) => arr,
+        _ => return Value::Array(vec![]),
+    };
+    let depth = args.get(1).and_then(logic::coerce_to_f64).unwrap_or(1f64);
+
+    let mut result = Vec::with_capacity(arr.len());
+    // Negative depths and NaN saturate to 0.
+    flatten(arr, depth as usize, &mut result);
+    Value::Array(result)
+}
+
+fn flatten(arr: Vec<Value>, depth: usize, result: &mut Vec<Value>) {
+    for elem in arr {
+        match elem {
+            Value::Array(nested) if depth > 0 => flatten(nested, depth - 1, result),
+            elem => result.push(elem),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute_const;
+    use serde_json::json;
+
+    #[test]
+    fn test() {
+        assert_eq!(compute_const!(), json!([]));
+        assert_eq!(compute_const!(json!(1)), json!([]));
+        assert_eq!(
+            compute_const!(json!([1, [2, [3, [4]]], []])),
+            json!([1, 2, [3, [4]]])
+        );
+        assert_eq!(
+            compute_const!(json!([1, [2, [3, [4]]]]), json!(2)),
+            json!([1, 2, 3, [4]])
+        );
+        assert_eq!(
+            compute_const!(json!([1, [2, [3, [4]]]]), json!(1e300)),
+            json!([1, 2, 3, 4])
+        );
+        assert_eq!(compute_const!(json!([1, [2]]), json!(-1)), json!([1, [2]]));
+    }
+}
@@ -0,0 +1,53 @@
+use serde_json::Value;
+
+use super::{logic, Data};
+
+/// Gets the value of the key of the second argument, coerced into a string, from the object of
+/// the first argument, or the element at an index from an array. Unlike `var`, the key is not
+/// split at dots, so it can be any key computed by the rule. Returns the third argument, or
+/// `Value::Null`, if there is no such value.
+pub fn compute(args: &[Value], _data: &Data) -> Value {
+    let value = match (args.first(), args.get(1)) {
+        (Some(container), Some(key)) => match container {
+            Value::Object(obj) => obj.get(&logic::coerce_to_str(key)),
+            Value::Array(arr) => logic::coerce_to_str(key)
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| arr.get(index)),
+            _ => None,
+        },
+        _ => None,
+    };
+
+    value
+        .or_else(|| args.get(2))
+        .cloned()
+        .unwrap_or(Value::Null)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute_const;
+    use serde_json::json;
+
+    #[test]
+    fn test() {
+        assert_eq!(compute_const!(), Value::Null);
+        assert_eq!(compute_const!(json!({"a": 1})), Value::Null);
+        assert_eq!(compute_const!(json!({"a": 1}), json!("a")), json!(1));
+        assert_eq!(compute_const!(json!({"a.b": 1}), json!("a.b")), json!(1));
+        assert_eq!(compute_const!(json!({"1": "x"}), json!(1)), json!("x"));
+        assert_eq!(compute_const!(json!({"a": 1}), json!("b")), Value::Null);
+        assert_eq!(
+            compute_const!(json!({"a": 1}), json!("b"), json!("default")),
+            json!("default")
+        );
+        assert_eq!(compute_const!(json!(["x", "y"]), json!(1)), json!("y"));
+        assert_eq!(
+            compute_const!(json!(["x", "y"]), json!(2), json!(0)),
+            json!(0)
+        );
+        assert_eq!(compute_const!(json!("abc"), json!(0)), Value::Null);
+    }
+}
@@ -1,17 +1,17 @@
 use serde_json::Value;
 
-use super::{logic, Data, Expression};
+use super::{logic, Data};
 
-pub fn compute(args: &[Expression], data: &Data) -> Value {
+pub fn compute(args: &[Value], _data: &Data) -> Value {
     let a = match args.get(0) {
-        Some(arg) => arg.compute(data),
+        Some(arg) => arg,
         None => return Value::Bool(false),
     };
 
     let b = match args.get(1) {
-        Some(arg) => arg.compute(data),
+        Some(arg) => arg,
         None => return Value::Bool(false),
     };
 
-    Value::Bool(logic::greater_equal_than(&a, &b))
+    Value::Bool(logic::greater_equal_than(a, b))
 }
@@ -0,0 +1,69 @@
+use serde_json::{Map, Value};
+
+use super::{logic, Data, Expression};
+
+/// Groups the elements of the array of the first argument by the value of the second argument
+/// for each element, coerced into a string. Returns an object with a key for each group and the
+/// elements of the group in an array, in their order. `var` operations inside the second argument
+/// expression are relative to the element. Returns an empty object if the first argument is not
+/// an array.
+pub fn compute(args: &[Expression], data: &Data) -> Result<Value, Value> {
+    let arr = match args.first().map(|arg| arg.compute(data)).transpose()? {
+        Some(Value::Array(arr)) => arr,
+        _ => return Ok(Value::Object(Map::new())),
+    };
+    let keys = match args.get(1) {
+        Some(key) => arr
+            .iter()
+            .map(|elem| key.compute(&data.nested(elem)))
+            .collect::<Result<_, _>>()?,
+        None => vec![Value::Null; arr.len()],
+    };
+
+    Ok(group(arr, keys))
+}
+
+/// Groups the elements by their keys, which are at the same positions as the elements.
+pub fn group(arr: Vec<Value>, keys: Vec<Value>) -> Value {
+    let mut groups = Map::new();
+    for (key, elem) in keys.iter().zip(arr) {
+        match groups
+            .entry(logic::coerce_to_str(key))
+            .or_insert_with(|| Value::Array(vec![]))
+        {
+            Value::Array(group) => group.push(elem),
+            _ => unreachable!(),
+        }
+    }
+
+    Value::Object(groups)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute_lazy;
+    use serde_json::json;
+
+    #[test]
+    fn test() {
+        assert_eq!(compute_lazy!(), Ok(json!({})));
+        assert_eq!(compute_lazy!(json!([1, 2])), Ok(json!({"null": [1, 2]})));
+        assert_eq!(
+            crate::apply(
+                &json!({"group_by": [{"var": "orders"}, {"var": "status"}]}),
+                &json!({"orders": [
+                    {"id": 1, "status": "open"},
+                    {"id": 2, "status": "paid"},
+                    {"id": 3, "status": "open"},
+                    {"id": 4}
+                ]})
+            ),
+            Ok(json!({
+                "open": [{"id": 1, "status": "open"}, {"id": 3, "status": "open"}],
+                "paid": [{"id": 2, "status": "paid"}],
+                "null": [{"id": 4}]
+            }))
+        );
+    }
+}
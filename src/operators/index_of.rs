@@ -0,0 +1,37 @@
+use serde_json::Value;
+
+use super::{logic, Data};
+
+/// Returns the character index of the first occurrence of the second argument in the first
+/// argument, both coerced into strings, or `-1` if there is none. The index counts characters as
+/// defined by the string mode of the data. Returns `Value::Null` if an argument is missing.
+pub fn compute(args: &[Value], data: &Data) -> Value {
+    let (s, search) = match (args.first(), args.get(1)) {
+        (Some(s), Some(search)) => (logic::coerce_to_str(s), logic::coerce_to_str(search)),
+        _ => return Value::Null,
+    };
+
+    match s.find(&search) {
+        Some(byte_index) => Value::from(data.string_mode().index(&s, byte_index)),
+        None => Value::from(-1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute_const;
+    use serde_json::json;
+
+    #[test]
+    fn test() {
+        assert_eq!(compute_const!(), Value::Null);
+        assert_eq!(compute_const!(json!("jsonlogic")), Value::Null);
+        assert_eq!(compute_const!(json!("jsonlogic"), json!("logic")), json!(4));
+        assert_eq!(compute_const!(json!("jsonlogic"), json!("json")), json!(0));
+        assert_eq!(compute_const!(json!("jsonlogic"), json!("xml")), json!(-1));
+        assert_eq!(compute_const!(json!("jsonlogic"), json!("")), json!(0));
+        assert_eq!(compute_const!(json!("Grüße"), json!("ße")), json!(3));
+        assert_eq!(compute_const!(json!([1, 2, 3]), json!(2)), json!(2));
+    }
+}
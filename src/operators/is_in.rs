@@ -1,24 +1,24 @@
 use serde_json::{json, Value};
 
-use super::{logic, Data, Expression};
+use super::{logic, Data};
 
 /// Expects two arguments. Tests either for substring or whether an array contains an element.
 ///
 /// If the second argument is an array, tests that the first argument is a member of the array.
 ///
 /// If the second argument is a string, tests that the first argument is a substring.
-pub fn compute(args: &[Expression], data: &Data) -> Value {
+pub fn compute(args: &[Value], _data: &Data) -> Value {
     let a = match args.get(0) {
-        Some(arg) => arg.compute(data),
+        Some(arg) => arg,
         None => return json!(false),
     };
 
-    let result = match args.get(1).map(|arg| arg.compute(data)) {
+    let result = match args.get(1) {
         // Second argument is an array: test whether the first argument is a member of the array.
-        Some(Value::String(b)) => b.contains(&logic::coerce_to_str(&a)),
+        Some(Value::String(b)) => b.contains(&logic::coerce_to_str(a)),
         // Second argument is a string: test whether the first argument (coerced into a string) is
         // a substring of the second argument.
-        Some(Value::Array(b)) => b.iter().any(|el| logic::is_strict_equal(el, &a)),
+        Some(Value::Array(b)) => b.iter().any(|el| logic::is_strict_equal(el, a)),
         _ => false,
     };
     Value::Bool(result)
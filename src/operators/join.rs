@@ -0,0 +1,50 @@
+use serde_json::Value;
+
+use super::{logic, Data};
+
+/// Joins the elements of the array of the first argument, coerced into strings, with the
+/// separator of the second argument, which defaults to `","`. Any other value than an array is
+/// joined as an array of one element. Returns `Value::Null` if there is no argument.
+pub fn compute(args: &[Value], _data: &Data) -> Value {
+    let value = match args.first() {
+        Some(arg) => arg,
+        None => return Value::Null,
+    };
+    let separator = match args.get(1) {
+        Some(arg) => logic::coerce_to_str(arg),
+        None => String::from(","),
+    };
+    let joined = match value {
+        Value::Array(arr) => arr
+            .iter()
+            .map(logic::coerce_to_str)
+            .collect::<Vec<String>>()
+            .join(&separator),
+        value => logic::coerce_to_str(value),
+    };
+
+    Value::String(joined)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute_const;
+    use serde_json::json;
+
+    #[test]
+    fn test() {
+        assert_eq!(compute_const!(), Value::Null);
+        assert_eq!(compute_const!(json!(["a", 1, true])), json!("a,1,true"));
+        assert_eq!(
+            compute_const!(json!(["a", "b"]), json!(" - ")),
+            json!("a - b")
+        );
+        assert_eq!(compute_const!(json!([]), json!("-")), json!(""));
+        assert_eq!(
+            compute_const!(json!([[1, 2], 3]), json!(";")),
+            json!("1,2;3")
+        );
+        assert_eq!(compute_const!(json!("abc"), json!("-")), json!("abc"));
+    }
+}
@@ -0,0 +1,32 @@
+use serde_json::Value;
+
+use super::Data;
+
+/// Returns the keys of the object of the first argument, or the indices of an array as strings,
+/// like `Object.keys` in javascript. Returns an empty array for any other value.
+pub fn compute(args: &[Value], _data: &Data) -> Value {
+    let keys = match args.first() {
+        Some(Value::Object(obj)) => obj.keys().cloned().map(Value::String).collect(),
+        Some(Value::Array(arr)) => (0..arr.len())
+            .map(|index| Value::String(index.to_string()))
+            .collect(),
+        _ => vec![],
+    };
+
+    Value::Array(keys)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute_const;
+    use serde_json::json;
+
+    #[test]
+    fn test() {
+        assert_eq!(compute_const!(), json!([]));
+        assert_eq!(compute_const!(json!("abc")), json!([]));
+        assert_eq!(compute_const!(json!({"b": 1, "a": 2})), json!(["a", "b"]));
+        assert_eq!(compute_const!(json!(["x", "y"])), json!(["0", "1"]));
+    }
+}
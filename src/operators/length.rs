@@ -0,0 +1,32 @@
+use serde_json::Value;
+
+use super::{logic, Data};
+
+/// Returns the number of elements of an array. Any other value is coerced into a string and its
+/// number of characters, as defined by the string mode of the data, is returned. Returns
+/// `Value::Null` if there is no argument.
+pub fn compute(args: &[Value], data: &Data) -> Value {
+    match args.first() {
+        Some(Value::Array(arr)) => Value::from(arr.len()),
+        Some(value) => Value::from(data.string_mode().length(&logic::coerce_to_str(value))),
+        None => Value::Null,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute_const;
+    use serde_json::json;
+
+    #[test]
+    fn test() {
+        assert_eq!(compute_const!(), Value::Null);
+        assert_eq!(compute_const!(json!("")), json!(0));
+        assert_eq!(compute_const!(json!("jsonlogic")), json!(9));
+        assert_eq!(compute_const!(json!("Grüße")), json!(5));
+        assert_eq!(compute_const!(json!(12.5)), json!(4));
+        assert_eq!(compute_const!(json!([])), json!(0));
+        assert_eq!(compute_const!(json!([1, [2, 3], "four"])), json!(3));
+    }
+}
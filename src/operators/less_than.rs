@@ -1,21 +1,21 @@
 use serde_json::Value;
 
-use super::{logic, Data, Expression};
+use super::{logic, Data};
 
-pub fn compute(args: &[Expression], data: &Data) -> Value {
+pub fn compute(args: &[Value], _data: &Data) -> Value {
     let a = match args.get(0) {
-        Some(arg) => arg.compute(data),
+        Some(arg) => arg,
         None => return Value::Bool(false),
     };
 
     let b = match args.get(1) {
-        Some(arg) => arg.compute(data),
+        Some(arg) => arg,
         None => return Value::Bool(false),
     };
 
     let result = match args.get(2) {
-        Some(c) => compute_between_exclusive(&a, &b, &c.compute(data)),
-        None => compute_less_than(&a, &b),
+        Some(c) => compute_between_exclusive(a, b, c),
+        None => compute_less_than(a, b),
     };
 
     Value::Bool(result)
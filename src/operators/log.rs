@@ -1,13 +1,10 @@
 use serde_json::Value;
 
-use super::{Data, Expression};
+use super::Data;
 
 /// Logs the first value to console, then passes it through unmodified.
-pub fn compute(args: &[Expression], data: &Data) -> Value {
-    let a = args
-        .get(0)
-        .map(|arg| arg.compute(data))
-        .unwrap_or(Value::Null);
+pub fn compute(args: &[Value], _data: &Data) -> Value {
+    let a = args.get(0).cloned().unwrap_or(Value::Null);
 
     println!("{}", a);
 
@@ -1,4 +1,9 @@
 use serde_json::{Number, Value};
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+
+#[cfg(feature = "decimal")]
+use super::decimal;
 
 /// See http://jsonlogic.com/truthy.html
 pub fn is_truthy(value: &Value) -> bool {
@@ -6,7 +11,8 @@ pub fn is_truthy(value: &Value) -> bool {
         Value::Array(arr) => !arr.is_empty(),
         Value::Bool(b) => *b,
         Value::Null => false,
-        Value::Number(num) => num.as_f64().unwrap() != 0f64,
+        // Numbers too large for an `f64` are not zero.
+        Value::Number(num) => num.as_f64() != Some(0f64),
         Value::Object(_) => true,
         Value::String(s) => s != "",
     }
@@ -44,20 +50,19 @@ pub fn is_abstract_equal(a: &Value, b: &Value) -> bool {
         // short-circuit only one operand being null
         (Null, _) | (_, Null) => false,
         // 4. If Type(a) is number and Type(b) is string, return a == ToNumber(b).
-        (Number(a), String(_)) => coerce_to_f64(b)
-            .map(|b| a.as_f64().unwrap() == b)
-            .unwrap_or(false),
         // 5. If Type(a) is string and Type(b) is number, return ToNumber(a) == b.
-        (String(_), Number(b)) => coerce_to_f64(a)
-            .map(|a| a == b.as_f64().unwrap())
-            .unwrap_or(false),
+        (Number(_), String(_)) | (String(_), Number(_)) => {
+            compare_as_numbers(a, b) == Some(Ordering::Equal)
+        }
         // 6. If Type(a) is bool return ToNumber(a)==b
         (Bool(_), _) => coerce_to_f64(a)
-            .map(|a| is_abstract_equal(&Value::Number(serde_json::Number::from_f64(a).unwrap()), b))
+            .and_then(serde_json::Number::from_f64)
+            .map(|a| is_abstract_equal(&Value::Number(a), b))
             .unwrap_or(false),
         // 7. If Type(b) is bool return a==ToNumber(b)
         (_, Bool(_)) => coerce_to_f64(b)
-            .map(|b| is_abstract_equal(a, &Value::Number(serde_json::Number::from_f64(b).unwrap())))
+            .and_then(serde_json::Number::from_f64)
+            .map(|b| is_abstract_equal(a, &Value::Number(b)))
             .unwrap_or(false),
         // 8. something with object
         // if non array object:
@@ -74,27 +79,30 @@ pub fn is_abstract_equal(a: &Value, b: &Value) -> bool {
 
 // See https://www.ecma-international.org/ecma-262/#sec-abstract-relational-comparison
 pub fn less_than(a: &Value, b: &Value) -> bool {
+    abstract_less_than(a, b) == Some(true)
+}
+
+/// The abstract relational comparison `a < b`, which is undefined (`None`) if the operands are
+/// compared as numbers and one of them is NaN. Comparisons with an undefined result are false.
+fn abstract_less_than(a: &Value, b: &Value) -> Option<bool> {
     use Value::*;
 
     match (a, b) {
-        (Null, Null) => false,
-        (Bool(false), Bool(true)) => true,
-        (Bool(_), Bool(_)) => false,
-        (Object(_), _) | (_, Object(_)) => false,
-        (String(a), String(b)) => a < b,
+        (Null, Null) => Some(false),
+        (Bool(false), Bool(true)) => Some(true),
+        (Bool(_), Bool(_)) => Some(false),
+        (Object(_), _) | (_, Object(_)) => Some(false),
+        (String(a), String(b)) => Some(a < b),
         // Combinations where both operands will be coerced to strings:
         //   Arrays will be converted to a primitive (i.e. a string). (1.)
         //   Strings will be compared lexically. (3.)
         (Array(_), Array(_)) | (Array(_), String(_)) | (String(_), Array(_)) => {
-            coerce_to_str(a) < coerce_to_str(b)
+            Some(coerce_to_str(a) < coerce_to_str(b))
         }
         // Combinations where both operands will be coerced to numbers:
         //   In every other combination the operands will be converted to numbers in the end. (4.)
         (Null, _) | (_, Null) | (Number(_), _) | (_, Number(_)) | (Bool(_), _) | (_, Bool(_)) => {
-            match (coerce_to_f64(a), coerce_to_f64(b)) {
-                (Some(a), Some(b)) => a < b,
-                _ => false,
-            }
+            compare_as_numbers(a, b).map(|ordering| ordering == Ordering::Less)
         }
     }
 }
@@ -104,11 +112,11 @@ pub fn less_equal_than(a: &Value, b: &Value) -> bool {
 }
 
 pub fn greater_than(a: &Value, b: &Value) -> bool {
-    !less_equal_than(a, b)
+    abstract_less_than(a, b).is_some() && !less_equal_than(a, b)
 }
 
 pub fn greater_equal_than(a: &Value, b: &Value) -> bool {
-    !less_than(a, b)
+    abstract_less_than(a, b) == Some(false)
 }
 
 /// The javascript operation `String(val)`.
@@ -169,36 +177,111 @@ pub fn coerce_to_f64(val: &Value) -> Option<f64> {
 ///       trailing n character is discarded.
 ///
 /// This function does not support BigInt syntax, since JSON does not support it.
+#[cfg_attr(feature = "decimal", allow(dead_code))]
 pub fn parse_float(val: &Value) -> Option<f64> {
     match val {
-        Value::Number(num) => Some(num.as_f64().unwrap()),
-        Value::String(s) => {
-            let s = s.trim();
-            let mut end = 0;
-            // Keeping track of decimal point presence, since the parsing stops on a second one.
-            let mut has_decimal_point = false;
-            for ch in s.chars() {
-                match ch {
-                    '+' | '-' | '0'..='9' | 'e' | 'E' => end += 1,
-                    '.' => {
-                        if has_decimal_point {
-                            break;
-                        } else {
-                            end += 1;
-                            has_decimal_point = true;
-                        }
-                    }
-                    _ => break,
+        Value::Number(num) => num.as_f64(),
+        Value::String(s) => float_prefix(s).parse::<f64>().ok(),
+        _ => None,
+    }
+}
+
+/// Returns the part of the string that parseFloat parses, see `parse_float`.
+pub fn float_prefix(s: &str) -> &str {
+    let s = s.trim();
+    let mut end = 0;
+    // Keeping track of decimal point presence, since the parsing stops on a second one.
+    let mut has_decimal_point = false;
+    for ch in s.chars() {
+        match ch {
+            '+' | '-' | '0'..='9' | 'e' | 'E' => end += 1,
+            '.' => {
+                if has_decimal_point {
+                    break;
+                } else {
+                    end += 1;
+                    has_decimal_point = true;
                 }
             }
-
-            let parsed = &s[0..end];
-            parsed.parse::<f64>().ok()
+            _ => break,
         }
+    }
+
+    &s[0..end]
+}
+
+/// Converts the float into a JSON number. NaN and ±Infinity cannot be represented in JSON, so they
+/// are `Value::Null`, like the result of a division by zero.
+#[cfg_attr(feature = "decimal", allow(dead_code))]
+pub fn f64_to_value(num: f64) -> Value {
+    Number::from_f64(num).map_or(Value::Null, Value::Number)
+}
+
+/// The largest integer up to which every integer can be represented by an `f64`.
+const MAX_SAFE_INTEGER: f64 = 9_007_199_254_740_991f64;
+
+/// Converts the float into a JSON integer if it is whole and every integer up to it can be
+/// represented exactly, so `"42"` converted into a number is `42` rather than `42.0`. Other floats
+/// are converted with `f64_to_value`.
+pub fn whole_to_value(num: f64) -> Value {
+    if num.fract() == 0f64 && num.abs() <= MAX_SAFE_INTEGER {
+        // Avoids `-0`, which is not an integer in JSON.
+        Value::from(num as i64)
+    } else {
+        f64_to_value(num)
+    }
+}
+
+/// Returns the value as an integer if it is a JSON integer or a string that contains only an
+/// integer, ignoring leading and trailing spaces. Arithmetic operators stay in integers as long as
+/// all operands are integers by this definition. An `i128` can hold every `i64` and `u64`.
+#[cfg_attr(feature = "decimal", allow(dead_code))]
+pub fn as_integer(val: &Value) -> Option<i128> {
+    match val {
+        Value::Number(num) => num
+            .as_i64()
+            .map(i128::from)
+            .or_else(|| num.as_u64().map(i128::from)),
+        Value::String(s) => s.trim().parse::<i128>().ok(),
         _ => None,
     }
 }
 
+/// Converts the integer into a JSON number, if it fits into an `i64` or `u64`.
+#[cfg_attr(feature = "decimal", allow(dead_code))]
+pub fn integer_to_number(int: i128) -> Option<Number> {
+    i64::try_from(int)
+        .map(Number::from)
+        .or_else(|_| u64::try_from(int).map(Number::from))
+        .ok()
+}
+
+/// Compares the values after coercing them into numbers, which are decimals if the `decimal`
+/// feature is enabled. Returns `None` if one value cannot be coerced or is NaN.
+fn compare_as_numbers(a: &Value, b: &Value) -> Option<Ordering> {
+    // Integers above 2^53 are ordered exactly, like they are compared for equality.
+    #[cfg(not(feature = "decimal"))]
+    if let (Value::Number(_), Value::Number(_)) = (a, b) {
+        if let (Some(a), Some(b)) = (as_integer(a), as_integer(b)) {
+            return Some(a.cmp(&b));
+        }
+    }
+    #[cfg(not(feature = "decimal"))]
+    return coerce_to_f64(a)?.partial_cmp(&coerce_to_f64(b)?);
+    #[cfg(feature = "decimal")]
+    return Some(decimal::coerce_to_decimal(a)?.cmp(&decimal::coerce_to_decimal(b)?));
+}
+
+#[cfg(feature = "decimal")]
+fn equal_numbers(a: &Number, b: &Number) -> bool {
+    match (decimal::from_number(a), decimal::from_number(b)) {
+        (Some(a), Some(b)) => a == b,
+        // Numbers that do not fit into a decimal are compared as floats.
+        _ => a.as_f64() == b.as_f64(),
+    }
+}
+
+#[cfg(not(feature = "decimal"))]
 #[allow(clippy::float_cmp)]
 fn equal_numbers(a: &Number, b: &Number) -> bool {
     // Avoid float compare if possible.
@@ -207,7 +290,11 @@ fn equal_numbers(a: &Number, b: &Number) -> bool {
     } else if a.is_i64() && b.is_i64() {
         a.as_i64().unwrap() == b.as_i64().unwrap()
     } else {
-        a.as_f64().unwrap() == b.as_f64().unwrap()
+        match (a.as_f64(), b.as_f64()) {
+            (Some(a), Some(b)) => a == b,
+            // Numbers too large for an `f64` only exist with serde_json's `arbitrary_precision`.
+            _ => a == b,
+        }
     }
 }
 
@@ -390,6 +477,16 @@ mod tests {
             assert_eq!(less_than(&json!(1), &json!(2)), true);
             assert_eq!(less_than(&json!(2), &json!(2)), false);
             assert_eq!(less_than(&json!(3), &json!(2)), false);
+            // Integers above 2^53 are ordered exactly.
+            assert_eq!(
+                less_than(&json!(9007199254740992u64), &json!(9007199254740993u64)),
+                true
+            );
+            assert_eq!(
+                less_than(&json!(9007199254740993u64), &json!(9007199254740992u64)),
+                false
+            );
+            assert_eq!(less_than(&json!(i64::MIN), &json!(u64::MAX)), true);
 
             // string < string
             assert_eq!(less_than(&json!("a"), &json!("b")), true);
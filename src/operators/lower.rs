@@ -0,0 +1,27 @@
+use serde_json::Value;
+
+use super::{logic, Data};
+
+/// Converts the first argument, coerced into a string, to lower case. Returns `Value::Null` if
+/// there is no argument.
+pub fn compute(args: &[Value], _data: &Data) -> Value {
+    match args.first() {
+        Some(arg) => Value::String(logic::coerce_to_str(arg).to_lowercase()),
+        None => Value::Null,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute_const;
+    use serde_json::json;
+
+    #[test]
+    fn test() {
+        assert_eq!(compute_const!(), Value::Null);
+        assert_eq!(compute_const!(json!("JsonLogic")), json!("jsonlogic"));
+        assert_eq!(compute_const!(json!("ÄÖÜ")), json!("äöü"));
+        assert_eq!(compute_const!(json!([1, "A"])), json!("1,a"));
+    }
+}
@@ -4,8 +4,8 @@ use super::{Data, Expression};
 
 /// You can use `map` to perform an action on every member of an array. Note, that inside the
 /// logic being used to map, var operations are relative to the array element being worked on.
-pub fn compute(args: &[Expression], data: &Data) -> Value {
-    let arr = match args.get(0).map(|arg| arg.compute(data)) {
+pub fn compute(args: &[Expression], data: &Data) -> Result<Value, Value> {
+    let arr = match args.get(0).map(|arg| arg.compute(data)).transpose()? {
         Some(Value::Array(arr)) => arr,
         _ => Vec::with_capacity(0),
     };
@@ -16,25 +16,25 @@ pub fn compute(args: &[Expression], data: &Data) -> Value {
 
     let mut result = Vec::with_capacity(arr.len());
     for elem in arr.iter() {
-        let mapped_value = op.compute(&Data::from_json(elem));
+        let mapped_value = op.compute(&data.nested(elem))?;
         result.push(mapped_value);
     }
 
-    Value::Array(result)
+    Ok(Value::Array(result))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::compute_const;
+    use crate::compute_lazy;
     use serde_json::json;
 
     #[test]
     fn defaults() {
-        assert_eq!(compute_const!(), json!([]));
+        assert_eq!(compute_lazy!(), Ok(json!([])));
         assert_eq!(
-            compute_const!(json!([1, 2, 3, 4, 5])),
-            json!([null, null, null, null, null])
+            compute_lazy!(json!([1, 2, 3, 4, 5])),
+            Ok(json!([null, null, null, null, null]))
         );
     }
 
@@ -51,7 +51,7 @@ mod tests {
                 ],
                 &data,
             ),
-            json!([2.0, 4.0, 6.0, 8.0, 10.0])
+            Ok(json!([2, 4, 6, 8, 10]))
         );
 
         assert_eq!(
@@ -62,7 +62,7 @@ mod tests {
                 ],
                 &data,
             ),
-            json!([])
+            Ok(json!([]))
         );
 
         Ok(())
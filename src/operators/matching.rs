@@ -0,0 +1,242 @@
+//! The regular expression operators of the `regex` feature, `match` and `captures`. Both take the
+//! string to test and the pattern, in the syntax of the
+//! [`regex`](https://docs.rs/regex/latest/regex/#syntax) crate. Constant patterns are compiled
+//! once when parsing the rule, patterns that are computed from the data once per evaluation.
+
+use regex::{Regex, RegexBuilder};
+use serde_json::Value;
+
+use super::{logic, Data, Expression, Operator};
+
+/// The maximum size of a compiled pattern in bytes, so patterns from the data cannot use up the
+/// memory. Patterns that exceed it are invalid.
+pub const SIZE_LIMIT: usize = 1 << 20;
+
+/// A compiled regular expression. Patterns are equal if their source is equal.
+#[derive(Debug, Clone)]
+pub struct Pattern(Regex);
+
+impl Pattern {
+    /// Compiles the pattern. Errors if the pattern is invalid or its compiled size exceeds
+    /// `SIZE_LIMIT`.
+    pub fn new(pattern: &str) -> Result<Pattern, String> {
+        RegexBuilder::new(pattern)
+            .size_limit(SIZE_LIMIT)
+            .dfa_size_limit(SIZE_LIMIT)
+            .build()
+            .map(Pattern)
+            .map_err(|err| err.to_string())
+    }
+}
+
+impl PartialEq for Pattern {
+    fn eq(&self, other: &Pattern) -> bool {
+        self.0.as_str() == other.0.as_str()
+    }
+}
+
+/// Compiles the constant pattern of a `match` or `captures` operation, given the operator, its
+/// arguments and the JSON pointer to the operation in the rule, which is part of the error.
+/// Returns `None` for other operations and patterns that are not constant strings.
+pub fn parse_pattern(
+    operator: Operator,
+    args: &[Expression],
+    pointer: &str,
+) -> Option<Result<Pattern, String>> {
+    match (operator, args.get(1)) {
+        (Operator::Match, Some(Expression::Constant(Value::String(pattern))))
+        | (Operator::Captures, Some(Expression::Constant(Value::String(pattern)))) => {
+            Some(Pattern::new(pattern).map_err(|err| {
+                format!(
+                    "Invalid pattern {:?} in argument 2 of {} at {}/1: {}",
+                    pattern,
+                    operator.as_str(),
+                    pointer,
+                    err
+                )
+            }))
+        }
+        _ => None,
+    }
+}
+
+/// Tests whether the first argument, coerced into a string, matches the pattern of the second
+/// argument anywhere. Use `^` and `$` to match the whole string. Returns `Value::Null` if the
+/// pattern is invalid.
+pub fn compute_match(args: &[Expression], data: &Data) -> Result<Value, Value> {
+    compute(Operator::Match, args, data)
+}
+
+/// Returns the capture groups of the first match of the pattern of the second argument in the
+/// first argument, coerced into a string. The first element is the whole match, followed by the
+/// groups in the order of their opening parentheses, where groups that did not participate in the
+/// match are `null`. Returns `Value::Null` if the pattern does not match or is invalid.
+pub fn compute_captures(args: &[Expression], data: &Data) -> Result<Value, Value> {
+    compute(Operator::Captures, args, data)
+}
+
+/// Computes `match` or `captures`. The pattern is compiled once per evaluation, unless it has
+/// been compiled when parsing.
+fn compute(operator: Operator, args: &[Expression], data: &Data) -> Result<Value, Value> {
+    let values = Expression::compute_all(args, data)?;
+    let value = values.first().unwrap_or(&Value::Null);
+    Ok(match (args.get(1), values.get(1)) {
+        (Some(Expression::Pattern(_, pattern)), _) => apply_pattern(operator, value, pattern),
+        (_, Some(source)) => match data.pattern(&logic::coerce_to_str(source)) {
+            Some(pattern) => apply_pattern(operator, value, &pattern),
+            None => Value::Null,
+        },
+        _ => Value::Null,
+    })
+}
+
+/// Applies `match` or `captures` to the value, coerced into a string, with the compiled pattern.
+pub fn apply_pattern(operator: Operator, value: &Value, pattern: &Pattern) -> Value {
+    let value = logic::coerce_to_str(value);
+    if operator == Operator::Match {
+        return Value::Bool(pattern.0.is_match(&value));
+    }
+    match pattern.0.captures(&value) {
+        Some(captures) => Value::Array(
+            captures
+                .iter()
+                .map(|group| group.map_or(Value::Null, |group| Value::from(group.as_str())))
+                .collect(),
+        ),
+        None => Value::Null,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn apply(json_logic: Value, data: Value) -> Result<Value, crate::Error> {
+        crate::apply(&json_logic, &data)
+    }
+
+    #[test]
+    fn match_operator() {
+        let rule = json!({"match": [{"var": "email"}, "^[^@]+@example\\.com$"]});
+        assert_eq!(
+            apply(rule.clone(), json!({"email": "a@example.com"})),
+            Ok(json!(true))
+        );
+        assert_eq!(
+            apply(rule.clone(), json!({"email": "a@example.org"})),
+            Ok(json!(false))
+        );
+        assert_eq!(apply(rule, json!({})), Ok(json!(false)));
+        assert_eq!(
+            apply(json!({"match": [123, "^\\d+$"]}), json!(null)),
+            Ok(json!(true))
+        );
+        assert_eq!(
+            apply(json!({"match": ["ABC", "(?i)abc"]}), json!(null)),
+            Ok(json!(true))
+        );
+        assert_eq!(
+            apply(json!({"match": ["abc"]}), json!(null)),
+            Ok(Value::Null)
+        );
+    }
+
+    #[test]
+    fn captures_operator() {
+        let rule = json!({"captures": [{"var": "id"}, "^([A-Z]+)-(\\d+)(-draft)?$"]});
+        assert_eq!(
+            apply(rule.clone(), json!({"id": "ABC-123"})),
+            Ok(json!(["ABC-123", "ABC", "123", null]))
+        );
+        assert_eq!(apply(rule, json!({"id": "abc"})), Ok(Value::Null));
+    }
+
+    #[test]
+    fn constant_patterns() {
+        let json_logic = json!({"match": [{"var": "a"}, "^a+$"]});
+        match crate::expression::Expression::from_json(&json_logic).unwrap() {
+            Expression::Computed(Operator::Match, args) => {
+                assert_eq!(
+                    args[1],
+                    Expression::Pattern(&json_logic["match"][1], Pattern::new("^a+$").unwrap())
+                );
+            }
+            expr => panic!("unexpected expression {:?}", expr),
+        }
+
+        let err = apply(
+            json!({"if": [true, {"captures": ["a", "(a"]}]}),
+            json!(null),
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(
+            err.starts_with(
+                "Invalid pattern \"(a\" in argument 2 of captures at /if/1/captures/1: "
+            ),
+            "{}",
+            err
+        );
+        assert!(err.contains("unclosed group"), "{}", err);
+
+        let err = apply(
+            json!({"and": [
+                {"var": "a"},
+                {"map": [{"var": "b"}, {"!": {"match": [{"var": ""}, "[a-"]}}]}
+            ]}),
+            json!(null),
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(
+            err.starts_with(
+                "Invalid pattern \"[a-\" in argument 2 of match at /and/1/map/1/!/match/1: "
+            ),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn cached_patterns() {
+        let data = json!({"pattern": "^a"});
+        let data = Data::from_json(&data);
+        let rule = json!({"map": [["ab", "ba"], {"match": [{"var": ""}, {"ref": "pattern"}]}]});
+        let rule = json!({"let": ["pattern", {"var": "pattern"}, rule]});
+        let expr = Expression::from_json(&rule).unwrap();
+        assert_eq!(expr.compute(&data), Ok(json!([true, false])));
+
+        // The pattern was compiled once for both elements and is reused for a nested data.
+        let pattern = data.nested(&Value::Null).pattern("^a");
+        assert_eq!(pattern, Pattern::new("^a").ok());
+        assert_eq!(data.compiled_patterns(), 1);
+
+        // Distinct patterns are not all kept compiled.
+        let sources: Vec<_> = (0..100).map(|i| format!("^a{{{}}}", i)).collect();
+        let rule = json!({"map": [sources, {"match": ["aaa", {"var": ""}]}]});
+        let expr = Expression::from_json(&rule).unwrap();
+        let result = expr.compute(&data).unwrap();
+        assert_eq!(result[3], json!(true));
+        assert_eq!(result[4], json!(false));
+        assert!(data.compiled_patterns() <= 16);
+    }
+
+    #[test]
+    fn dynamic_patterns() {
+        let rule = json!({"match": ["abc", {"var": "pattern"}]});
+        assert_eq!(
+            apply(rule.clone(), json!({"pattern": "b"})),
+            Ok(json!(true))
+        );
+        assert_eq!(
+            apply(rule.clone(), json!({"pattern": "(b"})),
+            Ok(Value::Null)
+        );
+        // The compiled pattern exceeds the size limit.
+        assert_eq!(
+            apply(rule, json!({"pattern": "\\w{1000}{1000}"})),
+            Ok(Value::Null)
+        );
+    }
+}
@@ -0,0 +1,302 @@
+//! Math functions. Arguments are coerced into numbers with `logic::coerce_to_f64` and like `/`,
+//! the operators return `Value::Null` if an argument is missing or cannot be coerced, or if the
+//! result is not finite, like the square root of a negative number.
+
+use serde_json::Value;
+use std::convert::TryFrom;
+
+use super::{logic, Data};
+
+/// Returns the absolute value of the argument. The result is an integer if the argument is one.
+pub fn abs(args: &[Value], _data: &Data) -> Value {
+    let value = match args.first() {
+        Some(arg) => arg,
+        None => return Value::Null,
+    };
+    match integer(value).and_then(|int| logic::integer_to_number(int.abs())) {
+        Some(num) => Value::Number(num),
+        None => {
+            logic::coerce_to_f64(value).map_or(Value::Null, |num| logic::f64_to_value(num.abs()))
+        }
+    }
+}
+
+/// Rounds the argument down to the next integer.
+pub fn floor(args: &[Value], _data: &Data) -> Value {
+    number_arg(args, 0).map_or(Value::Null, |num| logic::whole_to_value(num.floor()))
+}
+
+/// Rounds the argument up to the next integer.
+pub fn ceil(args: &[Value], _data: &Data) -> Value {
+    number_arg(args, 0).map_or(Value::Null, |num| logic::whole_to_value(num.ceil()))
+}
+
+/// Removes the fractional digits of the argument, i.e. rounds it towards zero.
+pub fn trunc(args: &[Value], _data: &Data) -> Value {
+    number_arg(args, 0).map_or(Value::Null, |num| logic::whole_to_value(num.trunc()))
+}
+
+/// Rounds the first argument to the number of decimal places of the second argument, which
+/// defaults to 0. Negative places round to tens, hundreds and so on. Halves are rounded away from
+/// zero.
+///
+/// The number is rounded in the decimal notation it is written in, so `{"round": [2.675, 2]}` is
+/// `2.68`, although the closest `f64` to 2.675 is slightly less than it.
+#[cfg_attr(feature = "decimal", allow(dead_code))]
+pub fn round(args: &[Value], _data: &Data) -> Value {
+    let num = match number_arg(args, 0) {
+        Some(num) => num,
+        None => return Value::Null,
+    };
+    let places = match args.get(1) {
+        Some(_) => match number_arg(args, 1) {
+            Some(places) => places.trunc() as i64,
+            None => return Value::Null,
+        },
+        None => 0,
+    };
+
+    logic::whole_to_value(round_half_away_from_zero(num, places))
+}
+
+/// Raises the first argument to the power of the second argument. The result is an integer if
+/// the base is an integer and the exponent a non-negative integer, unless it overflows.
+pub fn pow(args: &[Value], _data: &Data) -> Value {
+    let (base, exponent) = match (args.first(), args.get(1)) {
+        (Some(base), Some(exponent)) => (base, exponent),
+        _ => return Value::Null,
+    };
+    let int_result = integer(base).and_then(|base| {
+        let exponent = u32::try_from(integer(exponent)?).ok()?;
+        logic::integer_to_number(base.checked_pow(exponent)?)
+    });
+
+    match int_result {
+        Some(num) => Value::Number(num),
+        None => match (logic::coerce_to_f64(base), logic::coerce_to_f64(exponent)) {
+            (Some(base), Some(exponent)) => logic::f64_to_value(base.powf(exponent)),
+            _ => Value::Null,
+        },
+    }
+}
+
+/// Returns the square root of the argument.
+pub fn sqrt(args: &[Value], _data: &Data) -> Value {
+    number_arg(args, 0).map_or(Value::Null, |num| logic::f64_to_value(num.sqrt()))
+}
+
+/// Returns the natural logarithm of the first argument, or the logarithm to the base of the
+/// second argument. It is not named `log` like `Math.log` in JavaScript, since `log` is the
+/// operator that logs its argument.
+pub fn ln(args: &[Value], _data: &Data) -> Value {
+    let num = match number_arg(args, 0) {
+        Some(num) => num,
+        None => return Value::Null,
+    };
+    match args.get(1) {
+        Some(_) => {
+            number_arg(args, 1).map_or(Value::Null, |base| logic::f64_to_value(num.log(base)))
+        }
+        None => logic::f64_to_value(num.ln()),
+    }
+}
+
+/// Returns e raised to the power of the argument.
+pub fn exp(args: &[Value], _data: &Data) -> Value {
+    number_arg(args, 0).map_or(Value::Null, |num| logic::f64_to_value(num.exp()))
+}
+
+/// Limits the first argument to the range from the second to the third argument. The result is
+/// an integer if all arguments are integers. Returns `Value::Null` if the minimum is greater than
+/// the maximum.
+pub fn clamp(args: &[Value], _data: &Data) -> Value {
+    let values = &args[..args.len().min(3)];
+    let numbers: Option<Vec<f64>> = values.iter().map(logic::coerce_to_f64).collect();
+    let (num, min, max) = match numbers.as_deref() {
+        Some([num, min, max]) if min <= max => (*num, *min, *max),
+        _ => return Value::Null,
+    };
+
+    let index = if num < min {
+        1
+    } else if num > max {
+        2
+    } else {
+        0
+    };
+    let integers: Option<Vec<i128>> = values.iter().map(integer).collect();
+    match integers.and_then(|integers| logic::integer_to_number(integers[index])) {
+        Some(int) => Value::Number(int),
+        None => logic::f64_to_value([num, min, max][index]),
+    }
+}
+
+fn number_arg(args: &[Value], index: usize) -> Option<f64> {
+    logic::coerce_to_f64(args.get(index)?)
+}
+
+/// Returns the value as an integer if it is a JSON integer or a string containing one.
+fn integer(value: &Value) -> Option<i128> {
+    match value {
+        Value::Number(_) | Value::String(_) => logic::as_integer(value),
+        _ => None,
+    }
+}
+
+/// Rounds the number to the decimal places, using the shortest decimal notation of the number
+/// that converts back to the same `f64`.
+#[cfg_attr(feature = "decimal", allow(dead_code))]
+fn round_half_away_from_zero(num: f64, places: i64) -> f64 {
+    if !num.is_finite() {
+        return num;
+    }
+    // Like "2.675e0": the significant digits, with a single digit before the point.
+    let notation = format!("{:e}", num.abs());
+    let (mantissa, exponent) = notation.split_once('e').unwrap();
+    let exponent: i64 = exponent.parse().unwrap();
+    let digits: Vec<u8> = mantissa
+        .bytes()
+        .filter(|byte| byte.is_ascii_digit())
+        .map(|byte| byte - b'0')
+        .collect();
+
+    // The number of digits that remain before the rounding position.
+    let keep = exponent.saturating_add(1).saturating_add(places);
+    if keep >= digits.len() as i64 {
+        return num;
+    }
+    if keep < 0 {
+        return 0f64.copysign(num);
+    }
+    let keep = keep as usize;
+
+    let mut kept = digits[..keep].to_vec();
+    if digits[keep] >= 5 {
+        // Carry the one into the kept digits. If all of them are 9, they become 0 and a leading 1
+        // is added, like 9.96 rounded to 1 place is 10.0.
+        let mut index = kept.len();
+        loop {
+            if index == 0 {
+                kept.insert(0, 1);
+                break;
+            }
+            index -= 1;
+            if kept[index] == 9 {
+                kept[index] = 0;
+            } else {
+                kept[index] += 1;
+                break;
+            }
+        }
+    }
+
+    rebuild(&kept, exponent + 1 - keep as i64, num)
+}
+
+/// Parses the digits, multiplied by 10 to the power of `exponent`, with the sign of `num`.
+#[cfg_attr(feature = "decimal", allow(dead_code))]
+fn rebuild(digits: &[u8], exponent: i64, num: f64) -> f64 {
+    if digits.is_empty() {
+        return 0f64.copysign(num);
+    }
+    let digits: String = digits
+        .iter()
+        .map(|digit| char::from(b'0' + digit))
+        .collect();
+    let rounded: f64 = format!("{}e{}", digits, exponent).parse().unwrap();
+    rounded.copysign(num)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn apply(json_logic: Value) -> Value {
+        crate::apply(&json_logic, &Value::Null).unwrap()
+    }
+
+    #[test]
+    fn abs() {
+        assert_eq!(apply(json!({"abs": []})), Value::Null);
+        assert_eq!(apply(json!({"abs": "a"})), Value::Null);
+        assert_eq!(apply(json!({"abs": -3})), json!(3));
+        assert_eq!(apply(json!({"abs": "-3"})), json!(3));
+        assert_eq!(apply(json!({"abs": -1.5})), json!(1.5));
+        assert_eq!(
+            apply(json!({"abs": i64::MIN})),
+            json!(9_223_372_036_854_775_808u64)
+        );
+    }
+
+    #[test]
+    fn floor_ceil_trunc() {
+        assert_eq!(apply(json!({"floor": 2.7})), json!(2));
+        assert_eq!(apply(json!({"floor": -2.2})), json!(-3));
+        assert_eq!(apply(json!({"ceil": 2.2})), json!(3));
+        assert_eq!(apply(json!({"ceil": -0.5})), json!(0));
+        assert_eq!(apply(json!({"trunc": -2.7})), json!(-2));
+        assert_eq!(apply(json!({"trunc": "2.7"})), json!(2));
+        assert_eq!(apply(json!({"floor": 1e300})), json!(1e300));
+        assert_eq!(apply(json!({"floor": "x"})), Value::Null);
+        assert_eq!(apply(json!({"ceil": []})), Value::Null);
+    }
+
+    #[test]
+    fn round() {
+        assert_eq!(apply(json!({"round": 2.5})), json!(3));
+        assert_eq!(apply(json!({"round": -2.5})), json!(-3));
+        assert_eq!(apply(json!({"round": 2.4})), json!(2));
+        assert_eq!(apply(json!({"round": [2.675, 2]})), json!(2.68));
+        assert_eq!(apply(json!({"round": [1.005, 2]})), json!(1.01));
+        assert_eq!(apply(json!({"round": [-1.005, 2]})), json!(-1.01));
+        assert_eq!(apply(json!({"round": [19.999, 2]})), json!(20));
+        assert_eq!(apply(json!({"round": [0.994, 2]})), json!(0.99));
+        assert_eq!(apply(json!({"round": [1234.5, -2]})), json!(1200));
+        assert_eq!(apply(json!({"round": [960, -3]})), json!(1000));
+        assert_eq!(apply(json!({"round": [0.04, 1]})), json!(0));
+        assert_eq!(apply(json!({"round": [0.5, 0]})), json!(1));
+        assert_eq!(apply(json!({"round": [0.4, -1]})), json!(0));
+        // With `arbitrary_precision`, the decimal result is written as 0.0000000002.
+        assert_eq!(apply(json!({"round": [1.5e-10, 10]})).as_f64(), Some(2e-10));
+        assert_eq!(apply(json!({"round": [1.25, 1e300]})), json!(1.25));
+        assert_eq!(apply(json!({"round": [1.25, -1e300]})), json!(0));
+        assert_eq!(apply(json!({"round": [1.25, "x"]})), Value::Null);
+    }
+
+    #[test]
+    fn pow() {
+        assert_eq!(apply(json!({"pow": [2]})), Value::Null);
+        assert_eq!(apply(json!({"pow": [2, 10]})), json!(1024));
+        assert_eq!(apply(json!({"pow": [-3, 3]})), json!(-27));
+        assert_eq!(apply(json!({"pow": [2, -1]})), json!(0.5));
+        assert_eq!(apply(json!({"pow": [4, 0.5]})), json!(2.0));
+        assert_eq!(apply(json!({"pow": [10, 30]})), json!(1e30));
+        assert_eq!(apply(json!({"pow": [-8, 0.5]})), Value::Null);
+        assert_eq!(apply(json!({"pow": [10, 400]})), Value::Null);
+    }
+
+    #[test]
+    fn sqrt_ln_exp() {
+        assert_eq!(apply(json!({"sqrt": 16})), json!(4.0));
+        assert_eq!(apply(json!({"sqrt": -1})), Value::Null);
+        assert_eq!(apply(json!({"ln": 1})), json!(0.0));
+        assert_eq!(apply(json!({"ln": [8, 2]})), json!(3.0));
+        assert_eq!(apply(json!({"ln": 0})), Value::Null);
+        assert_eq!(apply(json!({"ln": [8, "x"]})), Value::Null);
+        assert_eq!(apply(json!({"exp": 0})), json!(1.0));
+        assert_eq!(apply(json!({"exp": 1000})), Value::Null);
+    }
+
+    #[test]
+    fn clamp() {
+        assert_eq!(apply(json!({"clamp": [5, 0]})), Value::Null);
+        assert_eq!(apply(json!({"clamp": [5, 0, 10]})), json!(5));
+        assert_eq!(apply(json!({"clamp": [-5, 0, 10]})), json!(0));
+        assert_eq!(apply(json!({"clamp": [15, 0, 10]})), json!(10));
+        assert_eq!(apply(json!({"clamp": ["15", 0, 10.5]})), json!(10.5));
+        assert_eq!(apply(json!({"clamp": [0.5, 0, 1]})), json!(0.5));
+        assert_eq!(apply(json!({"clamp": [5, 10, 0]})), Value::Null);
+        assert_eq!(apply(json!({"clamp": ["x", 0, 10]})), Value::Null);
+    }
+}
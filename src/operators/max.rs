@@ -1,17 +1,28 @@
-use serde_json::{Number, Value};
+use serde_json::Value;
 
-use super::{logic, Data, Expression};
+use super::{logic, Data};
 
 /// Returns the largest of the given numbers. Arguments that are no numbers are coerced into
 /// numbers. If one argument cannot be coerced or there are no arguments, `Value::Null` will be
-/// returned.
+/// returned, as well as for infinite results. The result is an integer if all arguments are
+/// integers.
 /// https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Math/max
-pub fn compute(args: &[Expression], data: &Data) -> Value {
+pub fn compute(args: &[Value], _data: &Data) -> Value {
     let mut max: Option<f64> = None;
+    let mut int_max: Option<i128> = None;
+    let mut all_integers = true;
 
     for arg in args {
-        let arg = arg.compute(data);
-        match (logic::coerce_to_f64(&arg), max) {
+        match logic::as_integer(arg) {
+            Some(num) if all_integers => {
+                int_max = match int_max {
+                    Some(current_max) if current_max >= num => Some(current_max),
+                    _ => Some(num),
+                };
+            }
+            _ => all_integers = false,
+        }
+        match (logic::coerce_to_f64(arg), max) {
             (Some(num), Some(current_max)) => {
                 if num > current_max {
                     max = Some(num);
@@ -22,8 +33,15 @@ pub fn compute(args: &[Expression], data: &Data) -> Value {
         }
     }
 
+    if let Some(num) = int_max
+        .filter(|_| all_integers)
+        .and_then(logic::integer_to_number)
+    {
+        return Value::Number(num);
+    }
+
     match max {
-        Some(max) => Value::Number(Number::from_f64(max).unwrap()),
+        Some(max) => logic::f64_to_value(max),
         None => Value::Null,
     }
 }
@@ -38,13 +56,23 @@ mod tests {
     fn test() {
         assert_eq!(compute_const!(), Value::Null);
         assert_eq!(compute_const!(json!("foo")), Value::Null);
-        assert_eq!(compute_const!(json!("1"), json!(-2)), json!(1.0));
+        assert_eq!(compute_const!(json!("1"), json!(-2)), json!(1));
         assert_eq!(
             compute_const!(json!(1), json!("-2"), json!("foo"), json!(-4)),
             Value::Null
         );
         assert_eq!(compute_const!(json!(null)), json!(0.0));
-        assert_eq!(compute_const!(json!(-4)), json!(-4.0));
+        assert_eq!(compute_const!(json!(-4)), json!(-4));
         assert_eq!(compute_const!(json!(null), json!(2), json!(-4)), json!(2.0));
     }
+
+    #[test]
+    fn integers() {
+        assert_eq!(compute_const!(json!(1), json!(0.5)), json!(1.0));
+        assert_eq!(compute_const!(json!(1), json!(1.5)), json!(1.5));
+        assert_eq!(
+            compute_const!(json!(9007199254740993u64), json!(-1)),
+            json!(9007199254740993u64)
+        );
+    }
 }
@@ -1,15 +1,14 @@
 use serde_json::Value;
 
-use super::{Data, Expression};
+use super::Data;
 
-pub fn compute(args: &[Expression], data: &Data) -> Value {
+pub fn compute(args: &[Value], _data: &Data) -> Value {
     let mut result: Vec<Value> = vec![];
 
     for arg in args {
-        let arg = arg.compute(data);
         match arg {
             Value::Array(arr) => result.extend(arr.iter().cloned()),
-            _ => result.push(arg),
+            _ => result.push(arg.clone()),
         };
     }
 
@@ -0,0 +1,38 @@
+use serde_json::{Map, Value};
+
+use super::Data;
+
+/// Merges the objects of all arguments into one object. If several objects have the same key,
+/// the value of the last one is taken. Nested objects are not merged. Arguments that are not
+/// objects are ignored.
+pub fn compute(args: &[Value], _data: &Data) -> Value {
+    let mut result = Map::new();
+    for arg in args {
+        if let Value::Object(obj) = arg {
+            result.extend(obj.clone());
+        }
+    }
+
+    Value::Object(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute_const;
+    use serde_json::json;
+
+    #[test]
+    fn test() {
+        assert_eq!(compute_const!(), json!({}));
+        assert_eq!(compute_const!(json!({"a": 1})), json!({"a": 1}));
+        assert_eq!(
+            compute_const!(
+                json!({"a": 1, "b": {"c": 1}}),
+                json!([1, 2]),
+                json!({"b": {"d": 2}, "e": 3})
+            ),
+            json!({"a": 1, "b": {"d": 2}, "e": 3})
+        );
+    }
+}
@@ -1,48 +1,51 @@
 use serde_json::Value;
+use std::iter;
 
-use super::{Data, Expression};
+use super::{Data, Expression, Path};
 
 /// Takes an array of data keys. Returns an array of any keys missing from the data object.
 ///
 /// Can also receive 1 argument that is an array of keys, which typically happens if it's actually
 /// acting on the output of another command (like 'if' or 'merge').
 /// See https://github.com/jwadhams/json-logic-js/blob/a15f528919346f2ec7d82bd4fc91c41481546c01/logic.js#L145
-pub fn compute(args: &[Expression], data: &Data) -> Value {
+pub fn compute(args: &[Expression], data: &Data) -> Result<Value, Value> {
     let mut result: Vec<Value> = vec![];
-    let mut args = args.iter().map(|arg| arg.compute(data));
+    let values = Expression::compute_all(args, data)?;
+    let mut args = values.into_iter().zip(args);
 
     // The list of keys to look up is either the first argument if that is an array or the list
-    // of all arguments otherwise.
+    // of all arguments otherwise. Keys that are constant come with their parsed paths.
     let first = args.next();
-    let keys = match first {
+    let keys: Vec<(Value, Option<&Path>)> = match first {
         // The first argument is an array, so use its values as keys.
-        Some(Value::Array(arr)) => arr,
+        Some((Value::Array(arr), arg)) => match arg.array_paths() {
+            Some((_, paths)) => arr.into_iter().zip(paths.iter().map(Some)).collect(),
+            None => arr.into_iter().map(|key| (key, None)).collect(),
+        },
         // The first argument is something else, so interpret the arguments as keys.
-        Some(first) => {
-            let mut keys = Vec::with_capacity(args.len());
-            keys.push(first);
-            keys.append(&mut args.collect());
-            keys
-        }
+        Some(first) => iter::once(first)
+            .chain(args)
+            .map(|(key, arg)| (key, arg.path()))
+            .collect(),
         // No argument, return an empty array.
-        _ => return Value::Array(result),
+        _ => return Ok(Value::Array(result)),
     };
 
-    for key in keys.iter() {
+    for (key, path) in keys.into_iter() {
         // TODO: Even tough we only look for the existence, the value to the key will be cloned.
         // Something like Data::has_value without cloning would help.
-        if data.get_value(key).is_none() {
-            result.push(key.clone());
+        if data.get_value_or_by_path(&key, path).is_none() {
+            result.push(key);
         }
     }
 
-    Value::Array(result)
+    Ok(Value::Array(result))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::compute_const_with_data;
+    use crate::compute_lazy_with_data;
     use serde_json::json;
 
     #[test]
@@ -50,19 +53,19 @@ mod tests {
         let data_json = json!({ "a": 5, "b": "foo" });
         let data = &Data::from_json(&data_json);
 
-        assert_eq!(compute_const_with_data!(&[], data), json!([]));
+        assert_eq!(compute_lazy_with_data!(&[], data), Ok(json!([])));
         assert_eq!(
-            compute_const_with_data!(&[json!("bar")], data),
-            json!(["bar"])
+            compute_lazy_with_data!(&[json!("bar")], data),
+            Ok(json!(["bar"]))
         );
-        assert_eq!(compute_const_with_data!(&[json!("a")], data), json!([]));
+        assert_eq!(compute_lazy_with_data!(&[json!("a")], data), Ok(json!([])));
         assert_eq!(
-            compute_const_with_data!(&[json!("a"), json!("b")], data),
-            json!([])
+            compute_lazy_with_data!(&[json!("a"), json!("b")], data),
+            Ok(json!([]))
         );
         assert_eq!(
-            compute_const_with_data!(&[json!("a"), json!("b"), json!("c")], data),
-            json!(["c"])
+            compute_lazy_with_data!(&[json!("a"), json!("b"), json!("c")], data),
+            Ok(json!(["c"]))
         );
     }
 
@@ -72,17 +75,45 @@ mod tests {
         let data = &Data::from_json(&data_json);
 
         assert_eq!(
-            compute_const_with_data!(&[json!(["bar"])], data),
-            json!(["bar"])
+            compute_lazy_with_data!(&[json!(["bar"])], data),
+            Ok(json!(["bar"]))
+        );
+        assert_eq!(
+            compute_lazy_with_data!(&[json!(["a"])], data),
+            Ok(json!([]))
+        );
+        assert_eq!(
+            compute_lazy_with_data!(&[json!(["a", "b"])], data),
+            Ok(json!([]))
+        );
+        assert_eq!(
+            compute_lazy_with_data!(&[json!(["a", "b", "c"])], data),
+            Ok(json!(["c"]))
+        );
+    }
+
+    #[test]
+    fn parsed_paths() -> Result<(), Box<dyn std::error::Error>> {
+        let data_json = json!({ "a": { "b": [1] }, "c": "foo" });
+        let data = &Data::from_json(&data_json);
+        let compute_json = |json: &Value| -> Result<Value, String> {
+            Expression::from_json(json)?
+                .compute(data)
+                .map_err(|error| error.to_string())
+        };
+
+        assert_eq!(
+            compute_json(&json!({ "missing": ["a.b.0", "a.b.1", "c.0", "c.5"] }))?,
+            json!(["a.b.1", "c.5"])
         );
-        assert_eq!(compute_const_with_data!(&[json!(["a"])], data), json!([]));
         assert_eq!(
-            compute_const_with_data!(&[json!(["a", "b"])], data),
-            json!([])
+            compute_json(&json!({ "missing": [["a.b", "d", "c"]] }))?,
+            json!(["d"])
         );
         assert_eq!(
-            compute_const_with_data!(&[json!(["a", "b", "c"])], data),
-            json!(["c"])
+            compute_json(&json!({ "missing": [{ "var": "c" }, "a"] }))?,
+            json!(["foo"])
         );
+        Ok(())
     }
 }
@@ -1,47 +1,51 @@
 use serde_json::Value;
 
-use super::{logic, Data, Expression};
+use super::{logic, Data, Expression, Path};
 
 /// Takes a minimum number of data keys that are required, and an array of keys to search for
 /// (same format as `var` or `missing`). Returns an empty array if the minimum is met, or an array
 /// of the missing keys otherwise.
-pub fn compute(args: &[Expression], data: &Data) -> Value {
-    let mut min_num = args
-        .get(0)
-        .map(|arg| arg.compute(data))
+pub fn compute(args: &[Expression], data: &Data) -> Result<Value, Value> {
+    let mut values = Expression::compute_all(args, data)?.into_iter();
+    let mut min_num = values
+        .next()
         .and_then(|arg| logic::coerce_to_f64(&arg))
         .map(|arg| arg.ceil() as u64)
         .unwrap_or(0);
 
-    let keys = match args.get(1).map(|arg| arg.compute(data)) {
-        Some(arg) => match arg {
-            Value::Array(keys) => keys,
-            _ => return Value::Array(vec![]),
+    // Constant keys come with their parsed paths.
+    let keys: Vec<(Value, Option<&Path>)> = match (values.next(), args.get(1)) {
+        (Some(keys), Some(arg)) => match (keys, arg.array_paths()) {
+            (Value::Array(keys), Some((_, paths))) => {
+                keys.into_iter().zip(paths.iter().map(Some)).collect()
+            }
+            (Value::Array(keys), None) => keys.into_iter().map(|key| (key, None)).collect(),
+            _ => return Ok(Value::Array(vec![])),
         },
-        None => return Value::Array(vec![]),
+        _ => return Ok(Value::Array(vec![])),
     };
 
     let mut result: Vec<&Value> = vec![];
 
-    for arg in keys.iter() {
+    for (key, path) in keys.iter() {
         if min_num < 1 {
-            return Value::Array(vec![]);
+            return Ok(Value::Array(vec![]));
         }
 
-        if data.get_value(arg).is_some() {
+        if data.get_value_or_by_path(key, *path).is_some() {
             min_num -= 1;
         } else {
-            result.push(arg);
+            result.push(key);
         }
     }
 
-    Value::Array(result.iter().map(|&el| el.clone()).collect())
+    Ok(Value::Array(result.iter().map(|&el| el.clone()).collect()))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::compute_const_with_data;
+    use crate::compute_lazy_with_data;
     use serde_json::json;
 
     #[test]
@@ -49,45 +53,45 @@ mod tests {
         let data_json = json!({"a": 5, "b": 6});
         let data = Data::from_json(&data_json);
 
-        assert_eq!(compute_const_with_data!(&[], &data), json!([]));
-        assert_eq!(compute_const_with_data!(&[json!("a")], &data), json!([]));
-        assert_eq!(compute_const_with_data!(&[json!(1)], &data), json!([]));
+        assert_eq!(compute_lazy_with_data!(&[], &data), Ok(json!([])));
+        assert_eq!(compute_lazy_with_data!(&[json!("a")], &data), Ok(json!([])));
+        assert_eq!(compute_lazy_with_data!(&[json!(1)], &data), Ok(json!([])));
         assert_eq!(
-            compute_const_with_data!(&[json!(1), json!([])], &data),
-            json!([])
+            compute_lazy_with_data!(&[json!(1), json!([])], &data),
+            Ok(json!([]))
         );
         assert_eq!(
-            compute_const_with_data!(&[json!(0), json!(["a"])], &data),
-            json!([])
+            compute_lazy_with_data!(&[json!(0), json!(["a"])], &data),
+            Ok(json!([]))
         );
         assert_eq!(
-            compute_const_with_data!(&[json!(1), json!(["a"])], &data),
-            json!([])
+            compute_lazy_with_data!(&[json!(1), json!(["a"])], &data),
+            Ok(json!([]))
         );
         assert_eq!(
-            compute_const_with_data!(&[json!(1), json!(["c"])], &data),
-            json!(["c"])
+            compute_lazy_with_data!(&[json!(1), json!(["c"])], &data),
+            Ok(json!(["c"]))
         );
         assert_eq!(
-            compute_const_with_data!(&[json!(2), json!(["a", "b", "c"])], &data),
-            json!([])
+            compute_lazy_with_data!(&[json!(2), json!(["a", "b", "c"])], &data),
+            Ok(json!([]))
         );
         assert_eq!(
-            compute_const_with_data!(&[json!(2), json!(["a", "c", "d"])], &data),
-            json!(["c", "d"])
+            compute_lazy_with_data!(&[json!(2), json!(["a", "c", "d"])], &data),
+            Ok(json!(["c", "d"]))
         );
 
         assert_eq!(
-            compute_const_with_data!(&[json!(1.9), json!(["a", "b", "d", "e"])], &data),
-            json!([])
+            compute_lazy_with_data!(&[json!(1.9), json!(["a", "b", "d", "e"])], &data),
+            Ok(json!([]))
         );
         assert_eq!(
-            compute_const_with_data!(&[json!(2), json!(["a", "b", "d", "e"])], &data),
-            json!([])
+            compute_lazy_with_data!(&[json!(2), json!(["a", "b", "d", "e"])], &data),
+            Ok(json!([]))
         );
         assert_eq!(
-            compute_const_with_data!(&[json!(2.1), json!(["a", "b", "d", "e"])], &data),
-            json!(["d", "e"])
+            compute_lazy_with_data!(&[json!(2.1), json!(["a", "b", "d", "e"])], &data),
+            Ok(json!(["d", "e"]))
         );
     }
 }
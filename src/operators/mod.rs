@@ -1,46 +1,114 @@
 mod test_helper;
 
+#[cfg(not(feature = "decimal"))]
 mod addition;
 mod all;
 mod and;
+mod binding;
 mod cat;
+mod count;
+#[cfg(feature = "datetime")]
+mod datetime;
+#[cfg(feature = "decimal")]
+mod decimal;
+#[cfg(not(feature = "decimal"))]
 mod division;
 mod double_negation;
+mod ends_with;
+mod entries;
 mod equality;
 mod filter;
+mod find;
+mod flatten;
+mod get;
 mod greater_equal_than;
 mod greater_than;
+mod group_by;
 mod if_else;
+mod index_of;
 mod is_in;
+mod join;
+mod keys;
+mod length;
 mod less_equal_than;
 mod less_than;
 mod log;
-mod logic;
+pub mod logic;
+mod lower;
 mod map;
+#[cfg(feature = "regex")]
+mod matching;
+mod math;
+#[cfg(not(feature = "decimal"))]
 mod max;
 mod merge;
+mod merge_objects;
+#[cfg(not(feature = "decimal"))]
 mod min;
 mod missing;
 mod missing_some;
+#[cfg(not(feature = "decimal"))]
 mod modulo;
+#[cfg(not(feature = "decimal"))]
 mod multiplication;
 mod negation;
 mod none;
 mod not_equal;
+mod object;
+mod omit;
 mod or;
+mod pad_start;
+mod pick;
+mod range;
 mod reduce;
+mod replace;
+mod reverse;
+mod slice;
 mod some;
+mod sort;
+mod split;
+mod starts_with;
 mod strict_equality;
 mod strict_not_equal;
 mod substr;
+#[cfg(not(feature = "decimal"))]
 mod subtraction;
+mod sum;
+mod throw;
+mod trim;
+mod try_catch;
+mod types;
+mod unique;
+mod upper;
+mod values;
 mod variable;
+mod zip;
 
 use serde_json::Value;
 
+use super::data::Path;
 use super::expression::Expression;
 use super::Data;
 
+pub use binding::evaluate_let;
+pub use group_by::group;
+#[cfg(feature = "regex")]
+pub use matching::{apply_pattern, parse_pattern, Pattern};
+pub use sort::sort_by_keys;
+pub use try_catch::try_alternatives;
+
+/// The function that computes an operator.
+#[derive(Copy, Clone)]
+pub enum Function {
+    /// Is called with the values of the arguments, which are all evaluated before, from left to
+    /// right.
+    Eager(fn(&[Value], &Data) -> Value),
+    /// Evaluates the arguments itself, like `if`, which evaluates only one of its branches, or
+    /// `map`, which evaluates its logic for every element of an array. Errors with the thrown
+    /// value if an evaluated argument throws an error that is not caught.
+    Lazy(fn(&[Expression], &Data) -> Result<Value, Value>),
+}
+
 /// Represents a JsonLogic operator.
 #[derive(Debug, PartialEq, Copy, Clone)]
 pub enum Operator {
@@ -173,6 +241,141 @@ pub enum Operator {
     /// `var` operations inside the second argument expression are relative to the array element
     /// being tested.
     None,
+    /// Converts a string to upper case.
+    Upper,
+    /// Converts a string to lower case.
+    Lower,
+    /// Removes leading and trailing whitespace from a string.
+    Trim,
+    /// Splits a string at every occurrence of a separator into an array of strings.
+    Split,
+    /// Joins the elements of an array into a string with a separator, which defaults to `","`.
+    Join,
+    /// Replaces every occurrence of a search string in a string.
+    Replace,
+    /// Tests whether a string starts with another string.
+    StartsWith,
+    /// Tests whether a string ends with another string.
+    EndsWith,
+    /// Returns the number of elements of an array or the number of characters of a string.
+    Length,
+    /// Pads a string at the start to a length in characters, with spaces or the given padding.
+    PadStart,
+    /// Returns the character index of the first occurrence of a string in another string, or `-1`.
+    IndexOf,
+    /// Sorts an array, by the elements or by a key expression that is evaluated for each element.
+    Sort,
+    /// Removes duplicate elements from an array.
+    Unique,
+    /// Flattens nested arrays into an array, one level deep or as deep as the second argument.
+    Flatten,
+    /// Returns the elements of an array between a start and an end index.
+    Slice,
+    /// Returns the first element of an array for which a condition is truthy.
+    Find,
+    /// Returns the number of elements of an array for which a condition is truthy.
+    Count,
+    /// Sums up the elements of an array.
+    Sum,
+    /// Groups the elements of an array into an object by a key expression that is evaluated for each
+    /// element.
+    GroupBy,
+    /// Combines arrays into an array of arrays of the elements at the same index.
+    Zip,
+    /// Reverses an array.
+    Reverse,
+    /// Creates an array of the numbers in a range.
+    Range,
+    /// Returns the keys of an object.
+    Keys,
+    /// Returns the values of an object.
+    Values,
+    /// Returns the key-value pairs of an object as arrays of two elements.
+    Entries,
+    /// Gets the value of a computed key from an object or array, with an optional default.
+    Get,
+    /// Returns an object with only the given keys of another object.
+    Pick,
+    /// Returns an object without the given keys.
+    Omit,
+    /// Merges objects into one object, where later keys win.
+    MergeObjects,
+    /// Builds an object from pairs of key and value arguments.
+    Object,
+    /// Returns the absolute value of a number.
+    Abs,
+    /// Rounds a number down.
+    Floor,
+    /// Rounds a number up.
+    Ceil,
+    /// Rounds a number to a number of decimal places, which defaults to 0.
+    Round,
+    /// Removes the fractional digits of a number.
+    Trunc,
+    /// Raises a number to a power.
+    Pow,
+    /// Returns the square root of a number.
+    Sqrt,
+    /// Returns the natural logarithm of a number, or the logarithm to a base.
+    Ln,
+    /// Returns e raised to the power of a number.
+    Exp,
+    /// Limits a number to a range.
+    Clamp,
+    /// Returns the type of a value as a string, like `"number"`.
+    TypeOf,
+    /// Tests whether a value is a number.
+    IsNumber,
+    /// Tests whether a value is a string.
+    IsString,
+    /// Tests whether a value is an array.
+    IsArray,
+    /// Converts a value into a number.
+    ToNumber,
+    /// Parses the number at the start of a string.
+    ParseFloat,
+    /// Converts a value into a string.
+    ToString,
+    /// Converts a value into a boolean by its truthiness.
+    ToBool,
+    /// Throws a value as an error, which aborts the evaluation unless it is caught by `try`.
+    Throw,
+    /// Evaluates alternatives until one of them does not throw an error.
+    Try,
+    /// Binds names to values for the evaluation of an expression.
+    Let,
+    /// Reads a name bound by `let`.
+    Ref,
+    /// Returns the current time as an RFC 3339 timestamp. Takes no arguments.
+    #[cfg(feature = "datetime")]
+    Now,
+    /// Parses a timestamp, optionally converting it into the offset of the second argument.
+    #[cfg(feature = "datetime")]
+    DateTime,
+    /// Adds an amount of a unit, like `"days"`, to a timestamp.
+    #[cfg(feature = "datetime")]
+    DateAdd,
+    /// Returns the number of whole units between two timestamps.
+    #[cfg(feature = "datetime")]
+    DateDiff,
+    /// Truncates a timestamp to the start of a unit, like the first day of the month.
+    #[cfg(feature = "datetime")]
+    DateTrunc,
+    /// Extracts a part of a timestamp, like the weekday or the hour.
+    #[cfg(feature = "datetime")]
+    DatePart,
+    /// Tests whether the first timestamp is before the second one.
+    #[cfg(feature = "datetime")]
+    DateBefore,
+    /// Tests whether the first timestamp is after the second one.
+    #[cfg(feature = "datetime")]
+    DateAfter,
+    /// Tests whether a string matches a regular expression.
+    #[cfg(feature = "regex")]
+    Match,
+    /// Returns the capture groups of the first match of a regular expression in a string.
+    #[cfg(feature = "regex")]
+    Captures,
 }
 
 impl Operator {
@@ -214,49 +417,356 @@ impl Operator {
             "all" => Some(Operator::All),
             "some" => Some(Operator::Some),
             "none" => Some(Operator::None),
+            "upper" => Some(Operator::Upper),
+            "lower" => Some(Operator::Lower),
+            "trim" => Some(Operator::Trim),
+            "split" => Some(Operator::Split),
+            "join" => Some(Operator::Join),
+            "replace" => Some(Operator::Replace),
+            "starts_with" => Some(Operator::StartsWith),
+            "ends_with" => Some(Operator::EndsWith),
+            "length" => Some(Operator::Length),
+            "pad_start" => Some(Operator::PadStart),
+            "index_of" => Some(Operator::IndexOf),
+            "sort" => Some(Operator::Sort),
+            "unique" => Some(Operator::Unique),
+            "flatten" => Some(Operator::Flatten),
+            "slice" => Some(Operator::Slice),
+            "find" => Some(Operator::Find),
+            "count" => Some(Operator::Count),
+            "sum" => Some(Operator::Sum),
+            "group_by" => Some(Operator::GroupBy),
+            "zip" => Some(Operator::Zip),
+            "reverse" => Some(Operator::Reverse),
+            "range" => Some(Operator::Range),
+            "keys" => Some(Operator::Keys),
+            "values" => Some(Operator::Values),
+            "entries" => Some(Operator::Entries),
+            "get" => Some(Operator::Get),
+            "pick" => Some(Operator::Pick),
+            "omit" => Some(Operator::Omit),
+            "merge_objects" => Some(Operator::MergeObjects),
+            "object" => Some(Operator::Object),
+            "abs" => Some(Operator::Abs),
+            "floor" => Some(Operator::Floor),
+            "ceil" => Some(Operator::Ceil),
+            "round" => Some(Operator::Round),
+            "trunc" => Some(Operator::Trunc),
+            "pow" => Some(Operator::Pow),
+            "sqrt" => Some(Operator::Sqrt),
+            "ln" => Some(Operator::Ln),
+            "exp" => Some(Operator::Exp),
+            "clamp" => Some(Operator::Clamp),
+            "typeof" => Some(Operator::TypeOf),
+            "is_number" => Some(Operator::IsNumber),
+            "is_string" => Some(Operator::IsString),
+            "is_array" => Some(Operator::IsArray),
+            "to_number" => Some(Operator::ToNumber),
+            "parse_float" => Some(Operator::ParseFloat),
+            "to_string" => Some(Operator::ToString),
+            "to_bool" => Some(Operator::ToBool),
+            "throw" => Some(Operator::Throw),
+            "try" => Some(Operator::Try),
+            "let" => Some(Operator::Let),
+            "ref" => Some(Operator::Ref),
+            #[cfg(feature = "datetime")]
+            "now" => Some(Operator::Now),
+            #[cfg(feature = "datetime")]
+            "datetime" => Some(Operator::DateTime),
+            #[cfg(feature = "datetime")]
+            "date_add" => Some(Operator::DateAdd),
+            #[cfg(feature = "datetime")]
+            "date_diff" => Some(Operator::DateDiff),
+            #[cfg(feature = "datetime")]
+            "date_trunc" => Some(Operator::DateTrunc),
+            #[cfg(feature = "datetime")]
+            "date_part" => Some(Operator::DatePart),
+            #[cfg(feature = "datetime")]
+            "date_before" => Some(Operator::DateBefore),
+            #[cfg(feature = "datetime")]
+            "date_after" => Some(Operator::DateAfter),
+            #[cfg(feature = "regex")]
+            "match" => Some(Operator::Match),
+            #[cfg(feature = "regex")]
+            "captures" => Some(Operator::Captures),
             _ => None,
         }
     }
 
-    pub fn compute(self, args: &[Expression], data: &Data) -> Value {
-        let compute_fn = match self {
-            Operator::Addition => addition::compute,
-            Operator::All => all::compute,
-            Operator::And => and::compute,
-            Operator::Cat => cat::compute,
-            Operator::Division => division::compute,
-            Operator::DoubleNegation => double_negation::compute,
-            Operator::Equal => equality::compute,
-            Operator::Filter => filter::compute,
-            Operator::GreaterEqualThan => greater_equal_than::compute,
-            Operator::GreaterThan => greater_than::compute,
-            Operator::If => if_else::compute,
-            Operator::In => is_in::compute,
-            Operator::LessEqualThan => less_equal_than::compute,
-            Operator::LessThan => less_than::compute,
-            Operator::Log => log::compute,
-            Operator::Max => max::compute,
-            Operator::Merge => merge::compute,
-            Operator::Min => min::compute,
-            Operator::MissingSome => missing_some::compute,
-            Operator::Missing => missing::compute,
-            Operator::Map => map::compute,
-            Operator::Modulo => modulo::compute,
-            Operator::Multiplication => multiplication::compute,
-            Operator::Negation => negation::compute,
-            Operator::None => none::compute,
-            Operator::NotEqual => not_equal::compute,
-            Operator::Or => or::compute,
-            Operator::Reduce => reduce::compute,
-            Operator::Some => some::compute,
-            Operator::StrictEqual => strict_equality::compute,
-            Operator::StrictNotEqual => strict_not_equal::compute,
-            Operator::Substr => substr::compute,
-            Operator::Subtraction => subtraction::compute,
-            Operator::Variable => variable::compute,
-        };
+    /// Returns the string representation of this operator, i.e. the key it has in a JsonLogic
+    /// rule.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Operator::Equal => "==",
+            Operator::StrictEqual => "===",
+            Operator::NotEqual => "!=",
+            Operator::StrictNotEqual => "!==",
+            Operator::Variable => "var",
+            Operator::Negation => "!",
+            Operator::DoubleNegation => "!!",
+            Operator::If => "if",
+            Operator::Or => "or",
+            Operator::And => "and",
+            Operator::LessThan => "<",
+            Operator::LessEqualThan => "<=",
+            Operator::GreaterThan => ">",
+            Operator::GreaterEqualThan => ">=",
+            Operator::Missing => "missing",
+            Operator::MissingSome => "missing_some",
+            Operator::Min => "min",
+            Operator::Max => "max",
+            Operator::Addition => "+",
+            Operator::Subtraction => "-",
+            Operator::Multiplication => "*",
+            Operator::Division => "/",
+            Operator::Modulo => "%",
+            Operator::In => "in",
+            Operator::Cat => "cat",
+            Operator::Substr => "substr",
+            Operator::Log => "log",
+            Operator::Merge => "merge",
+            Operator::Map => "map",
+            Operator::Filter => "filter",
+            Operator::Reduce => "reduce",
+            Operator::All => "all",
+            Operator::Some => "some",
+            Operator::None => "none",
+            Operator::Upper => "upper",
+            Operator::Lower => "lower",
+            Operator::Trim => "trim",
+            Operator::Split => "split",
+            Operator::Join => "join",
+            Operator::Replace => "replace",
+            Operator::StartsWith => "starts_with",
+            Operator::EndsWith => "ends_with",
+            Operator::Length => "length",
+            Operator::PadStart => "pad_start",
+            Operator::IndexOf => "index_of",
+            Operator::Sort => "sort",
+            Operator::Unique => "unique",
+            Operator::Flatten => "flatten",
+            Operator::Slice => "slice",
+            Operator::Find => "find",
+            Operator::Count => "count",
+            Operator::Sum => "sum",
+            Operator::GroupBy => "group_by",
+            Operator::Zip => "zip",
+            Operator::Reverse => "reverse",
+            Operator::Range => "range",
+            Operator::Keys => "keys",
+            Operator::Values => "values",
+            Operator::Entries => "entries",
+            Operator::Get => "get",
+            Operator::Pick => "pick",
+            Operator::Omit => "omit",
+            Operator::MergeObjects => "merge_objects",
+            Operator::Object => "object",
+            Operator::Abs => "abs",
+            Operator::Floor => "floor",
+            Operator::Ceil => "ceil",
+            Operator::Round => "round",
+            Operator::Trunc => "trunc",
+            Operator::Pow => "pow",
+            Operator::Sqrt => "sqrt",
+            Operator::Ln => "ln",
+            Operator::Exp => "exp",
+            Operator::Clamp => "clamp",
+            Operator::TypeOf => "typeof",
+            Operator::IsNumber => "is_number",
+            Operator::IsString => "is_string",
+            Operator::IsArray => "is_array",
+            Operator::ToNumber => "to_number",
+            Operator::ParseFloat => "parse_float",
+            Operator::ToString => "to_string",
+            Operator::ToBool => "to_bool",
+            Operator::Throw => "throw",
+            Operator::Try => "try",
+            Operator::Let => "let",
+            Operator::Ref => "ref",
+            #[cfg(feature = "datetime")]
+            Operator::Now => "now",
+            #[cfg(feature = "datetime")]
+            Operator::DateTime => "datetime",
+            #[cfg(feature = "datetime")]
+            Operator::DateAdd => "date_add",
+            #[cfg(feature = "datetime")]
+            Operator::DateDiff => "date_diff",
+            #[cfg(feature = "datetime")]
+            Operator::DateTrunc => "date_trunc",
+            #[cfg(feature = "datetime")]
+            Operator::DatePart => "date_part",
+            #[cfg(feature = "datetime")]
+            Operator::DateBefore => "date_before",
+            #[cfg(feature = "datetime")]
+            Operator::DateAfter => "date_after",
+            #[cfg(feature = "regex")]
+            Operator::Match => "match",
+            #[cfg(feature = "regex")]
+            Operator::Captures => "captures",
+        }
+    }
 
-        compute_fn(args, data)
+    /// Computes the operator with the given arguments. Errors with the thrown value if the
+    /// operator or one of its arguments throws an error that is not caught.
+    pub fn compute(self, args: &[Expression], data: &Data) -> Result<Value, Value> {
+        match self.function() {
+            Function::Lazy(compute) => compute(args, data),
+            // Avoid allocating for the common case of up to three arguments.
+            Function::Eager(compute) => Ok(match args {
+                [] => compute(&[], data),
+                [a] => compute(&[a.compute(data)?], data),
+                [a, b] => compute(&[a.compute(data)?, b.compute(data)?], data),
+                [a, b, c] => compute(
+                    &[a.compute(data)?, b.compute(data)?, c.compute(data)?],
+                    data,
+                ),
+                _ => compute(&Expression::compute_all(args, data)?, data),
+            }),
+        }
+    }
+
+    /// Returns the function that computes this operator.
+    pub fn function(self) -> Function {
+        use Function::{Eager, Lazy};
+
+        match self {
+            #[cfg(not(feature = "decimal"))]
+            Operator::Addition => Eager(addition::compute),
+            #[cfg(feature = "decimal")]
+            Operator::Addition => Eager(decimal::addition),
+            Operator::All => Lazy(all::compute),
+            Operator::And => Lazy(and::compute),
+            Operator::Cat => Eager(cat::compute),
+            #[cfg(not(feature = "decimal"))]
+            Operator::Division => Eager(division::compute),
+            #[cfg(feature = "decimal")]
+            Operator::Division => Eager(decimal::division),
+            Operator::DoubleNegation => Eager(double_negation::compute),
+            Operator::Equal => Eager(equality::compute),
+            Operator::Filter => Lazy(filter::compute),
+            Operator::GreaterEqualThan => Eager(greater_equal_than::compute),
+            Operator::GreaterThan => Eager(greater_than::compute),
+            Operator::If => Lazy(if_else::compute),
+            Operator::In => Eager(is_in::compute),
+            Operator::LessEqualThan => Eager(less_equal_than::compute),
+            Operator::LessThan => Eager(less_than::compute),
+            Operator::Log => Eager(log::compute),
+            #[cfg(not(feature = "decimal"))]
+            Operator::Max => Eager(max::compute),
+            #[cfg(feature = "decimal")]
+            Operator::Max => Eager(decimal::max),
+            Operator::Merge => Eager(merge::compute),
+            #[cfg(not(feature = "decimal"))]
+            Operator::Min => Eager(min::compute),
+            #[cfg(feature = "decimal")]
+            Operator::Min => Eager(decimal::min),
+            Operator::MissingSome => Lazy(missing_some::compute),
+            Operator::Missing => Lazy(missing::compute),
+            Operator::Map => Lazy(map::compute),
+            #[cfg(not(feature = "decimal"))]
+            Operator::Modulo => Eager(modulo::compute),
+            #[cfg(feature = "decimal")]
+            Operator::Modulo => Eager(decimal::modulo),
+            #[cfg(not(feature = "decimal"))]
+            Operator::Multiplication => Eager(multiplication::compute),
+            #[cfg(feature = "decimal")]
+            Operator::Multiplication => Eager(decimal::multiplication),
+            Operator::Negation => Eager(negation::compute),
+            Operator::None => Lazy(none::compute),
+            Operator::NotEqual => Eager(not_equal::compute),
+            Operator::Or => Lazy(or::compute),
+            Operator::Reduce => Lazy(reduce::compute),
+            Operator::Some => Lazy(some::compute),
+            Operator::StrictEqual => Eager(strict_equality::compute),
+            Operator::StrictNotEqual => Eager(strict_not_equal::compute),
+            Operator::Substr => Eager(substr::compute),
+            #[cfg(not(feature = "decimal"))]
+            Operator::Subtraction => Eager(subtraction::compute),
+            #[cfg(feature = "decimal")]
+            Operator::Subtraction => Eager(decimal::subtraction),
+            Operator::Variable => Lazy(variable::compute),
+            Operator::Upper => Eager(upper::compute),
+            Operator::Lower => Eager(lower::compute),
+            Operator::Trim => Eager(trim::compute),
+            Operator::Split => Eager(split::compute),
+            Operator::Join => Eager(join::compute),
+            Operator::Replace => Eager(replace::compute),
+            Operator::StartsWith => Eager(starts_with::compute),
+            Operator::EndsWith => Eager(ends_with::compute),
+            Operator::Length => Eager(length::compute),
+            Operator::PadStart => Eager(pad_start::compute),
+            Operator::IndexOf => Eager(index_of::compute),
+            Operator::Sort => Lazy(sort::compute),
+            Operator::Unique => Eager(unique::compute),
+            Operator::Flatten => Eager(flatten::compute),
+            Operator::Slice => Eager(slice::compute),
+            Operator::Find => Lazy(find::compute),
+            Operator::Count => Lazy(count::compute),
+            Operator::Sum => Eager(sum::compute),
+            Operator::GroupBy => Lazy(group_by::compute),
+            Operator::Zip => Eager(zip::compute),
+            Operator::Reverse => Eager(reverse::compute),
+            Operator::Range => Eager(range::compute),
+            Operator::Keys => Eager(keys::compute),
+            Operator::Values => Eager(values::compute),
+            Operator::Entries => Eager(entries::compute),
+            Operator::Get => Eager(get::compute),
+            Operator::Pick => Eager(pick::compute),
+            Operator::Omit => Eager(omit::compute),
+            Operator::MergeObjects => Eager(merge_objects::compute),
+            Operator::Object => Eager(object::compute),
+            Operator::Abs => Eager(math::abs),
+            Operator::Floor => Eager(math::floor),
+            Operator::Ceil => Eager(math::ceil),
+            #[cfg(not(feature = "decimal"))]
+            Operator::Round => Eager(math::round),
+            #[cfg(feature = "decimal")]
+            Operator::Round => Eager(decimal::round),
+            Operator::Trunc => Eager(math::trunc),
+            Operator::Pow => Eager(math::pow),
+            Operator::Sqrt => Eager(math::sqrt),
+            Operator::Ln => Eager(math::ln),
+            Operator::Exp => Eager(math::exp),
+            Operator::Clamp => Eager(math::clamp),
+            Operator::TypeOf => Eager(types::type_of),
+            Operator::IsNumber => Eager(types::is_number),
+            Operator::IsString => Eager(types::is_string),
+            Operator::IsArray => Eager(types::is_array),
+            #[cfg(not(feature = "decimal"))]
+            Operator::ToNumber => Eager(types::to_number),
+            #[cfg(feature = "decimal")]
+            Operator::ToNumber => Eager(decimal::to_number),
+            #[cfg(not(feature = "decimal"))]
+            Operator::ParseFloat => Eager(types::parse_float),
+            #[cfg(feature = "decimal")]
+            Operator::ParseFloat => Eager(decimal::parse_float),
+            Operator::ToString => Eager(types::to_string),
+            Operator::ToBool => Eager(types::to_bool),
+            Operator::Throw => Lazy(throw::compute),
+            Operator::Try => Lazy(try_catch::compute),
+            Operator::Let => Lazy(binding::compute_let),
+            Operator::Ref => Eager(binding::compute_ref),
+            #[cfg(feature = "datetime")]
+            Operator::Now => Eager(datetime::now),
+            #[cfg(feature = "datetime")]
+            Operator::DateTime => Eager(datetime::datetime),
+            #[cfg(feature = "datetime")]
+            Operator::DateAdd => Eager(datetime::date_add),
+            #[cfg(feature = "datetime")]
+            Operator::DateDiff => Eager(datetime::date_diff),
+            #[cfg(feature = "datetime")]
+            Operator::DateTrunc => Eager(datetime::date_trunc),
+            #[cfg(feature = "datetime")]
+            Operator::DatePart => Eager(datetime::date_part),
+            #[cfg(feature = "datetime")]
+            Operator::DateBefore => Eager(datetime::date_before),
+            #[cfg(feature = "datetime")]
+            Operator::DateAfter => Eager(datetime::date_after),
+            #[cfg(feature = "regex")]
+            Operator::Match => Lazy(matching::compute_match),
+            #[cfg(feature = "regex")]
+            Operator::Captures => Lazy(matching::compute_captures),
+        }
     }
 }
 
@@ -305,4 +815,128 @@ mod tests {
         assert_eq!(Operator::from_str("none"), Some(Operator::None));
         assert_eq!(Operator::from_str("some"), Some(Operator::Some));
     }
+
+    #[test]
+    fn as_str() {
+        for s in [
+            "==",
+            "===",
+            "!=",
+            "!==",
+            "var",
+            "!",
+            "!!",
+            "if",
+            "or",
+            "and",
+            "<",
+            "<=",
+            ">",
+            ">=",
+            "missing",
+            "missing_some",
+            "min",
+            "max",
+            "+",
+            "-",
+            "*",
+            "/",
+            "%",
+            "in",
+            "cat",
+            "substr",
+            "log",
+            "merge",
+            "map",
+            "filter",
+            "reduce",
+            "all",
+            "some",
+            "none",
+            "upper",
+            "lower",
+            "trim",
+            "split",
+            "join",
+            "replace",
+            "starts_with",
+            "ends_with",
+            "length",
+            "pad_start",
+            "index_of",
+            "sort",
+            "unique",
+            "flatten",
+            "slice",
+            "find",
+            "count",
+            "sum",
+            "group_by",
+            "zip",
+            "reverse",
+            "range",
+            "keys",
+            "values",
+            "entries",
+            "get",
+            "pick",
+            "omit",
+            "merge_objects",
+            "object",
+            "abs",
+            "floor",
+            "ceil",
+            "round",
+            "trunc",
+            "pow",
+            "sqrt",
+            "ln",
+            "exp",
+            "clamp",
+            "typeof",
+            "is_number",
+            "is_string",
+            "is_array",
+            "to_number",
+            "parse_float",
+            "to_string",
+            "to_bool",
+            "throw",
+            "try",
+            "let",
+            "ref",
+        ]
+        .iter()
+        {
+            assert_eq!(Operator::from_str(s).unwrap().as_str(), *s);
+        }
+    }
+
+    #[cfg(feature = "datetime")]
+    #[test]
+    fn datetime_operators() {
+        for s in [
+            "now",
+            "datetime",
+            "date_add",
+            "date_diff",
+            "date_trunc",
+            "date_part",
+            "date_before",
+            "date_after",
+        ]
+        .iter()
+        {
+            assert_eq!(Operator::from_str(s).unwrap().as_str(), *s);
+        }
+    }
+
+    #[cfg(feature = "regex")]
+    #[test]
+    fn regex_operators() {
+        assert_eq!(Operator::from_str("match"), Some(Operator::Match));
+        assert_eq!(Operator::from_str("captures"), Some(Operator::Captures));
+        assert_eq!(Operator::Match.as_str(), "match");
+        assert_eq!(Operator::Captures.as_str(), "captures");
+    }
 }
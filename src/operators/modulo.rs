@@ -1,31 +1,34 @@
-use serde_json::{Number, Value};
+use serde_json::Value;
 
-use super::{logic, Data, Expression};
+use super::{logic, Data};
 
 /// %, finds the remainder after the first argument is divided by the second argument.
-pub fn compute(args: &[Expression], data: &Data) -> Value {
-    let a = match args
-        .get(0)
-        .map(|arg| arg.compute(data))
-        .and_then(|a| logic::coerce_to_f64(&a))
-    {
+/// The remainder is an integer if both arguments are integers. Returns `Value::Null` if it is NaN.
+pub fn compute(args: &[Value], _data: &Data) -> Value {
+    let a = match args.get(0) {
         Some(a) => a,
         None => return Value::Null,
     };
-
-    let b = match args
-        .get(1)
-        .map(|arg| arg.compute(data))
-        .and_then(|b| logic::coerce_to_f64(&b))
-    {
+    let b = match args.get(1) {
         Some(b) => b,
         None => return Value::Null,
     };
 
-    match Number::from_f64(a % b) {
-        Some(num) => Value::Number(num),
-        None => Value::Null,
+    // The remainder of a division by zero is NaN, which is handled with floats below.
+    if let Some(num) = logic::as_integer(a)
+        .zip(logic::as_integer(b))
+        .and_then(|(a, b)| a.checked_rem(b))
+        .and_then(logic::integer_to_number)
+    {
+        return Value::Number(num);
     }
+
+    let (a, b) = match (logic::coerce_to_f64(a), logic::coerce_to_f64(b)) {
+        (Some(a), Some(b)) => (a, b),
+        _ => return Value::Null,
+    };
+
+    logic::f64_to_value(a % b)
 }
 
 #[cfg(test)]
@@ -41,8 +44,20 @@ mod tests {
         assert_eq!(compute_const!(json!(1)), Value::Null);
         assert_eq!(compute_const!(json!(1), json!(0)), Value::Null);
 
-        assert_eq!(compute_const!(json!(1), json!(2)), json!(1.0));
-        assert_eq!(compute_const!(json!(101), json!(2)), json!(1.0));
-        assert_eq!(compute_const!(json!(102), json!(2)), json!(0.0));
+        assert_eq!(compute_const!(json!(1), json!(2)), json!(1));
+        assert_eq!(compute_const!(json!(101), json!(2)), json!(1));
+        assert_eq!(compute_const!(json!(102), json!(2)), json!(0));
+    }
+
+    #[test]
+    fn integers() {
+        assert_eq!(compute_const!(json!(-7), json!(3)), json!(-1));
+        assert_eq!(compute_const!(json!(7), json!("-3")), json!(1));
+        assert_eq!(compute_const!(json!(7.5), json!(2)), json!(1.5));
+        assert_eq!(compute_const!(json!(null), json!(2)), json!(0.0));
+        assert_eq!(
+            compute_const!(json!(u64::MAX), json!(10)),
+            json!(u64::MAX % 10)
+        );
     }
 }
@@ -1,28 +1,36 @@
-use serde_json::{Number, Value};
+use serde_json::Value;
 
-use super::{logic, Data, Expression};
+use super::{logic, Data};
 
 /// *, takes an arbitrary number of arguments and multiplicates them. Returns `Value::Null` if one
-/// argument cannot be coerced into a number or if no arguments are passed.
+/// argument cannot be coerced into a number, if the product is not finite or if no arguments are
+/// passed.
 /// If only one argument is specified, it is returned as is, to match the behaviour of the
 /// javascript implementation of JsonLogic.
-pub fn compute(args: &[Expression], data: &Data) -> Value {
+/// The product is an integer if all arguments are integers, unless it overflows.
+pub fn compute(args: &[Value], _data: &Data) -> Value {
     match args {
         [] => Value::Null,
-        [arg] => arg.compute(data),
+        [arg] => arg.clone(),
         _ => {
             let mut result = 1f64;
+            let mut int_result = Some(1i128);
 
             for arg in args {
+                int_result =
+                    int_result.and_then(|product| product.checked_mul(logic::as_integer(arg)?));
                 // Use parseFloat like in the javascript implementation.
                 // parseFloat(null) is NaN, whereas coerce_to_f64 would return 0.
-                match logic::parse_float(&arg.compute(data)) {
+                match logic::parse_float(arg) {
                     Some(num) => result *= num,
                     None => return Value::Null,
                 }
             }
 
-            Value::Number(Number::from_f64(result).unwrap())
+            match int_result.and_then(logic::integer_to_number) {
+                Some(num) => Value::Number(num),
+                None => logic::f64_to_value(result),
+            }
         }
     }
 }
@@ -39,10 +47,24 @@ mod tests {
         assert_eq!(compute_const!(Value::Null), Value::Null);
         assert_eq!(compute_const!(json!("foo")), json!("foo"));
         assert_eq!(compute_const!(json!("6")), json!("6"));
-        assert_eq!(compute_const!(json!(4), json!(2)), json!(8.0));
+        assert_eq!(compute_const!(json!(4), json!(2)), json!(8));
         assert_eq!(
             compute_const!(json!(4), json!(2), json!(2), json!(2)),
-            json!(32.0)
+            json!(32)
+        );
+    }
+
+    #[test]
+    fn integers() {
+        assert_eq!(compute_const!(json!(4), json!(0.5)), json!(2.0));
+        assert_eq!(compute_const!(json!("-4"), json!(2)), json!(-8));
+        assert_eq!(
+            compute_const!(json!(4294967296u64), json!(4294967295u64)),
+            json!(18446744069414584320u64)
+        );
+        assert_eq!(
+            compute_const!(json!(4294967296u64), json!(4294967296u64)),
+            json!(18446744073709551616f64)
         );
     }
 }
@@ -0,0 +1,41 @@
+use serde_json::{Map, Value};
+
+use super::{logic, Data};
+
+/// Builds an object from pairs of arguments, where the first argument of a pair is the key,
+/// coerced into a string, and the second one is the value. A missing value of the last pair is
+/// `Value::Null`. If a key occurs several times, the last value is taken.
+pub fn compute(args: &[Value], _data: &Data) -> Value {
+    let mut result = Map::new();
+    for pair in args.chunks(2) {
+        let key = logic::coerce_to_str(&pair[0]);
+        let value = pair.get(1).cloned().unwrap_or(Value::Null);
+        result.insert(key, value);
+    }
+
+    Value::Object(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute_const;
+    use serde_json::json;
+
+    #[test]
+    fn test() {
+        assert_eq!(compute_const!(), json!({}));
+        assert_eq!(compute_const!(json!("a")), json!({"a": null}));
+        assert_eq!(
+            compute_const!(
+                json!("a"),
+                json!(1),
+                json!(2),
+                json!([3]),
+                json!("a"),
+                json!(4)
+            ),
+            json!({"a": 4, "2": [3]})
+        );
+    }
+}
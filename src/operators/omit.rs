@@ -0,0 +1,44 @@
+use serde_json::{Map, Value};
+
+use super::{pick, Data};
+
+/// Returns the object of the first argument without the given keys. The keys are the other
+/// arguments, or the elements of an array, coerced into strings. Returns an empty object if the
+/// first argument is not an object.
+pub fn compute(args: &[Value], _data: &Data) -> Value {
+    let mut obj = match args.first().cloned() {
+        Some(Value::Object(obj)) => obj,
+        _ => return Value::Object(Map::new()),
+    };
+
+    for key in pick::key_args(&args[1..]) {
+        obj.remove(&key);
+    }
+
+    Value::Object(obj)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute_const;
+    use serde_json::json;
+
+    #[test]
+    fn test() {
+        assert_eq!(compute_const!(), json!({}));
+        assert_eq!(compute_const!(json!("abc")), json!({}));
+        assert_eq!(
+            compute_const!(json!({"a": 1, "b": 2})),
+            json!({"a": 1, "b": 2})
+        );
+        assert_eq!(
+            compute_const!(json!({"a": 1, "b": 2, "c": 3}), json!("a"), json!("c")),
+            json!({"b": 2})
+        );
+        assert_eq!(
+            compute_const!(json!({"a": 1, "b": 2, "c": 3}), json!(["b", "x"])),
+            json!({"a": 1, "c": 3})
+        );
+    }
+}
@@ -0,0 +1,75 @@
+use serde_json::Value;
+
+use super::{logic, Data};
+
+/// The maximum length in characters `pad_start` pads a string to, so rules cannot use up the
+/// memory.
+pub const MAX_LENGTH: usize = 1 << 20;
+
+/// Pads the first argument, coerced into a string, at the start with the third argument, which
+/// defaults to `" "`, until it is as long as the second argument in characters, as defined by
+/// the string mode of the data. Like `padStart` in javascript, the padding is repeated and cut
+/// off as needed, and strings that are already long enough are returned as they are. Returns
+/// `Value::Null` if there is no argument or the length exceeds `MAX_LENGTH`.
+pub fn compute(args: &[Value], data: &Data) -> Value {
+    let s = match args.first() {
+        Some(arg) => logic::coerce_to_str(arg),
+        None => return Value::Null,
+    };
+    let length = args.get(1).and_then(logic::coerce_to_f64).unwrap_or(0f64);
+    if length > MAX_LENGTH as f64 {
+        return Value::Null;
+    }
+    let padding = match args.get(2) {
+        Some(arg) => logic::coerce_to_str(arg),
+        None => String::from(" "),
+    };
+
+    let mode = data.string_mode();
+    // Negative lengths and NaN saturate to 0.
+    let missing = (length as usize).saturating_sub(mode.length(&s));
+    let padding_len = mode.length(&padding);
+    if padding_len == 0 || missing == 0 {
+        return Value::String(s);
+    }
+    let mut result = mode.substring(&padding.repeat(missing / padding_len + 1), 0, Some(missing));
+    result.push_str(&s);
+
+    Value::String(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute_const;
+    use serde_json::json;
+
+    #[test]
+    fn test() {
+        assert_eq!(compute_const!(), Value::Null);
+        assert_eq!(compute_const!(json!("abc")), json!("abc"));
+        assert_eq!(compute_const!(json!("abc"), json!(5)), json!("  abc"));
+        assert_eq!(compute_const!(json!(7), json!(3), json!(0)), json!("007"));
+        assert_eq!(
+            compute_const!(json!("abc"), json!(10), json!("12")),
+            json!("1212121abc")
+        );
+        assert_eq!(
+            compute_const!(json!("Grüße"), json!(7), json!("ä")),
+            json!("ääGrüße")
+        );
+        assert_eq!(
+            compute_const!(json!("abc"), json!(2), json!("-")),
+            json!("abc")
+        );
+        assert_eq!(
+            compute_const!(json!("abc"), json!(-5), json!("-")),
+            json!("abc")
+        );
+        assert_eq!(
+            compute_const!(json!("abc"), json!(5), json!("")),
+            json!("abc")
+        );
+        assert_eq!(compute_const!(json!("abc"), json!(1e300)), Value::Null);
+    }
+}
@@ -0,0 +1,58 @@
+use serde_json::{Map, Value};
+
+use super::{logic, Data};
+
+/// Returns an object with only the given keys of the object of the first argument. The keys are
+/// the other arguments, or the elements of an array, coerced into strings. Keys that the object
+/// does not have are left out. Returns an empty object if the first argument is not an object.
+pub fn compute(args: &[Value], _data: &Data) -> Value {
+    let mut obj = match args.first().cloned() {
+        Some(Value::Object(obj)) => obj,
+        _ => return Value::Object(Map::new()),
+    };
+
+    let mut result = Map::new();
+    for key in key_args(&args[1..]) {
+        if let Some(value) = obj.remove(&key) {
+            result.insert(key, value);
+        }
+    }
+
+    Value::Object(result)
+}
+
+/// Returns the keys given as the arguments of `pick` and `omit`. Arrays are flattened into the
+/// keys, so the keys can be computed by the rule.
+pub fn key_args(args: &[Value]) -> Vec<String> {
+    let mut keys = vec![];
+    for arg in args {
+        match arg {
+            Value::Array(arr) => keys.extend(arr.iter().map(logic::coerce_to_str)),
+            value => keys.push(logic::coerce_to_str(value)),
+        }
+    }
+
+    keys
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute_const;
+    use serde_json::json;
+
+    #[test]
+    fn test() {
+        assert_eq!(compute_const!(), json!({}));
+        assert_eq!(compute_const!(json!([1]), json!("0")), json!({}));
+        assert_eq!(compute_const!(json!({"a": 1, "b": 2})), json!({}));
+        assert_eq!(
+            compute_const!(json!({"a": 1, "b": 2, "c": 3}), json!("a"), json!("c")),
+            json!({"a": 1, "c": 3})
+        );
+        assert_eq!(
+            compute_const!(json!({"a": 1, "b": 2, "c": 3}), json!(["b", "x"])),
+            json!({"b": 2})
+        );
+    }
+}
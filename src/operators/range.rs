@@ -0,0 +1,92 @@
+use serde_json::Value;
+
+use super::{logic, Data};
+
+/// The maximum number of elements `range` creates, so rules cannot use up the memory.
+pub const MAX_LENGTH: usize = 1 << 20;
+
+/// Creates an array of the numbers from the first argument up to, but not including, the second
+/// argument, in steps of the third argument. With one argument, the numbers start at 0 and with
+/// two arguments, the step is 1. A negative step counts down. The numbers are integers if all
+/// arguments are integers.
+///
+/// Returns an empty array if the step is 0 and `Value::Null` if an argument cannot be coerced
+/// into a number or the array would be longer than `MAX_LENGTH`.
+pub fn compute(args: &[Value], _data: &Data) -> Value {
+    let (zero, one) = (Value::from(0), Value::from(1));
+    let (start, end, step) = match args {
+        [] => return Value::Null,
+        [end] => (&zero, end, &one),
+        [start, end] => (start, end, &one),
+        [start, end, step, ..] => (start, end, step),
+    };
+
+    let (start_f, end_f, step_f) = match (
+        logic::coerce_to_f64(start),
+        logic::coerce_to_f64(end),
+        logic::coerce_to_f64(step),
+    ) {
+        (Some(start), Some(end), Some(step)) => (start, end, step),
+        _ => return Value::Null,
+    };
+    if step_f == 0f64 {
+        return Value::Array(vec![]);
+    }
+    let len = ((end_f - start_f) / step_f).ceil().max(0f64);
+    // Infinite arguments can make the length NaN.
+    if len.is_nan() || len > MAX_LENGTH as f64 {
+        return Value::Null;
+    }
+    let len = len as usize;
+
+    let integers = match (integer(start), integer(end), integer(step)) {
+        (Some(start), Some(_), Some(step)) => Some((start, step)),
+        _ => None,
+    };
+    Value::Array(
+        (0..len)
+            .map(|index| match integers {
+                Some((start, step)) => Value::from(start + index as i64 * step),
+                None => logic::f64_to_value(start_f + index as f64 * step_f),
+            })
+            .collect(),
+    )
+}
+
+/// Returns the value if it is an integer that fits into an `i64`, so the integers of the range
+/// cannot overflow, given that the range is not longer than `MAX_LENGTH`.
+fn integer(value: &Value) -> Option<i64> {
+    logic::as_integer(value)
+        .filter(|int| int.unsigned_abs() < 1 << 40)
+        .map(|int| int as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute_const;
+    use serde_json::json;
+
+    #[test]
+    fn test() {
+        assert_eq!(compute_const!(), Value::Null);
+        assert_eq!(compute_const!(json!(4)), json!([0, 1, 2, 3]));
+        assert_eq!(compute_const!(json!(-1)), json!([]));
+        assert_eq!(compute_const!(json!(2), json!(5)), json!([2, 3, 4]));
+        assert_eq!(
+            compute_const!(json!(10), json!(0), json!(-3)),
+            json!([10, 7, 4, 1])
+        );
+        assert_eq!(
+            compute_const!(json!(0), json!(1), json!(0.25)),
+            json!([0.0, 0.25, 0.5, 0.75])
+        );
+        assert_eq!(compute_const!(json!(0), json!(1), json!(0)), json!([]));
+        assert_eq!(compute_const!(json!("a")), Value::Null);
+        assert_eq!(compute_const!(json!(1e300)), Value::Null);
+        assert_eq!(
+            compute_const!(json!(0), json!(1), json!(1e-300)),
+            Value::Null
+        );
+    }
+}
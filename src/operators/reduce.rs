@@ -12,14 +12,15 @@ use super::{Data, Expression};
 ///     "accumulator" : // progress so far, or the initial value
 /// }
 /// ```
-pub fn compute(args: &[Expression], data: &Data) -> Value {
+pub fn compute(args: &[Expression], data: &Data) -> Result<Value, Value> {
+    let arr = args.get(0).map(|arg| arg.compute(data)).transpose()?;
     let initial = match args.get(2) {
-        Some(expr) => expr.compute(data),
+        Some(expr) => expr.compute(data)?,
         None => Value::Null,
     };
-    let arr = match args.get(0).map(|arg| arg.compute(data)) {
+    let arr = match arr {
         Some(Value::Array(arr)) => arr,
-        _ => return initial,
+        _ => return Ok(initial),
     };
     let reducer = match args.get(1) {
         Some(expr) => expr,
@@ -28,11 +29,10 @@ pub fn compute(args: &[Expression], data: &Data) -> Value {
 
     let mut accumulator = initial;
     for current in arr.iter() {
-        let reduced_value = reducer.compute(&Data::from_json(
-            &json!({ "current": current, "accumulator": accumulator }),
-        ));
+        let reduced_value = reducer
+            .compute(&data.nested(&json!({ "current": current, "accumulator": accumulator })))?;
         accumulator = reduced_value;
     }
 
-    accumulator
+    Ok(accumulator)
 }
@@ -0,0 +1,46 @@
+use serde_json::Value;
+
+use super::{logic, Data};
+
+/// Replaces every occurrence of the second argument in the first argument with the third
+/// argument, all coerced into strings, like `replaceAll` in javascript. The replacement defaults
+/// to the empty string. Returns `Value::Null` if the string or the search string is missing.
+pub fn compute(args: &[Value], _data: &Data) -> Value {
+    let (s, search) = match (args.first(), args.get(1)) {
+        (Some(s), Some(search)) => (logic::coerce_to_str(s), logic::coerce_to_str(search)),
+        _ => return Value::Null,
+    };
+    let replacement = args.get(2).map(logic::coerce_to_str).unwrap_or_default();
+
+    Value::String(s.replace(&search, &replacement))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute_const;
+    use serde_json::json;
+
+    #[test]
+    fn test() {
+        assert_eq!(compute_const!(), Value::Null);
+        assert_eq!(compute_const!(json!("abc")), Value::Null);
+        assert_eq!(
+            compute_const!(json!("a-b-c"), json!("-"), json!("+")),
+            json!("a+b+c")
+        );
+        assert_eq!(compute_const!(json!("a-b-c"), json!("-")), json!("abc"));
+        assert_eq!(
+            compute_const!(json!("Grüße"), json!("ü"), json!("ue")),
+            json!("Grueße")
+        );
+        assert_eq!(
+            compute_const!(json!("ab"), json!(""), json!("-")),
+            json!("-a-b-")
+        );
+        assert_eq!(
+            compute_const!(json!(1000), json!(0), json!(1)),
+            json!("1111")
+        );
+    }
+}
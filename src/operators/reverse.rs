@@ -0,0 +1,29 @@
+use serde_json::Value;
+
+use super::Data;
+
+/// Reverses the array of the first argument. Returns an empty array if the argument is not an
+/// array.
+pub fn compute(args: &[Value], _data: &Data) -> Value {
+    match args.first().cloned() {
+        Some(Value::Array(mut arr)) => {
+            arr.reverse();
+            Value::Array(arr)
+        }
+        _ => Value::Array(vec![]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute_const;
+    use serde_json::json;
+
+    #[test]
+    fn test() {
+        assert_eq!(compute_const!(), json!([]));
+        assert_eq!(compute_const!(json!("abc")), json!([]));
+        assert_eq!(compute_const!(json!([1, [2, 3], 4])), json!([4, [2, 3], 1]));
+    }
+}
@@ -0,0 +1,72 @@
+use serde_json::Value;
+
+use super::{logic, Data};
+
+/// Returns the elements of the array of the first argument from the index of the second argument
+/// up to, but not including, the index of the third argument, like `slice` in javascript. The
+/// start defaults to 0 and the end to the length of the array. Negative indices count from the
+/// end of the array. Returns an empty array if the first argument is not an array.
+pub fn compute(args: &[Value], _data: &Data) -> Value {
+    let arr = match args.first().cloned() {
+        Some(Value::Array(arr)) => arr,
+        _ => return Value::Array(vec![]),
+    };
+    let len = arr.len() as i64;
+    let index = |arg: Option<&Value>, default: i64| {
+        let index = arg
+            .and_then(logic::coerce_to_f64)
+            .map_or(default, |f| f as i64);
+        if index < 0 {
+            std::cmp::max(len.saturating_add(index), 0)
+        } else {
+            std::cmp::min(index, len)
+        }
+    };
+    let start = index(args.get(1), 0);
+    let end = index(args.get(2), len);
+
+    Value::Array(
+        arr.into_iter()
+            .skip(start as usize)
+            .take(std::cmp::max(end - start, 0) as usize)
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute_const;
+    use serde_json::json;
+
+    #[test]
+    fn test() {
+        assert_eq!(compute_const!(), json!([]));
+        assert_eq!(compute_const!(json!("abc"), json!(1)), json!([]));
+        assert_eq!(compute_const!(json!([1, 2, 3, 4])), json!([1, 2, 3, 4]));
+        assert_eq!(
+            compute_const!(json!([1, 2, 3, 4]), json!(1)),
+            json!([2, 3, 4])
+        );
+        assert_eq!(
+            compute_const!(json!([1, 2, 3, 4]), json!(1), json!(3)),
+            json!([2, 3])
+        );
+        assert_eq!(
+            compute_const!(json!([1, 2, 3, 4]), json!(-2)),
+            json!([3, 4])
+        );
+        assert_eq!(
+            compute_const!(json!([1, 2, 3, 4]), json!(0), json!(-1)),
+            json!([1, 2, 3])
+        );
+        assert_eq!(
+            compute_const!(json!([1, 2, 3, 4]), json!(3), json!(1)),
+            json!([])
+        );
+        assert_eq!(
+            compute_const!(json!([1, 2, 3, 4]), json!(-1e300), json!(1e300)),
+            json!([1, 2, 3, 4])
+        );
+    }
+}
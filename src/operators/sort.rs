@@ -0,0 +1,121 @@
+use serde_json::Value;
+use std::cmp::Ordering;
+
+use super::{logic, Data, Expression};
+
+/// Sorts the array of the first argument. Elements are compared by themselves or, if there is a
+/// second argument, by the value of the second argument for each element, where `var`
+/// operations are relative to the element. Elements with equal keys keep their order.
+///
+/// Keys are ordered by `compare`. Returns an empty array if the first argument is not an array.
+pub fn compute(args: &[Expression], data: &Data) -> Result<Value, Value> {
+    let arr = match args.first().map(|arg| arg.compute(data)).transpose()? {
+        Some(Value::Array(arr)) => arr,
+        _ => return Ok(Value::Array(vec![])),
+    };
+    let keys = match args.get(1) {
+        Some(key) => arr
+            .iter()
+            .map(|elem| key.compute(&data.nested(elem)))
+            .collect::<Result<_, _>>()?,
+        None => arr.clone(),
+    };
+
+    Ok(sort_by_keys(arr, keys))
+}
+
+/// Sorts the elements by their keys, which are at the same positions as the elements.
+pub fn sort_by_keys(arr: Vec<Value>, keys: Vec<Value>) -> Value {
+    let mut pairs: Vec<(Value, Value)> = keys.into_iter().zip(arr).collect();
+    pairs.sort_by(|(a, _), (b, _)| compare(a, b));
+    Value::Array(pairs.into_iter().map(|(_, elem)| elem).collect())
+}
+
+/// The order of `sort`. Nulls, booleans and numbers come first and are compared as numbers, then
+/// strings, arrays and objects follow, each compared among themselves with `logic::less_than`.
+/// Unlike `<` across types, this is a total order, so the result does not depend on the order of
+/// the input.
+pub fn compare(a: &Value, b: &Value) -> Ordering {
+    fn rank(value: &Value) -> u8 {
+        match value {
+            Value::Null | Value::Bool(_) | Value::Number(_) => 0,
+            Value::String(_) => 1,
+            Value::Array(_) => 2,
+            Value::Object(_) => 3,
+        }
+    }
+
+    rank(a).cmp(&rank(b)).then_with(|| {
+        if logic::less_than(a, b) {
+            Ordering::Less
+        } else if logic::less_than(b, a) {
+            Ordering::Greater
+        } else {
+            // Numbers that cannot be compared as decimals with the `decimal` feature, because they
+            // are too large, are compared as floats.
+            logic::coerce_to_f64(a)
+                .zip(logic::coerce_to_f64(b))
+                .and_then(|(a, b)| a.partial_cmp(&b))
+                .unwrap_or(Ordering::Equal)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute_lazy;
+    use serde_json::json;
+
+    #[test]
+    fn basic() {
+        assert_eq!(compute_lazy!(), Ok(json!([])));
+        assert_eq!(compute_lazy!(json!("cba")), Ok(json!([])));
+        assert_eq!(compute_lazy!(json!([3, 1, 2.5])), Ok(json!([1, 2.5, 3])));
+        assert_eq!(
+            compute_lazy!(json!(["b", "a", "B"])),
+            Ok(json!(["B", "a", "b"]))
+        );
+        // Numbers are not compared as strings.
+        assert_eq!(compute_lazy!(json!([10, 9, 100])), Ok(json!([9, 10, 100])));
+    }
+
+    #[test]
+    fn mixed_types() {
+        assert_eq!(
+            compute_lazy!(json!([{"a": 1}, "10", [1], 9, null, "9", true, [0]])),
+            Ok(json!([null, true, 9, "10", "9", [0], [1], {"a": 1}]))
+        );
+    }
+
+    #[test]
+    fn keys() {
+        let people = json!([
+            {"name": "b", "age": 30},
+            {"name": "a", "age": 40},
+            {"name": "c", "age": 30}
+        ]);
+        assert_eq!(
+            crate::apply(
+                &json!({"sort": [{"var": "people"}, {"var": "age"}]}),
+                &json!({ "people": people })
+            ),
+            Ok(json!([
+                {"name": "b", "age": 30},
+                {"name": "c", "age": 30},
+                {"name": "a", "age": 40}
+            ]))
+        );
+        assert_eq!(
+            crate::apply(
+                &json!({"sort": [{"var": "people"}, {"-": [0, {"var": "age"}]}]}),
+                &json!({ "people": people })
+            ),
+            Ok(json!([
+                {"name": "a", "age": 40},
+                {"name": "b", "age": 30},
+                {"name": "c", "age": 30}
+            ]))
+        );
+    }
+}
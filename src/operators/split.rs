@@ -0,0 +1,53 @@
+use serde_json::Value;
+
+use super::{logic, Data};
+
+/// Splits the first argument, coerced into a string, at every occurrence of the separator of the
+/// second argument. Like `split` in javascript, an empty separator splits the string into its
+/// characters and a missing separator returns the whole string in an array. Returns `Value::Null`
+/// if there is no argument.
+pub fn compute(args: &[Value], _data: &Data) -> Value {
+    let s = match args.first() {
+        Some(arg) => logic::coerce_to_str(arg),
+        None => return Value::Null,
+    };
+    let parts: Vec<Value> = match args.get(1) {
+        Some(arg) => {
+            let separator = logic::coerce_to_str(arg);
+            if separator.is_empty() {
+                s.chars().map(|c| Value::String(c.to_string())).collect()
+            } else {
+                s.split(separator.as_str())
+                    .map(|part| Value::String(part.to_string()))
+                    .collect()
+            }
+        }
+        None => vec![Value::String(s)],
+    };
+
+    Value::Array(parts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute_const;
+    use serde_json::json;
+
+    #[test]
+    fn test() {
+        assert_eq!(compute_const!(), Value::Null);
+        assert_eq!(compute_const!(json!("a,b")), json!(["a,b"]));
+        assert_eq!(
+            compute_const!(json!("a,b,,c"), json!(",")),
+            json!(["a", "b", "", "c"])
+        );
+        assert_eq!(compute_const!(json!(""), json!(",")), json!([""]));
+        assert_eq!(
+            compute_const!(json!("äbc"), json!("")),
+            json!(["ä", "b", "c"])
+        );
+        assert_eq!(compute_const!(json!(""), json!("")), json!([]));
+        assert_eq!(compute_const!(json!(1.5), json!(".")), json!(["1", "5"]));
+    }
+}
@@ -0,0 +1,37 @@
+use serde_json::Value;
+
+use super::{logic, Data};
+
+/// Tests whether the first argument starts with the second argument, both coerced into strings.
+/// Returns `false` if an argument is missing.
+pub fn compute(args: &[Value], _data: &Data) -> Value {
+    match (args.first(), args.get(1)) {
+        (Some(s), Some(prefix)) => {
+            Value::Bool(logic::coerce_to_str(s).starts_with(&logic::coerce_to_str(prefix)))
+        }
+        _ => Value::Bool(false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute_const;
+    use serde_json::json;
+
+    #[test]
+    fn test() {
+        assert_eq!(compute_const!(), json!(false));
+        assert_eq!(compute_const!(json!("jsonlogic")), json!(false));
+        assert_eq!(
+            compute_const!(json!("jsonlogic"), json!("json")),
+            json!(true)
+        );
+        assert_eq!(
+            compute_const!(json!("jsonlogic"), json!("logic")),
+            json!(false)
+        );
+        assert_eq!(compute_const!(json!("jsonlogic"), json!("")), json!(true));
+        assert_eq!(compute_const!(json!(123), json!(12)), json!(true));
+    }
+}
@@ -1,16 +1,10 @@
-use serde_json::{json, Value};
+use serde_json::Value;
 
-use super::{logic, Data, Expression};
+use super::{logic, Data};
 
-pub fn compute(args: &[Expression], data: &Data) -> Value {
-    let a = args
-        .get(0)
-        .map(|arg| arg.compute(data))
-        .unwrap_or(json!(null));
-    let b = args
-        .get(1)
-        .map(|arg| arg.compute(data))
-        .unwrap_or(json!(null));
+pub fn compute(args: &[Value], _data: &Data) -> Value {
+    let a = args.get(0).unwrap_or(&Value::Null);
+    let b = args.get(1).unwrap_or(&Value::Null);
 
-    Value::Bool(logic::is_strict_equal(&a, &b))
+    Value::Bool(logic::is_strict_equal(a, b))
 }
@@ -1,6 +1,6 @@
 use serde_json::Value;
 
-use super::{logic, Data, Expression};
+use super::{logic, Data};
 
 /// Gets a portion of a string. Takes two to three arguments.
 ///
@@ -12,54 +12,48 @@ use super::{logic, Data, Expression};
 ///
 /// The third argument limits the length of the returned substring. Give a negative index to
 /// stop that many characters before the end.
-pub fn compute(args: &[Expression], data: &Data) -> Value {
-    let a = match args.get(0).map(|arg| arg.compute(data)) {
-        Some(val) => logic::coerce_to_str(&val),
+///
+/// Indices and lengths count characters as defined by the string mode of the data, see
+/// `StringMode`.
+pub fn compute(args: &[Value], data: &Data) -> Value {
+    let a = match args.get(0) {
+        Some(val) => logic::coerce_to_str(val),
         // Replicates specifics of the javascript implementation.
         None => String::from("undefined"),
     };
     let b = args
         .get(1)
-        .map(|arg| arg.compute(data))
-        .and_then(|val| logic::coerce_to_f64(&val))
+        .and_then(logic::coerce_to_f64)
         .map(|f| f as i64)
         .unwrap_or(0);
-    let c = args
-        .get(2)
-        .map(|arg| arg.compute(data))
-        .and_then(|val| logic::coerce_to_f64(&val))
-        .map(|f| f as i64);
+    let c = args.get(2).and_then(logic::coerce_to_f64).map(|f| f as i64);
 
-    let len = a.len() as i64;
+    let mode = data.string_mode();
+    let len = mode.length(&a) as i64;
     let start = if b >= 0 {
         b
     } else {
         // Avoid a negative start index.
         std::cmp::max(len + b, 0)
     };
-    let iter = a.chars().skip(start as usize);
-    let s = match c {
-        Some(c) => {
-            let limit = if c >= 0 {
-                c
-            } else {
-                // Avoid a negative limit. We must stop at c bytes before the end.
-                let len_after_start = len - start;
-                std::cmp::max(len_after_start - c.abs(), 0)
-            };
-
-            iter.take(limit as usize).collect()
+    let limit = c.map(|c| {
+        if c >= 0 {
+            c
+        } else {
+            // Avoid a negative limit. We must stop at c characters before the end.
+            let len_after_start = len - start;
+            std::cmp::max(len_after_start.saturating_add(c), 0)
         }
-        None => iter.collect(),
-    };
+    });
 
-    Value::String(s)
+    Value::String(mode.substring(&a, start as usize, limit.map(|limit| limit as usize)))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::compute_const;
+    use crate::StringMode;
     use serde_json::json;
 
     #[test]
@@ -89,7 +83,7 @@ mod tests {
         );
         assert_eq!(compute_const!(json!("äüö"), json!(1), json!(1)), json!("ü"));
 
-        // Negative limit c, stop at c characters (i.e. bytes) from the end.
+        // Negative limit c, stop at c characters from the end.
         assert_eq!(
             compute_const!(json!("jsonlogic"), json!(4), json!(-2)),
             json!("log")
@@ -122,4 +116,28 @@ mod tests {
             json!("")
         );
     }
+
+    #[test]
+    fn non_ascii() {
+        assert_eq!(compute_const!(json!("Grüße"), json!(-3)), json!("üße"));
+        assert_eq!(
+            compute_const!(json!("Grüße"), json!(1), json!(-1)),
+            json!("rüß")
+        );
+        assert_eq!(
+            compute_const!(json!("Grüße"), json!(-4), json!(-2)),
+            json!("rü")
+        );
+    }
+
+    #[test]
+    fn string_modes() {
+        let args = [json!("a😀b"), json!(-3), json!(2)];
+
+        let data = Data::empty();
+        assert_eq!(compute(&args, &data), json!("a😀"));
+        let data = Data::empty().with_string_mode(StringMode::Utf16);
+        assert_eq!(compute(&args, &data), json!("😀"));
+        assert_eq!(compute(&args[..2], &data), json!("😀b"));
+    }
 }
@@ -1,35 +1,45 @@
-use serde_json::{Number, Value};
+use serde_json::Value;
 
-use super::{logic, Data, Expression};
+use super::{logic, Data};
 
 /// "-", takes two numbers and returns the substraction of the them.
 /// If only one argument is passed, returns the negation of that argument.
-/// Returns `Value::Null` one of the arguments cannot be coerced into a number.
-pub fn compute(args: &[Expression], data: &Data) -> Value {
-    let a = match args.get(0).map(|arg| arg.compute(data)) {
+/// Returns `Value::Null` if one of the arguments cannot be coerced into a number or the result is
+/// not finite.
+/// The result is an integer if the arguments are integers, unless it overflows.
+pub fn compute(args: &[Value], _data: &Data) -> Value {
+    let a = match args.get(0) {
         Some(arg) => arg,
         None => return Value::Null,
     };
 
-    match args.get(1).map(|arg| arg.compute(data)) {
-        None => compute_negation(&logic::coerce_to_f64(&a)),
-        Some(b) => compute_substraction(&logic::coerce_to_f64(&a), &logic::coerce_to_f64(&b)),
+    match args.get(1) {
+        None => logic::as_integer(a)
+            .and_then(|a| a.checked_neg())
+            .and_then(logic::integer_to_number)
+            .map(Value::Number)
+            .unwrap_or_else(|| compute_negation(&logic::coerce_to_f64(a))),
+        Some(b) => logic::as_integer(a)
+            .zip(logic::as_integer(b))
+            .and_then(|(a, b)| a.checked_sub(b))
+            .and_then(logic::integer_to_number)
+            .map(Value::Number)
+            .unwrap_or_else(|| {
+                compute_substraction(&logic::coerce_to_f64(a), &logic::coerce_to_f64(b))
+            }),
     }
 }
 
 fn compute_negation(a: &Option<f64>) -> Value {
     match a {
-        Some(a) => Value::Number(Number::from_f64(-1f64 * a).unwrap()),
+        Some(a) => logic::f64_to_value(-a),
         None => Value::Null,
     }
 }
 
 fn compute_substraction(a: &Option<f64>, b: &Option<f64>) -> Value {
     match (a, b) {
-        (Some(a), Some(b)) => match Number::from_f64(a - b) {
-            Some(num) => Value::Number(num),
-            None => Value::Null,
-        },
+        (Some(a), Some(b)) => logic::f64_to_value(a - b),
         _ => Value::Null,
     }
 }
@@ -48,19 +58,38 @@ mod tests {
 
     #[test]
     fn negation() {
-        assert_eq!(compute_const!(json!(1)), json!(-1.0));
+        assert_eq!(compute_const!(json!(1)), json!(-1));
         assert_eq!(compute_const!(json!("")), json!(-0.0));
         assert!(logic::is_strict_equal(
             &compute_const!(json!("")),
             &json!(0)
         ));
-        assert_eq!(compute_const!(json!("-5")), json!(5.0));
+        assert_eq!(compute_const!(json!("-5")), json!(5));
     }
 
     #[test]
     fn substraction() {
-        assert_eq!(compute_const!(json!(1), json!(2)), json!(-1.0));
-        assert_eq!(compute_const!(json!(4), json!(2)), json!(2.0));
-        assert_eq!(compute_const!(json!(4), json!(-2)), json!(6.0));
+        assert_eq!(compute_const!(json!(1), json!(2)), json!(-1));
+        assert_eq!(compute_const!(json!(4), json!(2)), json!(2));
+        assert_eq!(compute_const!(json!(4), json!(-2)), json!(6));
+        assert_eq!(compute_const!(json!(4), json!(1.5)), json!(2.5));
+        assert_eq!(compute_const!(json!(true), json!(2)), json!(-1.0));
+    }
+
+    #[test]
+    fn integers() {
+        assert_eq!(
+            compute_const!(json!(u64::MAX), json!(1)),
+            json!(u64::MAX - 1)
+        );
+        assert_eq!(
+            compute_const!(json!(0), json!(u64::MAX)),
+            json!(-(u64::MAX as f64))
+        );
+        assert_eq!(
+            compute_const!(json!(i64::MIN)),
+            json!(9223372036854775808u64)
+        );
+        assert_eq!(compute_const!(json!(u64::MAX)), json!(-(u64::MAX as f64)));
     }
 }
@@ -0,0 +1,35 @@
+use serde_json::Value;
+
+#[cfg(not(feature = "decimal"))]
+use super::addition::compute as add;
+#[cfg(feature = "decimal")]
+use super::decimal::addition as add;
+use super::Data;
+
+/// Sums up the elements of the array of the first argument, with the semantics of `+`. Returns
+/// `Value::Null` if an element cannot be coerced into a number and 0 if the argument is not an
+/// array.
+pub fn compute(args: &[Value], data: &Data) -> Value {
+    match args.first() {
+        Some(Value::Array(arr)) => add(arr, data),
+        _ => Value::from(0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute_const;
+    use serde_json::json;
+
+    #[test]
+    fn test() {
+        assert_eq!(compute_const!(), json!(0));
+        assert_eq!(compute_const!(json!(5)), json!(0));
+        assert_eq!(compute_const!(json!([])), json!(0));
+        assert_eq!(compute_const!(json!([1, 2, "3"])), json!(6));
+        assert_eq!(compute_const!(json!([1, "a"])), Value::Null);
+        // A single element is summed up, not only cast.
+        assert_eq!(compute_const!(json!([[1, 2]])), Value::Null);
+    }
+}
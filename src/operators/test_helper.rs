@@ -1,39 +1,71 @@
-/// Expects a function typically used for JsonLogic operators in the scope this macro is called in:
+/// Expects a function typically used for eager JsonLogic operators in the scope this macro is
+/// called in:
 ///
 /// ```ignore
-/// fn compute(args: &[Expression], data: &Data) -> Value
+/// fn compute(args: &[Value], data: &Data) -> Value
 /// ```
 ///
-/// Calls the `compute` function with the given arguments wrapped in `Expression::Constant` and an
-/// empty data object created with `Data::empty()`.
+/// Calls the `compute` function with the given arguments and an empty data object created with
+/// `Data::empty()`.
 #[macro_export]
 macro_rules! compute_const {
     ($($args:expr),*) => {{
         #[allow(unused_mut)]
-        let mut args_vec = vec![];
+        let mut args_vec: Vec<Value> = vec![];
         $(
             args_vec.push($args);
         )*
 
-        let expressions: Vec<Expression> = args_vec
-            .iter()
-            .map(|arg| Expression::Constant(&arg))
-            .collect();
-        compute(&expressions, &Data::empty())
+        compute(&args_vec, &Data::empty())
     }}
 }
 
-/// Expects a function typically used for JsonLogic operators in the scope this macro is called in:
+/// Expects a function typically used for eager JsonLogic operators in the scope this macro is
+/// called in:
 ///
 /// ```ignore
-/// fn compute(args: &[Expression], data: &Data) -> Value
+/// fn compute(args: &[Value], data: &Data) -> Value
 /// ```
 ///
 /// Expects two arguments. First an slice containing `Value`'s and second a `Data` instance.
-/// Calls the `compute` function with the given `Value` slice where every element is wrapped in
-/// `Expression::Constant` and the given data instance.
+/// Calls the `compute` function with the given `Value` slice and the given data instance.
 #[macro_export]
 macro_rules! compute_const_with_data {
+    ($args:expr, $data:expr) => {{
+        // Avoid "temporary value dropped while borrowed" errors.
+        let args: &[Value] = $args;
+        compute(args, $data)
+    }};
+}
+
+/// Like `compute_const`, but for a function of a lazy operator, which evaluates its arguments
+/// itself:
+///
+/// ```ignore
+/// fn compute(args: &[Expression], data: &Data) -> Result<Value, Value>
+/// ```
+///
+/// The arguments are wrapped in `Expression::Constant`.
+#[macro_export]
+macro_rules! compute_lazy {
+    ($($args:expr),*) => {{
+        #[allow(unused_mut)]
+        let mut args_vec = vec![];
+        $(
+            args_vec.push($args);
+        )*
+
+        let expressions: Vec<Expression> = args_vec
+            .iter()
+            .map(|arg| Expression::Constant(&arg))
+            .collect();
+        compute(&expressions, &Data::empty())
+    }}
+}
+
+/// Like `compute_const_with_data`, but for a function of a lazy operator, see `compute_lazy`.
+#[macro_export]
+macro_rules! compute_lazy_with_data {
     ($args:expr, $data:expr) => {{
         // Avoid "temporary value dropped while borrowed" errors.
         let args: &[Value] = $args;
@@ -0,0 +1,61 @@
+use serde_json::Value;
+
+use super::{Data, Expression};
+
+/// Throws the first argument as an error, which aborts the evaluation of the rule, unless it is
+/// caught by `try`. The error can be any value, like an error code or an object built with
+/// `object`. Like the arguments of other operators, all arguments are evaluated before.
+pub fn compute(args: &[Expression], data: &Data) -> Result<Value, Value> {
+    let error = Expression::compute_all(args, data)?
+        .into_iter()
+        .next()
+        .unwrap_or(Value::Null);
+    Err(error)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{apply, Error, Rule};
+    use serde_json::{json, Value};
+
+    #[test]
+    fn test() {
+        let json_logic = json!({"throw": "InvalidAge"});
+        let rule = Rule::compile(&json_logic).unwrap();
+        assert_eq!(rule.apply(&Value::Null), Err(json!("InvalidAge")));
+        assert_eq!(
+            apply(&json_logic, &Value::Null),
+            Err(Error::Thrown(json!("InvalidAge")))
+        );
+
+        let json_logic = json!({"throw": []});
+        let rule = Rule::compile(&json_logic).unwrap();
+        assert_eq!(rule.apply(&Value::Null), Err(Value::Null));
+    }
+
+    #[test]
+    fn arguments_are_evaluated_before() {
+        // Like other operators, all arguments are evaluated, so the first error thrown wins.
+        let json_logic = json!({"throw": [{"throw": "a"}, {"throw": "b"}]});
+        let rule = Rule::compile(&json_logic).unwrap();
+        assert_eq!(rule.apply(&Value::Null), Err(json!("a")));
+
+        let json_logic = json!({"!": [false, {"throw": "ignored argument"}]});
+        let rule = Rule::compile(&json_logic).unwrap();
+        assert_eq!(rule.apply(&Value::Null), Err(json!("ignored argument")));
+    }
+
+    #[test]
+    fn first_error_wins() {
+        let json_logic = json!({"or": [{"throw": "a"}, {"throw": "b"}, 1]});
+        let rule = Rule::compile(&json_logic).unwrap();
+        assert_eq!(rule.apply(&Value::Null), Err(json!("a")));
+
+        // Errors thrown in the logic of `map` abort the whole rule.
+        let json_logic = json!({"map": [[1, 2, 3], {"if": [
+            {">": [{"var": ""}, 1]}, {"throw": {"var": ""}}, {"var": ""}
+        ]}]});
+        let rule = Rule::compile(&json_logic).unwrap();
+        assert_eq!(rule.apply(&Value::Null), Err(json!(2)));
+    }
+}
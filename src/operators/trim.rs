@@ -0,0 +1,30 @@
+use serde_json::Value;
+
+use super::{logic, Data};
+
+/// Removes leading and trailing whitespace from the first argument, coerced into a string.
+/// Returns `Value::Null` if there is no argument.
+pub fn compute(args: &[Value], _data: &Data) -> Value {
+    match args.first() {
+        Some(arg) => Value::String(logic::coerce_to_str(arg).trim().to_string()),
+        None => Value::Null,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute_const;
+    use serde_json::json;
+
+    #[test]
+    fn test() {
+        assert_eq!(compute_const!(), Value::Null);
+        assert_eq!(
+            compute_const!(json!("  json logic \n")),
+            json!("json logic")
+        );
+        assert_eq!(compute_const!(json!("\u{3000}x\t")), json!("x"));
+        assert_eq!(compute_const!(json!(42)), json!("42"));
+    }
+}
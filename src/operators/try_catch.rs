@@ -0,0 +1,91 @@
+use serde_json::{json, Value};
+
+use super::{Data, Expression};
+
+/// Evaluates the arguments until one of them does not throw an error and returns its value. The
+/// arguments after the first one are fallbacks, which are evaluated with an object as data that
+/// contains the error thrown by the previous argument as `error` and the data as `data`. Throws
+/// the last error again if every argument throws one. Returns `Value::Null` if there are no
+/// arguments.
+pub fn compute(args: &[Expression], data: &Data) -> Result<Value, Value> {
+    try_alternatives(args, data, |arg, data| arg.compute(data))
+}
+
+/// Evaluates the alternatives with `compute` like the `try` operator, for the other backends.
+pub fn try_alternatives<T>(
+    alternatives: &[T],
+    data: &Data,
+    compute: impl Fn(&T, &Data) -> Result<Value, Value>,
+) -> Result<Value, Value> {
+    let mut caught = None;
+    for alternative in alternatives {
+        let result = match caught.take() {
+            None => compute(alternative, data),
+            Some(error) => {
+                let scope = json!({ "error": error, "data": data.get_plain() });
+                compute(alternative, &data.nested(&scope))
+            }
+        };
+        match result {
+            Ok(value) => return Ok(value),
+            Err(error) => caught = Some(error),
+        }
+    }
+
+    match caught {
+        Some(error) => Err(error),
+        None => Ok(Value::Null),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{apply, Rule};
+    use serde_json::{json, Value};
+
+    #[test]
+    fn test() {
+        assert_eq!(apply(&json!({"try": []}), &Value::Null), Ok(Value::Null));
+        assert_eq!(apply(&json!({"try": [1, 2]}), &Value::Null), Ok(json!(1)));
+        assert_eq!(
+            apply(&json!({"try": [{"throw": "a"}, 2]}), &Value::Null),
+            Ok(json!(2))
+        );
+        assert_eq!(
+            apply(
+                &json!({"try": [{"throw": "a"}, {"throw": "b"}, {"var": "error"}]}),
+                &Value::Null
+            ),
+            Ok(json!("b"))
+        );
+    }
+
+    #[test]
+    fn fallback_data() {
+        let json_logic = json!({"try": [
+            {"if": [{"<": [{"var": "age"}, 0]}, {"throw": "NegativeAge"}, {"var": "age"}]},
+            {"cat": [{"var": "error"}, ": ", {"var": "data.age"}]}
+        ]});
+        let rule = Rule::compile(&json_logic).unwrap();
+        assert_eq!(rule.apply(&json!({"age": 42})), Ok(json!(42)));
+        assert_eq!(
+            rule.apply(&json!({"age": -1})),
+            Ok(json!("NegativeAge: -1"))
+        );
+    }
+
+    #[test]
+    fn rethrow() {
+        let json_logic = json!({"try": [{"throw": "a"}, {"throw": {"var": "error"}}]});
+        let rule = Rule::compile(&json_logic).unwrap();
+        assert_eq!(rule.apply(&Value::Null), Err(json!("a")));
+
+        // Errors are caught by the innermost `try`.
+        let json_logic = json!({"try": [
+            {"+": [{"try": [{"throw": "inner"}, 1]}, {"throw": "outer"}]},
+            {"var": "error"}
+        ]});
+        let rule = Rule::compile(&json_logic).unwrap();
+        assert_eq!(rule.apply(&Value::Null), Ok(json!("outer")));
+    }
+}
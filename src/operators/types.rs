@@ -0,0 +1,163 @@
+//! Type inspection and explicit conversions. The conversions expose the implicit coercions of the
+//! other operators, so rules can normalize messy input before comparing it.
+
+use serde_json::Value;
+
+use super::{logic, Data};
+
+/// Returns the type of the first argument as a string, which is one of `"null"`, `"boolean"`,
+/// `"number"`, `"string"`, `"array"` and `"object"`. A missing argument is `"null"`.
+pub fn type_of(args: &[Value], _data: &Data) -> Value {
+    let name = match first(args) {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    };
+    Value::String(String::from(name))
+}
+
+/// Tests whether the first argument is a number. Strings containing numbers are not.
+pub fn is_number(args: &[Value], _data: &Data) -> Value {
+    Value::Bool(first(args).is_number())
+}
+
+/// Tests whether the first argument is a string.
+pub fn is_string(args: &[Value], _data: &Data) -> Value {
+    Value::Bool(first(args).is_string())
+}
+
+/// Tests whether the first argument is an array.
+pub fn is_array(args: &[Value], _data: &Data) -> Value {
+    Value::Bool(first(args).is_array())
+}
+
+/// Converts the first argument into a number like `Number(val)` in javascript, see
+/// `logic::coerce_to_f64`. Returns `Value::Null` if it cannot be converted, where javascript
+/// returns NaN.
+#[cfg_attr(feature = "decimal", allow(dead_code))]
+pub fn to_number(args: &[Value], _data: &Data) -> Value {
+    match first(args) {
+        num @ Value::Number(_) => num.clone(),
+        value => logic::coerce_to_f64(value).map_or(Value::Null, logic::whole_to_value),
+    }
+}
+
+/// Parses the number at the start of the first argument like `parseFloat(val)` in javascript, see
+/// `logic::parse_float`, so `"12px"` is 12. Returns `Value::Null` if there is no number.
+#[cfg_attr(feature = "decimal", allow(dead_code))]
+pub fn parse_float(args: &[Value], _data: &Data) -> Value {
+    match first(args) {
+        num @ Value::Number(_) => num.clone(),
+        value => logic::parse_float(value).map_or(Value::Null, logic::whole_to_value),
+    }
+}
+
+/// Converts the first argument into a string like `String(val)` in javascript, see
+/// `logic::coerce_to_str`.
+pub fn to_string(args: &[Value], _data: &Data) -> Value {
+    Value::String(logic::coerce_to_str(first(args)))
+}
+
+/// Converts the first argument into a boolean by its truthiness, like `!!`.
+pub fn to_bool(args: &[Value], _data: &Data) -> Value {
+    Value::Bool(logic::is_truthy(first(args)))
+}
+
+/// Returns the first argument, or `Value::Null` if there is none.
+fn first(args: &[Value]) -> &Value {
+    args.first().unwrap_or(&Value::Null)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::apply;
+    use serde_json::{json, Value};
+
+    /// Applies the operator to the value, which is passed as data, since objects in a rule are
+    /// operations.
+    fn apply_op(op: &str, value: Value) -> Value {
+        apply(&json!({ op: {"var": ""} }), &value).unwrap()
+    }
+
+    #[test]
+    fn type_of() {
+        assert_eq!(
+            apply(&json!({"typeof": []}), &Value::Null),
+            Ok(json!("null"))
+        );
+        assert_eq!(apply_op("typeof", json!(null)), json!("null"));
+        assert_eq!(apply_op("typeof", json!(false)), json!("boolean"));
+        assert_eq!(apply_op("typeof", json!(1.5)), json!("number"));
+        assert_eq!(apply_op("typeof", json!("1")), json!("string"));
+        assert_eq!(apply_op("typeof", json!([1])), json!("array"));
+        assert_eq!(apply_op("typeof", json!({"a": 1})), json!("object"));
+    }
+
+    #[test]
+    fn is_type() {
+        assert_eq!(apply_op("is_number", json!(0)), json!(true));
+        assert_eq!(apply_op("is_number", json!("0")), json!(false));
+        assert_eq!(apply_op("is_string", json!("")), json!(true));
+        assert_eq!(apply_op("is_string", json!(null)), json!(false));
+        assert_eq!(apply_op("is_array", json!([])), json!(true));
+        assert_eq!(apply_op("is_array", json!({})), json!(false));
+        assert_eq!(
+            apply(&json!({"is_array": []}), &Value::Null),
+            Ok(json!(false))
+        );
+    }
+
+    #[test]
+    fn to_number() {
+        assert_eq!(apply_op("to_number", json!(" 42 ")), json!(42));
+        assert_eq!(apply_op("to_number", json!("-0")), json!(0));
+        assert_eq!(apply_op("to_number", json!("1.5")), json!(1.5));
+        #[cfg(not(feature = "decimal"))]
+        assert_eq!(apply_op("to_number", json!("1e300")), json!(1e300));
+        // Decimals are less than 10^29.
+        #[cfg(feature = "decimal")]
+        assert_eq!(apply_op("to_number", json!("1e300")), Value::Null);
+        assert_eq!(apply_op("to_number", json!(2.0)), json!(2.0));
+        assert_eq!(apply_op("to_number", json!("")), json!(0));
+        assert_eq!(apply_op("to_number", json!(true)), json!(1));
+        assert_eq!(apply_op("to_number", json!(null)), json!(0));
+        assert_eq!(apply_op("to_number", json!(["7"])), json!(7));
+        assert_eq!(apply_op("to_number", json!("12px")), Value::Null);
+        assert_eq!(apply_op("to_number", json!({})), Value::Null);
+        assert_eq!(apply_op("to_number", json!("inf")), Value::Null);
+    }
+
+    #[test]
+    fn parse_float() {
+        assert_eq!(apply_op("parse_float", json!("12px")), json!(12));
+        assert_eq!(apply_op("parse_float", json!(" 2.5.1")), json!(2.5));
+        assert_eq!(apply_op("parse_float", json!(7)), json!(7));
+        assert_eq!(apply_op("parse_float", json!("px")), Value::Null);
+        assert_eq!(apply_op("parse_float", json!("")), Value::Null);
+        assert_eq!(apply_op("parse_float", json!(true)), Value::Null);
+        assert_eq!(apply_op("parse_float", json!(null)), Value::Null);
+    }
+
+    #[test]
+    fn to_string() {
+        assert_eq!(apply_op("to_string", json!(42)), json!("42"));
+        assert_eq!(apply_op("to_string", json!(null)), json!("null"));
+        assert_eq!(apply_op("to_string", json!([1, [2, 3]])), json!("1,2,3"));
+        assert_eq!(apply_op("to_string", json!({})), json!("[object Object]"));
+    }
+
+    #[test]
+    fn to_bool() {
+        assert_eq!(apply_op("to_bool", json!("0")), json!(true));
+        assert_eq!(apply_op("to_bool", json!(0)), json!(false));
+        assert_eq!(apply_op("to_bool", json!([])), json!(false));
+        assert_eq!(apply_op("to_bool", json!({})), json!(true));
+        assert_eq!(
+            apply(&json!({"to_bool": []}), &Value::Null),
+            Ok(json!(false))
+        );
+    }
+}
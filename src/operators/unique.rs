@@ -0,0 +1,46 @@
+use serde_json::Value;
+
+use super::{logic, Data};
+
+/// Removes duplicate elements from the array of the first argument, keeping the first
+/// occurrence. Elements are duplicates if they are strictly equal, or equal arrays or objects.
+/// Returns an empty array if the argument is not an array.
+pub fn compute(args: &[Value], _data: &Data) -> Value {
+    let arr = match args.first().cloned() {
+        Some(Value::Array(arr)) => arr,
+        _ => return Value::Array(vec![]),
+    };
+
+    let mut result: Vec<Value> = Vec::with_capacity(arr.len());
+    for elem in arr {
+        if !result
+            .iter()
+            .any(|other| other == &elem || logic::is_strict_equal(other, &elem))
+        {
+            result.push(elem);
+        }
+    }
+
+    Value::Array(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute_const;
+    use serde_json::json;
+
+    #[test]
+    fn test() {
+        assert_eq!(compute_const!(), json!([]));
+        assert_eq!(compute_const!(json!("aab")), json!([]));
+        assert_eq!(
+            compute_const!(json!([1, "1", 2, 1, 1.0, null, null])),
+            json!([1, "1", 2, null])
+        );
+        assert_eq!(
+            compute_const!(json!([[1], [1], {"a": 1}, {"a": 1}])),
+            json!([[1], {"a": 1}])
+        );
+    }
+}
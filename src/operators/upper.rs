@@ -0,0 +1,28 @@
+use serde_json::Value;
+
+use super::{logic, Data};
+
+/// Converts the first argument, coerced into a string, to upper case. Returns `Value::Null` if
+/// there is no argument.
+pub fn compute(args: &[Value], _data: &Data) -> Value {
+    match args.first() {
+        Some(arg) => Value::String(logic::coerce_to_str(arg).to_uppercase()),
+        None => Value::Null,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute_const;
+    use serde_json::json;
+
+    #[test]
+    fn test() {
+        assert_eq!(compute_const!(), Value::Null);
+        assert_eq!(compute_const!(json!("jsonLogic")), json!("JSONLOGIC"));
+        assert_eq!(compute_const!(json!("straße")), json!("STRASSE"));
+        assert_eq!(compute_const!(json!(true)), json!("TRUE"));
+        assert_eq!(compute_const!(json!(null)), json!("NULL"));
+    }
+}
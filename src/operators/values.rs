@@ -0,0 +1,28 @@
+use serde_json::Value;
+
+use super::Data;
+
+/// Returns the values of the object of the first argument, in the order of their keys. Arrays
+/// are returned as they are and any other value results in an empty array.
+pub fn compute(args: &[Value], _data: &Data) -> Value {
+    match args.first() {
+        Some(Value::Object(obj)) => Value::Array(obj.values().cloned().collect()),
+        Some(Value::Array(arr)) => Value::Array(arr.clone()),
+        _ => Value::Array(vec![]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute_const;
+    use serde_json::json;
+
+    #[test]
+    fn test() {
+        assert_eq!(compute_const!(), json!([]));
+        assert_eq!(compute_const!(json!(1)), json!([]));
+        assert_eq!(compute_const!(json!({"b": 1, "a": [2]})), json!([[2], 1]));
+        assert_eq!(compute_const!(json!(["x", "y"])), json!(["x", "y"]));
+    }
+}
@@ -2,45 +2,69 @@ use serde_json::Value;
 
 use super::{Data, Expression};
 
-pub fn compute(args: &[Expression], data: &Data) -> Value {
+pub fn compute(args: &[Expression], data: &Data) -> Result<Value, Value> {
+    // A constant path has already been parsed when parsing the rule.
+    if let Some(path) = args.first().and_then(Expression::path) {
+        return match data.get_value_by_path(path) {
+            Some(value) => Ok(value),
+            None => default(args, data),
+        };
+    }
+
     let arg = args
         .get(0)
         .map(|arg| arg.compute(data))
+        .transpose()?
         .unwrap_or(Value::Null);
 
     if arg.is_null() {
-        return data.get_plain().clone();
+        return Ok(data.get_plain().clone());
     }
 
     match &arg {
         // Return the whole data object if there is no argument given or the argument is an empty
         // string.
-        Value::Null => data.get_plain().clone(),
-        Value::String(s) if s == "" => data.get_plain().clone(),
-        _ => data.get_value(&arg).unwrap_or_else(|| {
-            args.get(1)
-                .map(|arg| arg.compute(data))
-                .unwrap_or(Value::Null)
-        }),
+        Value::Null => Ok(data.get_plain().clone()),
+        Value::String(s) if s == "" => Ok(data.get_plain().clone()),
+        _ => match data.get_value(&arg) {
+            Some(value) => Ok(value),
+            None => default(args, data),
+        },
     }
 }
 
+fn default(args: &[Expression], data: &Data) -> Result<Value, Value> {
+    args.get(1)
+        .map(|arg| arg.compute(data))
+        .unwrap_or(Ok(Value::Null))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::compute_const_with_data;
+    use crate::compute_lazy_with_data;
+    use crate::StringMode;
     use serde_json::json;
 
     #[test]
     fn invalid_arguments() {
         let data_json = json!({ "a": 5, "b": 6 });
         let data = Data::from_json(&data_json);
-        assert_eq!(compute_const_with_data!(&[json!([])], &data), json!(null));
-        assert_eq!(compute_const_with_data!(&[json!({})], &data), json!(null));
-        assert_eq!(compute_const_with_data!(&[json!(true)], &data), json!(null));
         assert_eq!(
-            compute_const_with_data!(&[json!(false)], &data),
-            json!(null)
+            compute_lazy_with_data!(&[json!([])], &data),
+            Ok(json!(null))
+        );
+        assert_eq!(
+            compute_lazy_with_data!(&[json!({})], &data),
+            Ok(json!(null))
+        );
+        assert_eq!(
+            compute_lazy_with_data!(&[json!(true)], &data),
+            Ok(json!(null))
+        );
+        assert_eq!(
+            compute_lazy_with_data!(&[json!(false)], &data),
+            Ok(json!(null))
         );
     }
 
@@ -48,13 +72,22 @@ mod tests {
     fn null_arg() {
         let data_json = json!({ "a": 5, "b": 6 });
         let data = Data::from_json(&data_json);
-        assert_eq!(compute_const_with_data!(&[], &data), data_json);
-        assert_eq!(compute_const_with_data!(&[Value::Null], &data), data_json);
-        assert_eq!(compute_const_with_data!(&[json!("")], &data), data_json);
-        assert_eq!(compute_const_with_data!(&[json!(" ")], &data), json!(null));
+        assert_eq!(compute_lazy_with_data!(&[], &data), Ok(data_json.clone()));
+        assert_eq!(
+            compute_lazy_with_data!(&[Value::Null], &data),
+            Ok(data_json.clone())
+        );
+        assert_eq!(
+            compute_lazy_with_data!(&[json!("")], &data),
+            Ok(data_json.clone())
+        );
         assert_eq!(
-            compute_const_with_data!(&[Value::Null, json!(123)], &data),
-            data_json
+            compute_lazy_with_data!(&[json!(" ")], &data),
+            Ok(json!(null))
+        );
+        assert_eq!(
+            compute_lazy_with_data!(&[Value::Null, json!(123)], &data),
+            Ok(data_json)
         );
     }
 
@@ -63,40 +96,52 @@ mod tests {
         let data_json = json!({ "a": 5, "b": 6, "1": 1337 });
         let data = Data::from_json(&data_json);
         assert_eq!(
-            compute_const_with_data!(&[Value::String(String::from("a"))], &data),
-            json!(5)
+            compute_lazy_with_data!(&[Value::String(String::from("a"))], &data),
+            Ok(json!(5))
         );
         assert_eq!(
-            compute_const_with_data!(&[Value::String(String::from("b"))], &data),
-            json!(6)
+            compute_lazy_with_data!(&[Value::String(String::from("b"))], &data),
+            Ok(json!(6))
         );
-        assert_eq!(compute_const_with_data!(&[json!(1)], &data), json!(1337));
+        assert_eq!(compute_lazy_with_data!(&[json!(1)], &data), Ok(json!(1337)));
     }
 
     #[test]
     fn data_is_string() {
         let data_json = json!("abcderfg");
         let data = Data::from_json(&data_json);
-        assert_eq!(compute_const_with_data!(&[json!(1)], &data), json!("b"));
-        assert_eq!(compute_const_with_data!(&[json!("1")], &data), json!("b"));
+        assert_eq!(compute_lazy_with_data!(&[json!(1)], &data), Ok(json!("b")));
+        assert_eq!(
+            compute_lazy_with_data!(&[json!("1")], &data),
+            Ok(json!("b"))
+        );
     }
 
     #[test]
     fn data_is_array() {
         let data_json = json!(["foo", "bar"]);
         let data = Data::from_json(&data_json);
-        assert_eq!(compute_const_with_data!(&[], &data), data_json);
-        assert_eq!(compute_const_with_data!(&[json!(0)], &data), json!("foo"));
-        assert_eq!(compute_const_with_data!(&[json!(1)], &data), json!("bar"));
-        assert_eq!(compute_const_with_data!(&[json!(2)], &data), json!(null));
+        assert_eq!(compute_lazy_with_data!(&[], &data), Ok(data_json.clone()));
+        assert_eq!(
+            compute_lazy_with_data!(&[json!(0)], &data),
+            Ok(json!("foo"))
+        );
+        assert_eq!(
+            compute_lazy_with_data!(&[json!(1)], &data),
+            Ok(json!("bar"))
+        );
+        assert_eq!(compute_lazy_with_data!(&[json!(2)], &data), Ok(json!(null)));
 
-        assert_eq!(compute_const_with_data!(&[json!("1")], &data), json!("bar"));
+        assert_eq!(
+            compute_lazy_with_data!(&[json!("1")], &data),
+            Ok(json!("bar"))
+        );
 
         let data_json = json!([{"foo": "bar"}]);
         let data = Data::from_json(&data_json);
         assert_eq!(
-            compute_const_with_data!(&[json!(0)], &data),
-            json!({"foo": "bar"})
+            compute_lazy_with_data!(&[json!(0)], &data),
+            Ok(json!({"foo": "bar"}))
         );
     }
 
@@ -106,12 +151,12 @@ mod tests {
         let data = Data::from_json(&data_json);
 
         assert_eq!(
-            compute_const_with_data!(&[json!(1), json!("def")], &data),
-            json!("bar")
+            compute_lazy_with_data!(&[json!(1), json!("def")], &data),
+            Ok(json!("bar"))
         );
         assert_eq!(
-            compute_const_with_data!(&[json!(2), json!("def")], &data),
-            json!("def")
+            compute_lazy_with_data!(&[json!(2), json!("def")], &data),
+            Ok(json!("def"))
         );
     }
 
@@ -121,12 +166,12 @@ mod tests {
         let data = Data::from_json(&data_json);
 
         assert_eq!(
-            compute_const_with_data!(&[json!("foo"), json!("def")], &data),
-            json!("bar")
+            compute_lazy_with_data!(&[json!("foo"), json!("def")], &data),
+            Ok(json!("bar"))
         );
         assert_eq!(
-            compute_const_with_data!(&[json!("unknown"), json!("def")], &data),
-            json!("def")
+            compute_lazy_with_data!(&[json!("unknown"), json!("def")], &data),
+            Ok(json!("def"))
         );
     }
 
@@ -136,40 +181,67 @@ mod tests {
         let data = Data::from_json(&data_json);
 
         assert_eq!(
-            compute_const_with_data!(&[json!("foo.")], &data),
-            json!(null)
+            compute_lazy_with_data!(&[json!("foo.")], &data),
+            Ok(json!(null))
         );
         assert_eq!(
-            compute_const_with_data!(&[json!("foo.bar")], &data),
-            json!("baz")
+            compute_lazy_with_data!(&[json!("foo.bar")], &data),
+            Ok(json!("baz"))
         );
         assert_eq!(
-            compute_const_with_data!(&[json!("foo.bar.baz")], &data),
-            json!(null)
+            compute_lazy_with_data!(&[json!("foo.bar.baz")], &data),
+            Ok(json!(null))
         );
         assert_eq!(
-            compute_const_with_data!(&[json!("foo")], &data),
-            json!({ "bar": "baz" })
+            compute_lazy_with_data!(&[json!("foo")], &data),
+            Ok(json!({ "bar": "baz" }))
         );
 
         let data_json = json!([{"foo": "bar"}]);
         let data = Data::from_json(&data_json);
         assert_eq!(
-            compute_const_with_data!(&[json!("0.foo")], &data),
-            json!("bar")
+            compute_lazy_with_data!(&[json!("0.foo")], &data),
+            Ok(json!("bar"))
         );
-        assert_eq!(compute_const_with_data!(&[json!("1")], &data), json!(null));
         assert_eq!(
-            compute_const_with_data!(&[json!("1.foo")], &data),
-            json!(null)
+            compute_lazy_with_data!(&[json!("1")], &data),
+            Ok(json!(null))
         );
         assert_eq!(
-            compute_const_with_data!(&[json!("0.foo.1")], &data),
-            json!("a")
+            compute_lazy_with_data!(&[json!("1.foo")], &data),
+            Ok(json!(null))
         );
         assert_eq!(
-            compute_const_with_data!(&[json!("0.foo.1.0")], &data),
-            json!(null)
+            compute_lazy_with_data!(&[json!("0.foo.1")], &data),
+            Ok(json!("a"))
         );
+        assert_eq!(
+            compute_lazy_with_data!(&[json!("0.foo.1.0")], &data),
+            Ok(json!(null))
+        );
+    }
+
+    #[test]
+    fn characters() {
+        let data_json = json!({"name": "a😀b"});
+        let data = Data::from_json(&data_json);
+        assert_eq!(
+            compute_lazy_with_data!(&[json!("name.1")], &data),
+            Ok(json!("😀"))
+        );
+        assert_eq!(
+            compute_lazy_with_data!(&[json!("name.3")], &data),
+            Ok(json!(null))
+        );
+
+        let data = Data::from_json(&data_json).with_string_mode(StringMode::Utf16);
+        assert_eq!(
+            compute_lazy_with_data!(&[json!("name.3")], &data),
+            Ok(json!("b"))
+        );
+
+        let data_json = json!("a😀b");
+        let data = Data::from_json(&data_json).with_string_mode(StringMode::Utf16);
+        assert_eq!(compute_lazy_with_data!(&[json!(3)], &data), Ok(json!("b")));
     }
 }
@@ -0,0 +1,44 @@
+use serde_json::Value;
+
+use super::Data;
+
+/// Combines the arrays of the arguments into an array of arrays, where the n-th array contains
+/// the n-th element of every argument. The result is as long as the shortest argument, so it is
+/// empty if an argument is not an array.
+pub fn compute(args: &[Value], _data: &Data) -> Value {
+    if args.is_empty() {
+        return Value::Array(vec![]);
+    }
+    let arrays: Vec<&[Value]> = args
+        .iter()
+        .map(|arg| match arg {
+            Value::Array(arr) => arr.as_slice(),
+            _ => &[],
+        })
+        .collect();
+    let len = arrays.iter().map(|arr| arr.len()).min().unwrap_or(0);
+
+    Value::Array(
+        (0..len)
+            .map(|index| Value::Array(arrays.iter().map(|arr| arr[index].clone()).collect()))
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compute_const;
+    use serde_json::json;
+
+    #[test]
+    fn test() {
+        assert_eq!(compute_const!(), json!([]));
+        assert_eq!(compute_const!(json!([1, 2])), json!([[1], [2]]));
+        assert_eq!(
+            compute_const!(json!([1, 2, 3]), json!(["a", "b"])),
+            json!([[1, "a"], [2, "b"]])
+        );
+        assert_eq!(compute_const!(json!([1, 2]), json!("ab")), json!([]));
+    }
+}
@@ -0,0 +1,619 @@
+use serde_json::{json, Map, Value};
+
+use crate::expression::Expression;
+use crate::operators::{logic, Operator};
+
+/// A comparison between a variable and a constant.
+#[derive(Debug, PartialEq, Copy, Clone)]
+enum Comparison {
+    Equal,
+    NotEqual,
+    LessThan,
+    LessEqualThan,
+    GreaterThan,
+    GreaterEqualThan,
+}
+
+impl Comparison {
+    /// Returns the comparison with swapped operands, so `a < b` becomes `b > a`.
+    fn flip(self) -> Comparison {
+        match self {
+            Comparison::LessThan => Comparison::GreaterThan,
+            Comparison::LessEqualThan => Comparison::GreaterEqualThan,
+            Comparison::GreaterThan => Comparison::LessThan,
+            Comparison::GreaterEqualThan => Comparison::LessEqualThan,
+            _ => self,
+        }
+    }
+}
+
+/// A rule in a form that maps directly to the supported query languages.
+///
+/// The paths of predicates inside of `Some`, `All` and `None` are relative to the elements of the
+/// array. The empty path stands for the element itself, which is only allowed in a comparison
+/// that is the direct condition of such a quantifier.
+#[derive(Debug, PartialEq)]
+enum Predicate<'a> {
+    Constant(bool),
+    Compare(String, Comparison, &'a Value),
+    /// A "between" comparison with a lower and an upper bound and whether they are inclusive.
+    Range(String, (&'a Value, bool), (&'a Value, bool)),
+    /// The variable is one of the given values.
+    In(String, &'a [Value]),
+    /// The variable is an array that contains the given value.
+    Contains(String, &'a Value),
+    /// At least one of the variables is missing.
+    Missing(Vec<String>),
+    And(Vec<Predicate<'a>>),
+    Or(Vec<Predicate<'a>>),
+    Not(Box<Predicate<'a>>),
+    Some(String, Box<Predicate<'a>>),
+    All(String, Box<Predicate<'a>>),
+    None(String, Box<Predicate<'a>>),
+}
+
+impl<'a> Predicate<'a> {
+    fn from_json(json_logic: &'a Value) -> Result<Predicate<'a>, String> {
+        let expr = Expression::from_json(json_logic)?;
+        Predicate::from_expression(&expr, false)
+    }
+
+    /// Converts the given expression. `in_quantifier` is true for the direct condition of `some`,
+    /// `all` and `none`, where the element itself may be compared.
+    fn from_expression(
+        expr: &Expression<'a>,
+        in_quantifier: bool,
+    ) -> Result<Predicate<'a>, String> {
+        let (operator, args) = match expr {
+            Expression::Constant(value) | Expression::Paths(value, _) => {
+                return Ok(Predicate::Constant(logic::is_truthy(value)))
+            }
+            #[cfg(feature = "regex")]
+            Expression::Pattern(value, _) => {
+                return Ok(Predicate::Constant(logic::is_truthy(value)))
+            }
+            Expression::Reference(_, expr) => {
+                return Predicate::from_expression(expr, in_quantifier)
+            }
+            Expression::Computed(operator, args) => (*operator, args),
+        };
+
+        let predicate = match operator {
+            Operator::Equal | Operator::StrictEqual => {
+                compare(args, Comparison::Equal, in_quantifier)?
+            }
+            Operator::NotEqual | Operator::StrictNotEqual => {
+                compare(args, Comparison::NotEqual, in_quantifier)?
+            }
+            Operator::LessThan | Operator::LessEqualThan if args.len() == 3 => {
+                let inclusive = operator == Operator::LessEqualThan;
+                match (
+                    constant(args.first()),
+                    variable(args.get(1), in_quantifier)?,
+                    constant(args.get(2)),
+                ) {
+                    (Some(lower), Some(path), Some(upper)) => {
+                        Predicate::Range(path, (lower, inclusive), (upper, inclusive))
+                    }
+                    _ => {
+                        return Err(String::from(
+                            "between must compare a variable to constant bounds",
+                        ))
+                    }
+                }
+            }
+            Operator::LessThan => compare(args, Comparison::LessThan, in_quantifier)?,
+            Operator::LessEqualThan => compare(args, Comparison::LessEqualThan, in_quantifier)?,
+            Operator::GreaterThan => compare(args, Comparison::GreaterThan, in_quantifier)?,
+            Operator::GreaterEqualThan => {
+                compare(args, Comparison::GreaterEqualThan, in_quantifier)?
+            }
+            Operator::In => match (args.first(), args.get(1)) {
+                (Some(var), Some(Expression::Constant(Value::Array(list)))) => {
+                    match variable(Some(var), in_quantifier)? {
+                        Some(path) => Predicate::In(path, list),
+                        None => return Err(String::from("in must test a variable")),
+                    }
+                }
+                (Some(Expression::Constant(value)), Some(var)) if is_scalar(value) => {
+                    match variable(Some(var), false)? {
+                        Some(path) => Predicate::Contains(path, value),
+                        None => return Err(String::from("in must test a variable")),
+                    }
+                }
+                _ => {
+                    return Err(String::from(
+                        "in must test a variable against a constant array or a constant against an array variable",
+                    ))
+                }
+            },
+            Operator::And => Predicate::And(predicates(args)?),
+            Operator::Or => Predicate::Or(predicates(args)?),
+            Operator::Negation => Predicate::Not(Box::new(single_predicate(args)?)),
+            Operator::DoubleNegation => single_predicate(args)?,
+            Operator::Missing => {
+                let keys: Vec<&Value> = args
+                    .iter()
+                    .map(|arg| {
+                        arg.as_constant()
+                            .ok_or_else(|| String::from("missing must have constant keys"))
+                    })
+                    .collect::<Result<_, _>>()?;
+                let keys: Vec<&Value> = match keys.as_slice() {
+                    [Value::Array(keys)] => keys.iter().collect(),
+                    _ => keys,
+                };
+                Predicate::Missing(
+                    keys.into_iter()
+                        .map(|key| path(key).ok_or("missing must have constant keys"))
+                        .collect::<Result<_, _>>()?,
+                )
+            }
+            Operator::Some | Operator::All | Operator::None => {
+                let path = match variable(args.first(), false)? {
+                    Some(path) => path,
+                    None => {
+                        return Err(format!(
+                            "{} must iterate over a variable",
+                            operator.as_str()
+                        ))
+                    }
+                };
+                let condition = match args.get(1) {
+                    Some(condition) => Predicate::from_expression(condition, true)?,
+                    None => Predicate::Constant(false),
+                };
+                match operator {
+                    Operator::Some => Predicate::Some(path, Box::new(condition)),
+                    Operator::All => Predicate::All(path, Box::new(condition)),
+                    _ => Predicate::None(path, Box::new(condition)),
+                }
+            }
+            _ => {
+                return Err(format!(
+                    "operation {} cannot be translated to a query",
+                    operator.as_str()
+                ))
+            }
+        };
+
+        Ok(predicate)
+    }
+
+    /// Returns whether this predicate compares the element of an array itself.
+    fn is_element_predicate(&self) -> bool {
+        match self {
+            Predicate::Compare(path, _, _)
+            | Predicate::Range(path, _, _)
+            | Predicate::In(path, _) => path.is_empty(),
+            _ => false,
+        }
+    }
+}
+
+fn predicates<'a>(args: &[Expression<'a>]) -> Result<Vec<Predicate<'a>>, String> {
+    args.iter()
+        .map(|arg| Predicate::from_expression(arg, false))
+        .collect()
+}
+
+fn single_predicate<'a>(args: &[Expression<'a>]) -> Result<Predicate<'a>, String> {
+    match args.first() {
+        Some(arg) => Predicate::from_expression(arg, false),
+        None => Ok(Predicate::Constant(false)),
+    }
+}
+
+fn compare<'a>(
+    args: &[Expression<'a>],
+    comparison: Comparison,
+    in_quantifier: bool,
+) -> Result<Predicate<'a>, String> {
+    if args.len() == 2 {
+        if let (Some(path), Some(value)) = (
+            variable(args.first(), in_quantifier)?,
+            constant(args.get(1)),
+        ) {
+            return Ok(Predicate::Compare(path, comparison, value));
+        }
+        if let (Some(value), Some(path)) = (
+            constant(args.first()),
+            variable(args.get(1), in_quantifier)?,
+        ) {
+            return Ok(Predicate::Compare(path, comparison.flip(), value));
+        }
+    }
+
+    Err(String::from(
+        "comparisons must compare a variable to a constant",
+    ))
+}
+
+/// Returns the path of the given `var` expression, or `None` if it is no `var` expression.
+fn variable(expr: Option<&Expression>, in_quantifier: bool) -> Result<Option<String>, String> {
+    let args = match expr {
+        Some(Expression::Computed(Operator::Variable, args)) => args,
+        Some(Expression::Reference(_, expr)) => return variable(Some(expr), in_quantifier),
+        _ => return Ok(None),
+    };
+
+    let path = match args.as_slice() {
+        [name] => name.as_constant().and_then(path),
+        _ => None,
+    }
+    .ok_or("var must have a constant path and no default value")?;
+    if path.is_empty() && !in_quantifier {
+        return Err(String::from(
+            "the empty path can only be compared directly in some, all and none",
+        ));
+    }
+
+    Ok(Some(path))
+}
+
+fn path(name: &Value) -> Option<String> {
+    match name {
+        Value::String(name) => Some(name.clone()),
+        Value::Number(index) => Some(index.to_string()),
+        _ => None,
+    }
+}
+
+fn constant<'a>(expr: Option<&Expression<'a>>) -> Option<&'a Value> {
+    match expr {
+        Some(Expression::Constant(value)) if is_scalar(value) => Some(value),
+        _ => None,
+    }
+}
+
+fn is_scalar(value: &Value) -> bool {
+    !value.is_array() && !value.is_object()
+}
+
+/// Translates the given JsonLogic rule into a MongoDB query document, as used by `find` and the
+/// `$match` stage.
+///
+/// Comparisons between a variable and a constant, "between", `in` with a constant array or with
+/// an array variable, `and`, `or`, `!`, `!!`, `missing` with constant keys and `some`, `all` and
+/// `none` over an array variable are supported. The conditions of `some`, `all` and `none` are
+/// evaluated against the elements of the array with `$elemMatch`; `{"var": ""}` refers to the
+/// element itself and can only be used if the condition is a single comparison.
+///
+/// Note that the query follows the semantics of MongoDB, not the type coercion of JsonLogic. For
+/// example, `{"<": [{"var": "a"}, 1]}` does not match documents without `a`.
+///
+/// # Example
+///
+/// ```
+/// use serde_json::json;
+///
+/// let rule = json!({ "and": [
+///     { ">=": [{ "var": "age" }, 18] },
+///     { "some": [{ "var": "orders" }, { "==": [{ "var": "status" }, "open"] }] }
+/// ]});
+/// assert_eq!(
+///     jsonlogic::to_mongodb_query(&rule),
+///     Ok(json!({ "$and": [
+///         { "age": { "$gte": 18 } },
+///         { "orders": { "$elemMatch": { "status": { "$eq": "open" } } } }
+///     ]}))
+/// );
+/// ```
+pub fn to_mongodb_query(json_logic: &Value) -> Result<Value, String> {
+    Predicate::from_json(json_logic).map(|predicate| mongodb(&predicate))
+}
+
+fn mongodb(predicate: &Predicate) -> Value {
+    // Conditions on the empty path are conditions on an array element itself, which consist of
+    // the operators only.
+    fn field(path: &str, condition: Value) -> Value {
+        if path.is_empty() {
+            condition
+        } else {
+            let mut query = Map::new();
+            query.insert(path.to_owned(), condition);
+            Value::Object(query)
+        }
+    }
+
+    match predicate {
+        Predicate::Constant(true) => json!({}),
+        Predicate::Constant(false) => json!({ "$expr": false }),
+        Predicate::Compare(path, comparison, value) => {
+            let operator = match comparison {
+                Comparison::Equal => "$eq",
+                Comparison::NotEqual => "$ne",
+                Comparison::LessThan => "$lt",
+                Comparison::LessEqualThan => "$lte",
+                Comparison::GreaterThan => "$gt",
+                Comparison::GreaterEqualThan => "$gte",
+            };
+            field(path, json!({ operator: value }))
+        }
+        Predicate::Range(path, (lower, inclusive), (upper, _)) => {
+            let (lower_operator, upper_operator) = if *inclusive {
+                ("$gte", "$lte")
+            } else {
+                ("$gt", "$lt")
+            };
+            field(
+                path,
+                json!({ lower_operator: lower, upper_operator: upper }),
+            )
+        }
+        Predicate::In(path, list) => field(path, json!({ "$in": list })),
+        Predicate::Contains(path, value) => field(path, json!({ "$elemMatch": { "$eq": value } })),
+        Predicate::Missing(paths) => match paths.as_slice() {
+            [] => mongodb(&Predicate::Constant(false)),
+            [path] => field(path, json!({ "$exists": false })),
+            _ => json!({
+                "$or": paths
+                    .iter()
+                    .map(|path| field(path, json!({ "$exists": false })))
+                    .collect::<Vec<_>>()
+            }),
+        },
+        Predicate::And(predicates) if predicates.is_empty() => mongodb(&Predicate::Constant(false)),
+        Predicate::And(predicates) => {
+            json!({ "$and": predicates.iter().map(mongodb).collect::<Vec<_>>() })
+        }
+        Predicate::Or(predicates) if predicates.is_empty() => mongodb(&Predicate::Constant(false)),
+        Predicate::Or(predicates) => {
+            json!({ "$or": predicates.iter().map(mongodb).collect::<Vec<_>>() })
+        }
+        Predicate::Not(predicate) => json!({ "$nor": [mongodb(predicate)] }),
+        Predicate::Some(path, condition) => {
+            field(path, json!({ "$elemMatch": mongodb(condition) }))
+        }
+        Predicate::None(path, condition) => field(
+            path,
+            json!({ "$not": { "$elemMatch": mongodb(condition) } }),
+        ),
+        Predicate::All(path, condition) => {
+            // There must not be an element that does not match the condition. Like in JsonLogic,
+            // an empty array does not match.
+            let negated = if condition.is_element_predicate() {
+                json!({ "$not": mongodb(condition) })
+            } else {
+                json!({ "$nor": [mongodb(condition)] })
+            };
+            json!({ "$and": [
+                field(path, json!({ "$exists": true, "$not": { "$size": 0 } })),
+                field(path, json!({ "$not": { "$elemMatch": negated } })),
+            ]})
+        }
+    }
+}
+
+/// Translates the given JsonLogic rule into an Elasticsearch query, as used in the `query` of a
+/// search request.
+///
+/// The same operations as for `to_mongodb_query` are supported. `and` is translated to a `filter`
+/// clause, so it does not contribute to the score. `some`, `all` and `none` over an array of
+/// objects are translated to `nested` queries, so these arrays must be mapped as `nested` fields.
+/// Conditions on the elements of an array of values (`{"var": ""}`) are translated to a query on
+/// the array field itself, which is not possible for `all`.
+///
+/// Note that the query follows the semantics of Elasticsearch, not the type coercion of
+/// JsonLogic, and that `==` is translated to a `term` query, so it should only be used on
+/// `keyword`, numeric, date and boolean fields.
+///
+/// # Example
+///
+/// ```
+/// use serde_json::json;
+///
+/// let rule = json!({ "and": [
+///     { ">=": [{ "var": "age" }, 18] },
+///     { "in": [{ "var": "country" }, ["DE", "AT"]] }
+/// ]});
+/// assert_eq!(
+///     jsonlogic::to_elasticsearch_query(&rule),
+///     Ok(json!({ "bool": { "filter": [
+///         { "range": { "age": { "gte": 18 } } },
+///         { "terms": { "country": ["DE", "AT"] } }
+///     ]}}))
+/// );
+/// ```
+pub fn to_elasticsearch_query(json_logic: &Value) -> Result<Value, String> {
+    let predicate = Predicate::from_json(json_logic)?;
+    elasticsearch(&predicate, "")
+}
+
+fn elasticsearch(predicate: &Predicate, prefix: &str) -> Result<Value, String> {
+    let full_path = |path: &str| match (prefix.is_empty(), path.is_empty()) {
+        (true, _) => path.to_owned(),
+        (false, true) => prefix.to_owned(),
+        (false, false) => format!("{}.{}", prefix, path),
+    };
+    let not = |query: Value| json!({ "bool": { "must_not": [query] } });
+    let exists = |path: &str| json!({ "exists": { "field": full_path(path) } });
+
+    let query = match predicate {
+        Predicate::Constant(true) => json!({ "match_all": {} }),
+        Predicate::Constant(false) => json!({ "match_none": {} }),
+        // Term queries cannot match `null`, which is not indexed.
+        Predicate::Compare(path, Comparison::Equal, Value::Null) => not(exists(path)),
+        Predicate::Compare(path, Comparison::NotEqual, Value::Null) => exists(path),
+        Predicate::Compare(path, Comparison::Equal, value) => {
+            json!({ "term": { full_path(path): value } })
+        }
+        Predicate::Compare(path, Comparison::NotEqual, value) => {
+            not(json!({ "term": { full_path(path): value } }))
+        }
+        Predicate::Compare(path, comparison, value) => {
+            let operator = match comparison {
+                Comparison::LessThan => "lt",
+                Comparison::LessEqualThan => "lte",
+                Comparison::GreaterThan => "gt",
+                _ => "gte",
+            };
+            json!({ "range": { full_path(path): { operator: value } } })
+        }
+        Predicate::Range(path, (lower, inclusive), (upper, _)) => {
+            let (lower_operator, upper_operator) = if *inclusive {
+                ("gte", "lte")
+            } else {
+                ("gt", "lt")
+            };
+            json!({ "range": { full_path(path): { lower_operator: lower, upper_operator: upper } } })
+        }
+        Predicate::In(path, list) => json!({ "terms": { full_path(path): list } }),
+        Predicate::Contains(path, value) => json!({ "term": { full_path(path): value } }),
+        Predicate::Missing(paths) => match paths.as_slice() {
+            [] => json!({ "match_none": {} }),
+            [path] => not(exists(path)),
+            _ => json!({ "bool": {
+                "should": paths.iter().map(|path| not(exists(path))).collect::<Vec<_>>(),
+                "minimum_should_match": 1
+            }}),
+        },
+        Predicate::And(predicates) if predicates.is_empty() => json!({ "match_none": {} }),
+        Predicate::And(predicates) => json!({ "bool": {
+            "filter": predicates
+                .iter()
+                .map(|predicate| elasticsearch(predicate, prefix))
+                .collect::<Result<Vec<_>, String>>()?
+        }}),
+        Predicate::Or(predicates) if predicates.is_empty() => json!({ "match_none": {} }),
+        Predicate::Or(predicates) => json!({ "bool": {
+            "should": predicates
+                .iter()
+                .map(|predicate| elasticsearch(predicate, prefix))
+                .collect::<Result<Vec<_>, String>>()?,
+            "minimum_should_match": 1
+        }}),
+        Predicate::Not(predicate) => not(elasticsearch(predicate, prefix)?),
+        Predicate::Some(path, condition) => elasticsearch_some(&full_path(path), condition)?,
+        Predicate::None(path, condition) => not(elasticsearch_some(&full_path(path), condition)?),
+        Predicate::All(path, condition) => {
+            if condition.is_element_predicate() {
+                return Err(String::from(
+                    "all over an array of values cannot be translated to Elasticsearch",
+                ));
+            }
+            // The array must not be empty and there must not be an element that does not match
+            // the condition.
+            let path = full_path(path);
+            json!({ "bool": {
+                "filter": [elasticsearch_some(&path, &Predicate::Constant(true))?],
+                "must_not": [{ "nested": {
+                    "path": path,
+                    "query": not(elasticsearch(condition, &path)?)
+                }}]
+            }})
+        }
+    };
+
+    Ok(query)
+}
+
+/// Translates `some` over the array with the given full path.
+fn elasticsearch_some(path: &str, condition: &Predicate) -> Result<Value, String> {
+    if condition.is_element_predicate() {
+        // Queries on an array of values match if any of the values matches.
+        return elasticsearch(condition, path);
+    }
+
+    Ok(json!({ "nested": {
+        "path": path,
+        "query": elasticsearch(condition, path)?
+    }}))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn predicates() {
+        let json_logic = json!({ "or": [
+            { ">": [5, { "var": "a" }] },
+            { "<=": [1, { "var": "b" }, 3] },
+            { "!": { "in": ["x", { "var": "tags" }] } },
+            { "missing": [["c", "d"]] },
+            { "some": [{ "var": "items" }, { "==": [{ "var": "" }, 2] }] },
+        ]});
+        let values = json!([5, 1, 3, "x", 2]);
+        assert_eq!(
+            Predicate::from_json(&json_logic),
+            Ok(Predicate::Or(vec![
+                Predicate::Compare(String::from("a"), Comparison::LessThan, &values[0]),
+                Predicate::Range(String::from("b"), (&values[1], true), (&values[2], true)),
+                Predicate::Not(Box::new(Predicate::Contains(
+                    String::from("tags"),
+                    &values[3]
+                ))),
+                Predicate::Missing(vec![String::from("c"), String::from("d")]),
+                Predicate::Some(
+                    String::from("items"),
+                    Box::new(Predicate::Compare(
+                        String::new(),
+                        Comparison::Equal,
+                        &values[4]
+                    ))
+                ),
+            ]))
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let error = |json_logic: Value| Predicate::from_json(&json_logic).map(|_| ());
+
+        assert_eq!(
+            error(json!({ "==": [{ "var": "a" }, { "var": "b" }] })),
+            Err(String::from(
+                "comparisons must compare a variable to a constant"
+            ))
+        );
+        assert_eq!(
+            error(json!({ "==": [{ "var": ["a", 1] }, 1] })),
+            Err(String::from(
+                "var must have a constant path and no default value"
+            ))
+        );
+        assert_eq!(
+            error(json!({ "==": [{ "var": "" }, 1] })),
+            Err(String::from(
+                "the empty path can only be compared directly in some, all and none"
+            ))
+        );
+        assert_eq!(
+            error(json!({ "some": [{ "var": "a" }, { "!": { "==": [{ "var": "" }, 1] } }] })),
+            Err(String::from(
+                "the empty path can only be compared directly in some, all and none"
+            ))
+        );
+        assert_eq!(
+            error(json!({ "<": [1, { "var": "a" }, { "var": "b" }] })),
+            Err(String::from(
+                "between must compare a variable to constant bounds"
+            ))
+        );
+        assert_eq!(
+            error(json!({ "all": [[1, 2], { "var": "" }] })),
+            Err(String::from("all must iterate over a variable"))
+        );
+        assert_eq!(
+            error(json!({ "in": [{ "var": "a" }, { "var": "b" }] })),
+            Err(String::from(
+                "in must test a variable against a constant array or a constant against an array variable"
+            ))
+        );
+        assert_eq!(
+            error(json!({ "cat": ["a", "b"] })),
+            Err(String::from(
+                "operation cat cannot be translated to a query"
+            ))
+        );
+        assert_eq!(
+            to_elasticsearch_query(
+                &json!({ "all": [{ "var": "a" }, { ">": [{ "var": "" }, 1] }] })
+            ),
+            Err(String::from(
+                "all over an array of values cannot be translated to Elasticsearch"
+            ))
+        );
+    }
+}
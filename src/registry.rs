@@ -0,0 +1,302 @@
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::expression::Expression;
+use crate::Rule;
+
+/// A collection of named JsonLogic rules that other rules can reference with
+/// `{"rule": "<name>"}`.
+///
+/// References are resolved when a rule is compiled through the registry. A reference to an
+/// unknown rule and cyclic references are compile errors. By default, every named rule is parsed
+/// once per compilation and shared by all references to it. `compile_inlined` replaces every
+/// reference with a copy of the referenced rule instead, which results in a single expression
+/// tree.
+///
+/// # Example
+///
+/// ```
+/// use serde_json::json;
+/// use jsonlogic::RuleRegistry;
+///
+/// let named_rules = json!({
+///     "is_adult": { ">=": [{ "var": "age" }, 18] },
+///     "is_premium_customer": { "and": [{ "rule": "is_adult" }, { "var": "premium" }] }
+/// });
+/// let registry = RuleRegistry::from_json(&named_rules).unwrap();
+///
+/// let json_logic = json!({ "if": [{ "rule": "is_premium_customer" }, 0.2, 0] });
+/// let rule = registry.compile(&json_logic).unwrap();
+/// assert_eq!(rule.apply(&json!({ "age": 30, "premium": true })), Ok(json!(0.2)));
+/// assert_eq!(rule.apply(&json!({ "age": 16, "premium": true })), Ok(json!(0)));
+/// ```
+#[derive(Debug, Default)]
+pub struct RuleRegistry<'a> {
+    rules: HashMap<String, &'a Value>,
+}
+
+impl<'a> RuleRegistry<'a> {
+    /// Creates an empty registry.
+    pub fn new() -> RuleRegistry<'a> {
+        RuleRegistry::default()
+    }
+
+    /// Creates a registry from a JSON object that maps rule names to rules.
+    pub fn from_json(json: &'a Value) -> Result<RuleRegistry<'a>, String> {
+        let object = json.as_object().ok_or("named rules must be an object")?;
+
+        let mut registry = RuleRegistry::new();
+        for (name, json_logic) in object.iter() {
+            registry.insert(name, json_logic);
+        }
+
+        Ok(registry)
+    }
+
+    /// Adds a named rule to the registry, replacing a previous rule with the same name.
+    pub fn insert(&mut self, name: &str, json_logic: &'a Value) {
+        self.rules.insert(name.to_owned(), json_logic);
+    }
+
+    /// Compiles the given rule, resolving all references to named rules of this registry.
+    pub fn compile(&self, json_logic: &'a Value) -> Result<Rule<'a>, String> {
+        Resolver::new(self, false).compile(json_logic)
+    }
+
+    /// Compiles the given rule, replacing all references to named rules of this registry with
+    /// the referenced rules.
+    pub fn compile_inlined(&self, json_logic: &'a Value) -> Result<Rule<'a>, String> {
+        Resolver::new(self, true).compile(json_logic)
+    }
+
+    /// Compiles the named rule of this registry.
+    pub fn compile_named(&self, name: &str) -> Result<Rule<'a>, String> {
+        let mut resolver = Resolver::new(self, false);
+        resolver.resolve(name).map(Rule::from_expression)
+    }
+
+    /// Compiles all named rules of this registry. Errors on the first unknown or cyclic reference
+    /// and on any other parse error.
+    pub fn validate(&self) -> Result<(), String> {
+        let mut names: Vec<_> = self.rules.keys().collect();
+        // Report errors deterministically.
+        names.sort();
+
+        let mut resolver = Resolver::new(self, false);
+        for name in names {
+            resolver.resolve(name)?;
+        }
+        Ok(())
+    }
+}
+
+/// Resolves the references of a single compilation.
+struct Resolver<'r, 'a> {
+    registry: &'r RuleRegistry<'a>,
+    inline: bool,
+    /// The names of the rules that are currently being resolved, in the order of their
+    /// references, to detect cycles.
+    stack: Vec<String>,
+    /// Rules that were already resolved during this compilation.
+    resolved: HashMap<String, Arc<Expression<'a>>>,
+}
+
+impl<'r, 'a> Resolver<'r, 'a> {
+    fn new(registry: &'r RuleRegistry<'a>, inline: bool) -> Resolver<'r, 'a> {
+        Resolver {
+            registry,
+            inline,
+            stack: vec![],
+            resolved: HashMap::new(),
+        }
+    }
+
+    fn compile(&mut self, json_logic: &'a Value) -> Result<Rule<'a>, String> {
+        self.parse(json_logic).map(Rule::from_expression)
+    }
+
+    /// Parses the given rule, resolving its references.
+    fn parse(&mut self, json_logic: &'a Value) -> Result<Expression<'a>, String> {
+        Expression::from_json_with_extension(json_logic, &mut |operation, value| {
+            if operation != "rule" {
+                return None;
+            }
+            Some(Expression::reference_name(operation, value).and_then(|name| self.resolve(name)))
+        })
+    }
+
+    fn resolve(&mut self, name: &str) -> Result<Expression<'a>, String> {
+        if let Some(start) = self.stack.iter().position(|other| other == name) {
+            let mut cycle = self.stack[start..].to_vec();
+            cycle.push(name.to_owned());
+            return Err(format!("cyclic rule reference {}", cycle.join(" -> ")));
+        }
+
+        let expr = match self.resolved.get(name) {
+            Some(expr) => expr.clone(),
+            None => {
+                let json_logic = self
+                    .registry
+                    .rules
+                    .get(name)
+                    .ok_or_else(|| format!("Unknown rule {}", name))?;
+
+                self.stack.push(name.to_owned());
+                let expr = self.parse(json_logic);
+                self.stack.pop();
+
+                let expr = Arc::new(expr?);
+                self.resolved.insert(name.to_owned(), expr.clone());
+                expr
+            }
+        };
+
+        if self.inline {
+            Ok((*expr).clone())
+        } else {
+            Ok(Expression::Reference(name.to_owned(), expr))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::Path;
+    use crate::operators::Operator;
+    use serde_json::json;
+    use std::collections::HashSet;
+
+    fn registry_json() -> Value {
+        json!({
+            "a": { "var": "a" },
+            "b": { "and": [{ "rule": "a" }, { "var": "b" }] },
+            "c": { "or": [{ "rule": "b" }, { "rule": "a" }, { "var": ["c", 1] }] },
+        })
+    }
+
+    #[test]
+    fn compile() {
+        let json = registry_json();
+        let registry = RuleRegistry::from_json(&json).unwrap();
+        assert_eq!(registry.validate(), Ok(()));
+
+        let json_logic = json!({ "!": { "rule": ["c"] } });
+        let rule = registry.compile(&json_logic).unwrap();
+        assert_eq!(rule.apply(&json!({ "a": 1, "b": 2 })), Ok(json!(false)));
+        assert_eq!(rule.apply(&json!({ "c": 0 })), Ok(json!(true)));
+
+        let rule = registry.compile_named("b").unwrap();
+        assert_eq!(rule.apply(&json!({ "a": 1, "b": 2 })), Ok(json!(2)));
+    }
+
+    #[test]
+    fn shared_and_inlined() {
+        let json = registry_json();
+        let registry = RuleRegistry::from_json(&json).unwrap();
+        let json_logic = json!({ "rule": "b" });
+
+        let a = Expression::Computed(
+            Operator::Variable,
+            vec![Expression::Paths(&json["a"]["var"], vec![Path::parse("a")])],
+        );
+        let b_var = Expression::Computed(
+            Operator::Variable,
+            vec![Expression::Paths(
+                &json["b"]["and"][1]["var"],
+                vec![Path::parse("b")],
+            )],
+        );
+
+        assert_eq!(
+            registry.compile(&json_logic).unwrap().expression(),
+            &Expression::Reference(
+                String::from("b"),
+                Arc::new(Expression::Computed(
+                    Operator::And,
+                    vec![
+                        Expression::Reference(String::from("a"), Arc::new(a.clone())),
+                        b_var.clone(),
+                    ]
+                ))
+            )
+        );
+        assert_eq!(
+            registry.compile_inlined(&json_logic).unwrap().expression(),
+            &Expression::Computed(Operator::And, vec![a, b_var])
+        );
+    }
+
+    #[test]
+    fn variable_names() {
+        let json = registry_json();
+        let registry = RuleRegistry::from_json(&json).unwrap();
+        let json_logic = json!({ "rule": "c" });
+
+        let names: HashSet<_> = ["a", "b", "c"]
+            .iter()
+            .map(|name| name.to_string())
+            .collect();
+        assert_eq!(
+            registry
+                .compile(&json_logic)
+                .unwrap()
+                .expression()
+                .get_variable_names(),
+            Ok(names.clone())
+        );
+        assert_eq!(
+            registry
+                .compile_inlined(&json_logic)
+                .unwrap()
+                .expression()
+                .get_variable_names(),
+            Ok(names)
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let json = json!({
+            "a": { "rule": "b" },
+            "b": { "and": [true, { "rule": "c" }] },
+            "c": { "!": { "rule": "a" } },
+            "d": { "rule": "unknown" },
+            "e": { "rule": { "var": "name" } },
+            "f": { "rule": "f" },
+        });
+        let registry = RuleRegistry::from_json(&json).unwrap();
+
+        assert_eq!(
+            registry.validate(),
+            Err(String::from("cyclic rule reference a -> b -> c -> a"))
+        );
+        assert_eq!(
+            registry.compile_named("c").map(|_| ()),
+            Err(String::from("cyclic rule reference c -> a -> b -> c"))
+        );
+        assert_eq!(
+            registry.compile(&json!({ "rule": "f" })).map(|_| ()),
+            Err(String::from("cyclic rule reference f -> f"))
+        );
+        assert_eq!(
+            registry.compile_named("d").map(|_| ()),
+            Err(String::from("Unknown rule unknown"))
+        );
+        assert_eq!(
+            registry.compile_named("e").map(|_| ()),
+            Err(String::from("rule reference must be a constant string"))
+        );
+        assert_eq!(
+            registry.compile_named("x").map(|_| ()),
+            Err(String::from("Unknown rule x"))
+        );
+
+        // Without a registry, references are unknown operations.
+        assert_eq!(
+            Rule::compile(&json!({ "rule": "a" })).map(|_| ()),
+            Err(String::from("Unrecognized operation rule"))
+        );
+    }
+}
@@ -0,0 +1,141 @@
+use serde_json::Value;
+use std::fmt;
+
+use crate::expression::Expression;
+use crate::{Data, StringMode};
+
+/// A JsonLogic rule that has been parsed once and can be applied to any number of data objects
+/// afterwards, without parsing the rule again.
+///
+/// # Example
+///
+/// ```
+/// use serde_json::json;
+/// use jsonlogic::Rule;
+///
+/// let json_logic = json!({ ">=": [{ "var": "score" }, 700] });
+/// let rule = Rule::compile(&json_logic).unwrap();
+///
+/// assert_eq!(rule.apply(&json!({ "score": 720 })), Ok(json!(true)));
+/// assert_eq!(rule.apply(&json!({ "score": 650 })), Ok(json!(false)));
+/// ```
+#[derive(Debug, PartialEq)]
+pub struct Rule<'a> {
+    expression: Expression<'a>,
+    string_mode: StringMode,
+}
+
+impl<'a> Rule<'a> {
+    /// Parses the given JsonLogic rule. Errors if the rule contains an unrecognized operation.
+    pub fn compile(json_logic: &'a Value) -> Result<Rule<'a>, String> {
+        Ok(Rule::from_expression(Expression::from_json(json_logic)?))
+    }
+
+    pub(crate) fn from_expression(expression: Expression<'a>) -> Rule<'a> {
+        Rule {
+            expression,
+            string_mode: StringMode::default(),
+        }
+    }
+
+    /// Sets how strings are measured and indexed when applying the rule, instead of
+    /// `StringMode::Chars`.
+    pub fn with_string_mode(self, string_mode: StringMode) -> Rule<'a> {
+        Rule {
+            string_mode,
+            ..self
+        }
+    }
+
+    fn data<'d>(&self, data: &'d Value) -> Data<'d> {
+        Data::from_json(data).with_string_mode(self.string_mode)
+    }
+
+    /// Applies the rule to the given data. Errors with the thrown value if the rule throws an
+    /// error with the `throw` operator that is not caught by `try`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use jsonlogic::Rule;
+    ///
+    /// let json_logic = json!({ "if": [
+    ///     { "<": [{ "var": "age" }, 0] },
+    ///     { "throw": { "object": ["code", "E_AGE", "field", "age"] } },
+    ///     { "var": "age" }
+    /// ]});
+    /// let rule = Rule::compile(&json_logic).unwrap();
+    ///
+    /// assert_eq!(rule.apply(&json!({ "age": 42 })), Ok(json!(42)));
+    /// assert_eq!(
+    ///     rule.apply(&json!({ "age": -1 })),
+    ///     Err(json!({ "code": "E_AGE", "field": "age" }))
+    /// );
+    /// ```
+    pub fn apply(&self, data: &Value) -> Result<Value, Value> {
+        self.expression.compute(&self.data(data))
+    }
+
+    /// Applies the rule to the given data, with `clock` as the source of the current time for
+    /// the `now` operator.
+    #[cfg(feature = "datetime")]
+    pub fn apply_with_clock(&self, data: &Value, clock: &dyn crate::Clock) -> Result<Value, Value> {
+        self.expression.compute(&self.data(data).with_clock(clock))
+    }
+
+    pub(crate) fn expression(&self) -> &Expression<'a> {
+        &self.expression
+    }
+}
+
+/// Formats the rule as readable infix text. The text is meant for humans, like in reviews of
+/// rules, and cannot be parsed again.
+///
+/// # Example
+///
+/// ```
+/// use serde_json::json;
+/// use jsonlogic::Rule;
+///
+/// let json_logic = json!({ "if": [
+///     { "==": [{ "%": [{ "var": "i" }, 15] }, 0] }, "fizzbuzz",
+///     { "==": [{ "%": [{ "var": "i" }, 3] }, 0] }, "fizz",
+///     { "var": "i" }
+/// ]});
+/// let rule = Rule::compile(&json_logic).unwrap();
+///
+/// assert_eq!(
+///     rule.to_string(),
+///     r#"if (i % 15 == 0) "fizzbuzz" else if (i % 3 == 0) "fizz" else i"#
+/// );
+/// ```
+impl<'a> fmt::Display for Rule<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.expression.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn compile_error() {
+        assert_eq!(
+            Rule::compile(&json!({ "foo": [1, 2] })),
+            Err(String::from("Unrecognized operation foo"))
+        );
+    }
+
+    #[test]
+    fn apply_multiple_times() {
+        let json_logic = json!({ "if": [{ "var": "a" }, "yes", "no"] });
+        let rule = Rule::compile(&json_logic).unwrap();
+
+        assert_eq!(rule.apply(&json!({ "a": true })), Ok(json!("yes")));
+        assert_eq!(rule.apply(&json!({ "a": false })), Ok(json!("no")));
+        assert_eq!(rule.apply(&Value::Null), Ok(json!("no")));
+    }
+}
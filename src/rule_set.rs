@@ -0,0 +1,412 @@
+use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::data::Path;
+use crate::expression::Expression;
+use crate::operators::{logic, Operator};
+use crate::{Data, Rule};
+
+/// A key under which rules are indexed. Two values that may be equal to each other, according to
+/// either abstract or strict equality, always share at least one key.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+enum Key {
+    Str(String),
+    /// The bits of the `f64` representation of a number, with `-0` normalized to `0`.
+    Num(u64),
+}
+
+impl Key {
+    fn from_f64(num: f64) -> Key {
+        // -0 == 0, so both must end up under the same key.
+        if num == 0f64 {
+            Key::Num(0f64.to_bits())
+        } else {
+            Key::Num(num.to_bits())
+        }
+    }
+
+    /// Returns the key under which a rule with an equality predicate against the given constant
+    /// is indexed. Returns `None` if the predicate could be truthy for values that cannot be
+    /// looked up by a key, in which case the rule is not indexed.
+    fn for_constant(constant: &Value, strict: bool) -> Option<Key> {
+        match constant {
+            Value::Number(num) => num.as_f64().map(Key::from_f64),
+            // Non-strict equality coerces numeric strings, i.e. `"1" == true` or `"1" == [1]`.
+            Value::String(s) if strict || logic::coerce_to_f64(constant).is_none() => {
+                Some(Key::Str(s.clone()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns all keys of rules whose indexed predicate might be truthy for the given value. If
+    /// the candidate rules cannot be narrowed down by key, `None` is returned.
+    fn candidates_for(value: &Value) -> Option<Vec<Key>> {
+        match value {
+            Value::String(s) => {
+                let mut keys = vec![Key::Str(s.clone())];
+                if let Some(num) = logic::coerce_to_f64(value) {
+                    keys.push(Key::from_f64(num));
+                }
+                Some(keys)
+            }
+            Value::Number(_) | Value::Bool(_) => logic::coerce_to_f64(value)
+                .map(|num| vec![Key::from_f64(num)])
+                .or_else(|| Some(vec![])),
+            // Indexed constants are never equal to null or an object.
+            Value::Null | Value::Object(_) => Some(vec![]),
+            // Arrays are coerced into primitives in too many ways to be looked up by key.
+            Value::Array(_) => None,
+        }
+    }
+}
+
+/// All rules that are indexed by a predicate on the same variable.
+#[derive(Debug)]
+struct PathIndex<'a> {
+    /// The `var` expression that reads the value the predicates of this index test.
+    variable: Expression<'a>,
+    rules: HashMap<Key, Vec<usize>>,
+    /// All rules in this index, in case the looked up value cannot be used as a key.
+    all: Vec<usize>,
+}
+
+/// A set of rules that is matched against data as a whole.
+///
+/// Rules that require an equality (`==`, `===`) or `in` predicate between a constant variable
+/// path and constant values, either at the top level or inside a top level `and`, are indexed by
+/// these values. Matching the set then only evaluates the rules whose indexed predicate can be
+/// truthy for the given data, all other indexed rules are skipped. Rules without such a predicate
+/// are always evaluated.
+///
+/// # Example
+///
+/// ```
+/// use serde_json::json;
+/// use jsonlogic::RuleSet;
+///
+/// let rules = vec![
+///     json!({ "and": [{ "==": [{ "var": "country" }, "DE"] }, { ">": [{ "var": "age" }, 17] }] }),
+///     json!({ "in": [{ "var": "country" }, ["US", "CA"]] }),
+///     json!({ "<": [{ "var": "age" }, 18] }),
+/// ];
+/// let mut rule_set = RuleSet::new();
+/// for rule in rules.iter() {
+///     rule_set.add(rule).unwrap();
+/// }
+///
+/// assert_eq!(rule_set.matching(&json!({ "country": "DE", "age": 30 })), vec![0]);
+/// assert_eq!(rule_set.matching(&json!({ "country": "CA", "age": 16 })), vec![1, 2]);
+/// ```
+#[derive(Debug, Default)]
+pub struct RuleSet<'a> {
+    rules: Vec<Rule<'a>>,
+    /// Rules without an indexable predicate that are evaluated for every data object.
+    unindexed: Vec<usize>,
+    /// Indices keyed by the path of their variable.
+    indices: HashMap<&'a str, PathIndex<'a>>,
+}
+
+impl<'a> RuleSet<'a> {
+    /// Creates an empty rule set.
+    pub fn new() -> RuleSet<'a> {
+        RuleSet::default()
+    }
+
+    /// Parses the given JsonLogic rule and adds it to the set. Returns the id of the rule, which
+    /// is the number of rules added before it.
+    pub fn add(&mut self, json_logic: &'a Value) -> Result<usize, String> {
+        let rule = Rule::compile(json_logic)?;
+        let id = self.rules.len();
+
+        match find_indexed_predicate(rule.expression()) {
+            Some((path, keys)) => {
+                // `variable_path` only returns string paths.
+                let key = path.as_str().unwrap();
+                let index = self.indices.entry(key).or_insert_with(|| PathIndex {
+                    variable: Expression::Computed(
+                        Operator::Variable,
+                        // Like in a parsed rule, the empty path refers to the data itself.
+                        vec![if key.is_empty() {
+                            Expression::Constant(path)
+                        } else {
+                            Expression::Paths(path, vec![Path::parse(key)])
+                        }],
+                    ),
+                    rules: HashMap::new(),
+                    all: vec![],
+                });
+                for key in keys {
+                    index.rules.entry(key).or_default().push(id);
+                }
+                index.all.push(id);
+            }
+            None => self.unindexed.push(id),
+        }
+
+        self.rules.push(rule);
+        Ok(id)
+    }
+
+    /// Returns the number of rules in this set.
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Returns the ids of all rules that evaluate to a truthy value for the given data, in
+    /// ascending order. Rules that throw an error that is not caught do not match.
+    pub fn matching(&self, data: &Value) -> Vec<usize> {
+        let mut candidates = self.unindexed.clone();
+
+        let wrapped_data = Data::from_json(data);
+        for index in self.indices.values() {
+            let keys = match index.variable.compute(&wrapped_data) {
+                Ok(value) => Key::candidates_for(&value),
+                Err(_) => None,
+            };
+            match keys {
+                Some(keys) => {
+                    for key in keys.iter() {
+                        if let Some(ids) = index.rules.get(key) {
+                            candidates.extend(ids);
+                        }
+                    }
+                }
+                None => candidates.extend(&index.all),
+            }
+        }
+
+        // A rule may be a candidate for multiple keys.
+        candidates.sort_unstable();
+        candidates.dedup();
+        candidates.retain(|&id| match self.rules[id].apply(data) {
+            Ok(value) => logic::is_truthy(&value),
+            Err(_) => false,
+        });
+        candidates
+    }
+}
+
+/// Searches for an equality or `in` predicate that must be truthy for the whole expression to be
+/// truthy. Returns the path of the variable the predicate tests and the keys of the constants it
+/// is tested against.
+fn find_indexed_predicate<'a>(expr: &Expression<'a>) -> Option<(&'a Value, Vec<Key>)> {
+    match expr {
+        // `and` is only truthy if all of its arguments are truthy.
+        Expression::Computed(Operator::And, args) => args.iter().find_map(find_indexed_predicate),
+        Expression::Reference(_, expr) => find_indexed_predicate(expr),
+        Expression::Computed(operator @ Operator::Equal, args)
+        | Expression::Computed(operator @ Operator::StrictEqual, args)
+            if args.len() == 2 =>
+        {
+            let strict = *operator == Operator::StrictEqual;
+            match (&args[0], &args[1]) {
+                (var, Expression::Constant(constant)) | (Expression::Constant(constant), var) => {
+                    let path = variable_path(var)?;
+                    Key::for_constant(constant, strict).map(|key| (path, vec![key]))
+                }
+                _ => None,
+            }
+        }
+        // `in` tests array membership with strict equality.
+        Expression::Computed(Operator::In, args) if args.len() == 2 => match &args[1] {
+            Expression::Constant(Value::Array(arr)) => {
+                let path = variable_path(&args[0])?;
+                arr.iter()
+                    .map(|el| Key::for_constant(el, true))
+                    .collect::<Option<Vec<_>>>()
+                    .map(|keys| (path, keys))
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Returns the path argument of a `var` expression with a single constant string argument.
+fn variable_path<'a>(expr: &Expression<'a>) -> Option<&'a Value> {
+    match expr {
+        Expression::Computed(Operator::Variable, args) if args.len() == 1 => {
+            args[0].as_constant().filter(|path| path.is_string())
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn rule_set(rules: &[Value]) -> RuleSet<'_> {
+        let mut rule_set = RuleSet::new();
+        for rule in rules.iter() {
+            rule_set.add(rule).unwrap();
+        }
+        rule_set
+    }
+
+    #[test]
+    fn indexing() {
+        let rules = [
+            json!({ "==": [{ "var": "country" }, "DE"] }),
+            json!({ "===": ["US", { "var": "country" }] }),
+            json!({ "and": [{ "<": [{ "var": "age" }, 18] }, { "in": [{ "var": "country" }, ["DE", "FR"]] }] }),
+            json!({ "==": [{ "var": "tier" }, 2] }),
+            // Numeric strings are not indexed for abstract equality.
+            json!({ "==": [{ "var": "tier" }, "2"] }),
+            json!({ "or": [{ "==": [{ "var": "country" }, "DE"] }, true] }),
+        ];
+        let rule_set = rule_set(&rules);
+
+        assert_eq!(rule_set.len(), 6);
+        assert_eq!(rule_set.unindexed, vec![4, 5]);
+        assert_eq!(rule_set.indices["country"].all, vec![0, 1, 2]);
+        assert_eq!(rule_set.indices["tier"].all, vec![3]);
+    }
+
+    #[test]
+    fn matching() {
+        let rules = [
+            json!({ "==": [{ "var": "country" }, "DE"] }),
+            json!({ "===": ["US", { "var": "country" }] }),
+            json!({ "and": [{ "<": [{ "var": "age" }, 18] }, { "in": [{ "var": "country" }, ["DE", "FR"]] }] }),
+            json!({ "==": [{ "var": "tier" }, 2] }),
+            json!({ "==": [{ "var": "tier" }, "2"] }),
+        ];
+        let rule_set = rule_set(&rules);
+
+        assert_eq!(rule_set.matching(&json!({})), Vec::<usize>::new());
+        assert_eq!(
+            rule_set.matching(&json!({ "country": "DE", "age": 30 })),
+            vec![0]
+        );
+        assert_eq!(
+            rule_set.matching(&json!({ "country": "DE", "age": 10 })),
+            vec![0, 2]
+        );
+        assert_eq!(rule_set.matching(&json!({ "country": "US" })), vec![1]);
+        assert_eq!(rule_set.matching(&json!({ "country": ["DE"] })), vec![0]);
+        assert_eq!(rule_set.matching(&json!({ "tier": 2 })), vec![3, 4]);
+        assert_eq!(rule_set.matching(&json!({ "tier": "2.0" })), vec![3]);
+        assert_eq!(rule_set.matching(&json!({ "tier": [2] })), vec![3, 4]);
+    }
+
+    #[test]
+    fn thrown_errors_do_not_match() {
+        let rules = [
+            json!({ "==": [{ "var": ["country", { "throw": "NoCountry" }] }, "DE"] }),
+            json!({ "or": [{ "var": "vip" }, { "throw": "NotVip" }] }),
+        ];
+        let rule_set = rule_set(&rules);
+
+        assert_eq!(rule_set.matching(&json!({ "country": "DE" })), vec![0]);
+        assert_eq!(rule_set.matching(&json!({ "vip": true })), vec![1]);
+        assert_eq!(rule_set.matching(&json!({})), Vec::<usize>::new());
+    }
+
+    /// A xorshift pseudo random number generator, good enough to generate test cases.
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn pick<T: Clone>(&mut self, items: &[T]) -> T {
+            items[self.next() as usize % items.len()].clone()
+        }
+    }
+
+    fn random_constant(rng: &mut Rng) -> Value {
+        rng.pick(&[
+            json!("DE"),
+            json!("US"),
+            json!("1"),
+            json!(" 1 "),
+            json!(""),
+            json!("true"),
+            json!(1),
+            json!(1.0),
+            json!(0),
+            json!(-0.0),
+            json!(2),
+            json!(true),
+            json!(false),
+            json!(null),
+            json!([1]),
+            json!(["DE"]),
+            json!([]),
+            json!({}),
+        ])
+    }
+
+    fn random_predicate(rng: &mut Rng, depth: usize) -> Value {
+        let var = json!({ "var": rng.pick(&["a", "b", "c.d", ""]) });
+        match rng.next() % if depth > 0 { 7 } else { 4 } {
+            0 => json!({ "==": [var, random_constant(rng)] }),
+            1 => json!({ "===": [random_constant(rng), var] }),
+            2 => {
+                let list: Vec<_> = (0..rng.next() % 4).map(|_| random_constant(rng)).collect();
+                json!({ "in": [var, list] })
+            }
+            3 => json!({ "<": [var, random_constant(rng)] }),
+            4 => {
+                let args: Vec<_> = (0..rng.next() % 4)
+                    .map(|_| random_predicate(rng, depth - 1))
+                    .collect();
+                json!({ "and": args })
+            }
+            5 => {
+                let args: Vec<_> = (0..rng.next() % 4)
+                    .map(|_| random_predicate(rng, depth - 1))
+                    .collect();
+                json!({ "or": args })
+            }
+            _ => json!({ "!": random_predicate(rng, depth - 1) }),
+        }
+    }
+
+    fn random_data(rng: &mut Rng) -> Value {
+        // The empty path tests the whole data, which is not always an object.
+        if rng.next() % 5 == 4 {
+            return random_constant(rng);
+        }
+        let mut data = json!({ "c": {} });
+        for key in ["a", "b"].iter() {
+            if rng.next() % 5 < 4 {
+                data[key] = random_constant(rng);
+            }
+        }
+        if rng.next() % 5 < 4 {
+            data["c"]["d"] = random_constant(rng);
+        }
+        data
+    }
+
+    #[test]
+    fn random_rules_match_brute_force() {
+        let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+        let rules: Vec<_> = (0..1000).map(|_| random_predicate(&mut rng, 3)).collect();
+        let rule_set = rule_set(&rules);
+        let compiled: Vec<_> = rules.iter().map(|r| Rule::compile(r).unwrap()).collect();
+
+        for _ in 0..500 {
+            let data = random_data(&mut rng);
+            let expected: Vec<_> = compiled
+                .iter()
+                .enumerate()
+                .filter(|(_, rule)| logic::is_truthy(&rule.apply(&data).unwrap()))
+                .map(|(id, _)| id)
+                .collect();
+            assert_eq!(rule_set.matching(&data), expected, "data: {}", data);
+        }
+    }
+}
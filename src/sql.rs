@@ -0,0 +1,1023 @@
+use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::expression::Expression;
+use crate::operators::Operator;
+
+/// The SQL dialect a `SqlTranslator` generates.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum SqlDialect {
+    /// Bind parameters are numbered (`$1`, `$2`, ...), JSON columns are accessed with `#>>`. As
+    /// `#>>` returns text, the values are cast to `NUMERIC` or `BOOLEAN` where they are used as
+    /// such.
+    PostgreSql,
+    /// Bind parameters are positional (`?`), JSON columns are accessed with `json_extract`.
+    Sqlite,
+}
+
+/// A parameterized SQL predicate, together with the values of its bind parameters in the order
+/// of their occurrence.
+#[derive(Debug, PartialEq, Clone)]
+pub struct SqlQuery {
+    pub sql: String,
+    pub params: Vec<Value>,
+}
+
+/// Translates JsonLogic rules into parameterized SQL predicates that can be used in a `WHERE`
+/// clause.
+///
+/// Variables are mapped to SQL expressions, usually column names, with `map_column`. Variables
+/// without a mapping are read from the JSON column set with `set_json_column`, if any. All
+/// constants are passed as bind parameters, except `null`.
+///
+/// The following operations are supported: `var` with a constant path, `==`, `===`, `!=`, `!==`,
+/// `<`, `<=`, `>`, `>=` (including "between"), `and`, `or`, `!`, `!!`, `if`, `in` (with a
+/// constant array or as substring test), `+`, `-`, `*`, `/`, `%`, `min`, `max`, `cat`, `missing`
+/// and `missing_some` with constant keys. Translating any other operation is an error.
+///
+/// Note that the translated predicate follows SQL semantics, not the type coercion of JsonLogic.
+/// The operands of `and`, `or`, `!` and `!!` are used as SQL booleans and `missing` and
+/// `missing_some` are translated to the predicate whether any of the keys are missing. Values of a
+/// JSON column in PostgreSQL are compared as the type of what they are compared with, or as
+/// numbers by `<` and friends if that is unknown.
+///
+/// # Example
+///
+/// ```
+/// use serde_json::json;
+/// use jsonlogic::{SqlDialect, SqlTranslator};
+///
+/// let mut translator = SqlTranslator::new(SqlDialect::PostgreSql);
+/// translator.map_column("age", "customers.age");
+/// translator.set_json_column("customers.attributes");
+///
+/// let query = translator
+///     .translate(&json!({ "and": [
+///         { ">=": [{ "var": "age" }, 18] },
+///         { "in": [{ "var": "address.country" }, ["US", "CA"]] }
+///     ]}))
+///     .unwrap();
+/// assert_eq!(
+///     query.sql,
+///     "(customers.age >= $1 AND (customers.attributes #>> '{address,country}') IN ($2, $3))"
+/// );
+/// assert_eq!(query.params, vec![json!(18), json!("US"), json!("CA")]);
+/// ```
+#[derive(Debug)]
+pub struct SqlTranslator {
+    dialect: SqlDialect,
+    columns: HashMap<String, String>,
+    json_column: Option<String>,
+}
+
+impl SqlTranslator {
+    /// Creates a translator without any column mapping.
+    pub fn new(dialect: SqlDialect) -> SqlTranslator {
+        SqlTranslator {
+            dialect,
+            columns: HashMap::new(),
+            json_column: None,
+        }
+    }
+
+    /// Maps the variable with the given path to the given SQL expression, which is inserted into
+    /// the generated SQL as is.
+    pub fn map_column(&mut self, path: &str, sql: &str) {
+        self.columns.insert(path.to_owned(), sql.to_owned());
+    }
+
+    /// Reads all variables without a column mapping from the given JSON column, using the path of
+    /// the variable as JSON path. The path may only consist of alphanumeric characters,
+    /// underscores and dots.
+    pub fn set_json_column(&mut self, sql: &str) {
+        self.json_column = Some(sql.to_owned());
+    }
+
+    /// Translates the given JsonLogic rule.
+    pub fn translate(&self, json_logic: &Value) -> Result<SqlQuery, String> {
+        let expr = Expression::from_json(json_logic)?;
+        let mut translation = Translation {
+            translator: self,
+            params: vec![],
+        };
+        // The predicate is used as a boolean in the `WHERE` clause.
+        let sql = translation.translate_as(&expr, Some(SqlType::Boolean))?;
+
+        Ok(SqlQuery {
+            sql,
+            params: translation.params,
+        })
+    }
+
+    /// Tests whether the variable is read as text from the JSON column, which is the case in
+    /// PostgreSQL for all variables without a column mapping.
+    fn reads_text(&self, path: &str) -> bool {
+        self.dialect == SqlDialect::PostgreSql && !self.columns.contains_key(path)
+    }
+
+    fn column(&self, path: &str) -> Result<String, String> {
+        if let Some(sql) = self.columns.get(path) {
+            return Ok(sql.clone());
+        }
+
+        let json_column = self
+            .json_column
+            .as_ref()
+            .ok_or_else(|| format!("variable {} is not mapped to a column", path))?;
+        // The path is inserted into the SQL, so only allow characters that are safe to use.
+        let steps: Vec<_> = path.split('.').collect();
+        if steps.iter().any(|step| {
+            step.is_empty()
+                || !step
+                    .chars()
+                    .all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+        }) {
+            return Err(format!(
+                "variable {} cannot be used as a JSON path in SQL",
+                path
+            ));
+        }
+
+        Ok(match self.dialect {
+            SqlDialect::PostgreSql => format!("{} #>> '{{{}}}'", json_column, steps.join(",")),
+            SqlDialect::Sqlite => {
+                let json_path: String = steps
+                    .iter()
+                    .map(|step| {
+                        if step.chars().all(|ch| ch.is_ascii_digit()) {
+                            format!("[{}]", step)
+                        } else {
+                            format!(".{}", step)
+                        }
+                    })
+                    .collect();
+                format!("json_extract({}, '${}')", json_column, json_path)
+            }
+        })
+    }
+}
+
+/// The type an operand is used as. Variables that are read as text are cast to it.
+#[derive(Debug, PartialEq, Copy, Clone)]
+enum SqlType {
+    Numeric,
+    Boolean,
+    Text,
+}
+
+impl SqlType {
+    /// Returns the type of the translation of the expression, if it is known without the types
+    /// of the columns.
+    fn of(expr: &Expression) -> Option<SqlType> {
+        match expr {
+            Expression::Constant(Value::Number(_)) => Some(SqlType::Numeric),
+            Expression::Constant(Value::Bool(_)) => Some(SqlType::Boolean),
+            Expression::Constant(Value::String(_)) => Some(SqlType::Text),
+            Expression::Reference(_, expr) => SqlType::of(expr),
+            Expression::Computed(operator, _) => match operator {
+                Operator::Addition
+                | Operator::Subtraction
+                | Operator::Multiplication
+                | Operator::Division
+                | Operator::Modulo
+                | Operator::Min
+                | Operator::Max => Some(SqlType::Numeric),
+                Operator::Equal
+                | Operator::StrictEqual
+                | Operator::NotEqual
+                | Operator::StrictNotEqual
+                | Operator::LessThan
+                | Operator::LessEqualThan
+                | Operator::GreaterThan
+                | Operator::GreaterEqualThan
+                | Operator::And
+                | Operator::Or
+                | Operator::Negation
+                | Operator::DoubleNegation
+                | Operator::In
+                | Operator::Missing
+                | Operator::MissingSome => Some(SqlType::Boolean),
+                Operator::Cat => Some(SqlType::Text),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Returns the common type of the operands that are compared with each other.
+    fn common(args: &[Expression]) -> Option<SqlType> {
+        args.iter().find_map(SqlType::of)
+    }
+}
+
+/// The state of a single translation.
+struct Translation<'t> {
+    translator: &'t SqlTranslator,
+    params: Vec<Value>,
+}
+
+impl<'t> Translation<'t> {
+    fn translate(&mut self, expr: &Expression) -> Result<String, String> {
+        self.translate_as(expr, None)
+    }
+
+    /// Translates an expression that is used as the given type, if it is known.
+    fn translate_as(&mut self, expr: &Expression, ty: Option<SqlType>) -> Result<String, String> {
+        match expr {
+            Expression::Constant(value) | Expression::Paths(value, _) => self.constant(value),
+            #[cfg(feature = "regex")]
+            Expression::Pattern(value, _) => self.constant(value),
+            Expression::Reference(_, expr) => self.translate_as(expr, ty),
+            Expression::Computed(Operator::Variable, args) => self.variable(args, ty),
+            Expression::Computed(operator, args) => self.operation(*operator, args),
+        }
+    }
+
+    fn constant(&mut self, value: &Value) -> Result<String, String> {
+        match value {
+            Value::Null => Ok(String::from("NULL")),
+            Value::Array(_) | Value::Object(_) => Err(String::from(
+                "arrays and objects cannot be translated to SQL, except as list of in",
+            )),
+            _ => Ok(self.bind(value)),
+        }
+    }
+
+    fn bind(&mut self, value: &Value) -> String {
+        self.params.push(value.clone());
+        match self.translator.dialect {
+            SqlDialect::PostgreSql => format!("${}", self.params.len()),
+            SqlDialect::Sqlite => String::from("?"),
+        }
+    }
+
+    fn operation(&mut self, operator: Operator, args: &[Expression]) -> Result<String, String> {
+        match operator {
+            Operator::Variable => self.variable(args, None),
+            Operator::Equal | Operator::StrictEqual => self.equality(args, "=", "IS NULL"),
+            Operator::NotEqual | Operator::StrictNotEqual => {
+                self.equality(args, "<>", "IS NOT NULL")
+            }
+            Operator::LessThan => self.comparison(args, "<"),
+            Operator::LessEqualThan => self.comparison(args, "<="),
+            Operator::GreaterThan => self.comparison(args, ">"),
+            Operator::GreaterEqualThan => self.comparison(args, ">="),
+            Operator::And => self.connect(args, " AND ", "FALSE"),
+            Operator::Or => self.connect(args, " OR ", "FALSE"),
+            Operator::Negation => Ok(format!(
+                "NOT ({})",
+                self.arg_as(args, 0, Some(SqlType::Boolean))?
+            )),
+            Operator::DoubleNegation => Ok(format!(
+                "({})",
+                self.arg_as(args, 0, Some(SqlType::Boolean))?
+            )),
+            Operator::If => self.if_else(args),
+            Operator::In => self.is_in(args),
+            Operator::Addition if args.len() == 1 => {
+                let num = self.arg(args, 0)?;
+                Ok(self.cast_to_number(&num))
+            }
+            Operator::Addition => self.join(args, " + ", "0", Some(SqlType::Numeric)),
+            Operator::Subtraction if args.len() == 1 => Ok(format!(
+                "-{}",
+                self.operand_as(args, 0, Some(SqlType::Numeric))?
+            )),
+            Operator::Subtraction => self.binary(args, "-", Some(SqlType::Numeric)),
+            Operator::Multiplication => self.join(args, " * ", "NULL", Some(SqlType::Numeric)),
+            Operator::Division => {
+                // Avoid integer division.
+                let a = self.arg(args, 0)?;
+                Ok(format!(
+                    "({} / {})",
+                    self.cast_to_number(&a),
+                    self.operand_as(args, 1, Some(SqlType::Numeric))?
+                ))
+            }
+            Operator::Modulo => self.binary(args, "%", Some(SqlType::Numeric)),
+            Operator::Min => self.function(args, "LEAST", "MIN"),
+            Operator::Max => self.function(args, "GREATEST", "MAX"),
+            Operator::Cat => self.join(args, " || ", "''", None),
+            Operator::Missing => self.missing(args),
+            Operator::MissingSome => self.missing_some(args),
+            _ => Err(format!(
+                "operation {} cannot be translated to SQL",
+                operator.as_str()
+            )),
+        }
+    }
+
+    fn arg(&mut self, args: &[Expression], index: usize) -> Result<String, String> {
+        self.arg_as(args, index, None)
+    }
+
+    fn arg_as(
+        &mut self,
+        args: &[Expression],
+        index: usize,
+        ty: Option<SqlType>,
+    ) -> Result<String, String> {
+        match args.get(index) {
+            Some(arg) => self.translate_as(arg, ty),
+            None => Ok(String::from("NULL")),
+        }
+    }
+
+    /// Translates an argument that is an operand of an SQL operator. Operands are put in
+    /// parentheses, unless they are columns, parameters, literals or already enclosed, since the
+    /// precedence of the SQL operators differs from the nesting of the rule.
+    fn operand(&mut self, args: &[Expression], index: usize) -> Result<String, String> {
+        self.operand_as(args, index, None)
+    }
+
+    fn operand_as(
+        &mut self,
+        args: &[Expression],
+        index: usize,
+        ty: Option<SqlType>,
+    ) -> Result<String, String> {
+        let sql = self.arg_as(args, index, ty)?;
+        match args.get(index) {
+            Some(arg) if !self.is_enclosed(arg, ty) => Ok(format!("({})", sql)),
+            _ => Ok(sql),
+        }
+    }
+
+    /// Tests whether the translation of the expression as the given type can be used as an
+    /// operand as it is.
+    fn is_enclosed(&self, expr: &Expression, ty: Option<SqlType>) -> bool {
+        match expr {
+            Expression::Reference(_, expr) => self.is_enclosed(expr, ty),
+            Expression::Computed(operator, args) => match operator {
+                // A column, a cast or a `COALESCE` call, except for the `#>>` operator that reads
+                // text from a JSON column in PostgreSQL.
+                Operator::Variable => {
+                    args.len() > 1
+                        || cast_name(ty).is_some()
+                        || variable_path(args)
+                            .map_or(true, |path| !self.translator.reads_text(&path))
+                }
+                Operator::Subtraction | Operator::Modulo => false,
+                // Translated to parentheses or function calls.
+                Operator::LessThan
+                | Operator::LessEqualThan
+                | Operator::GreaterThan
+                | Operator::GreaterEqualThan => args.len() == 3,
+                Operator::Negation
+                | Operator::Equal
+                | Operator::StrictEqual
+                | Operator::NotEqual
+                | Operator::StrictNotEqual
+                | Operator::In
+                | Operator::MissingSome => false,
+                _ => true,
+            },
+            _ => true,
+        }
+    }
+
+    fn cast_to_number(&self, sql: &str) -> String {
+        match self.translator.dialect {
+            SqlDialect::PostgreSql => format!("CAST({} AS DOUBLE PRECISION)", sql),
+            SqlDialect::Sqlite => format!("CAST({} AS REAL)", sql),
+        }
+    }
+
+    fn variable(&mut self, args: &[Expression], ty: Option<SqlType>) -> Result<String, String> {
+        let path = variable_path(args)?;
+        let mut column = self.translator.column(&path)?;
+        if let Some(name) = cast_name(ty).filter(|_| self.translator.reads_text(&path)) {
+            column = format!("CAST({} AS {})", column, name);
+        }
+        match args.get(1) {
+            Some(default) => Ok(format!(
+                "COALESCE({}, {})",
+                column,
+                self.translate_as(default, ty)?
+            )),
+            None => Ok(column),
+        }
+    }
+
+    fn equality(&mut self, args: &[Expression], op: &str, null_op: &str) -> Result<String, String> {
+        match (args.first(), args.get(1)) {
+            (Some(Expression::Constant(Value::Null)), Some(other))
+            | (Some(other), Some(Expression::Constant(Value::Null)))
+            | (Some(other), None) => {
+                let other = self.operand(std::slice::from_ref(other), 0)?;
+                Ok(format!("{} {}", other, null_op))
+            }
+            _ => self.binary(args, op, SqlType::common(args)),
+        }
+    }
+
+    /// Translates `<` and friends, which have a special "between" form with three arguments.
+    fn comparison(&mut self, args: &[Expression], op: &str) -> Result<String, String> {
+        let ty = Some(SqlType::common(args).unwrap_or(SqlType::Numeric));
+        if args.len() == 3 {
+            let a = self.operand_as(args, 0, ty)?;
+            let b = self.operand_as(args, 1, ty)?;
+            let b_again = self.operand_as(args, 1, ty)?;
+            let c = self.operand_as(args, 2, ty)?;
+            return Ok(format!("({} {} {} AND {} {} {})", a, op, b, b_again, op, c));
+        }
+
+        self.binary(args, op, ty)
+    }
+
+    fn binary(
+        &mut self,
+        args: &[Expression],
+        op: &str,
+        ty: Option<SqlType>,
+    ) -> Result<String, String> {
+        let a = self.operand_as(args, 0, ty)?;
+        let b = self.operand_as(args, 1, ty)?;
+        Ok(format!("{} {} {}", a, op, b))
+    }
+
+    /// Joins the arguments with an operator like `+`, with parentheses around each operand that
+    /// needs them.
+    fn join(
+        &mut self,
+        args: &[Expression],
+        separator: &str,
+        empty: &str,
+        ty: Option<SqlType>,
+    ) -> Result<String, String> {
+        if args.is_empty() {
+            return Ok(empty.to_owned());
+        }
+
+        let parts = (0..args.len())
+            .map(|index| self.operand_as(args, index, ty))
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(format!("({})", parts.join(separator)))
+    }
+
+    /// Joins the arguments with `AND` or `OR`, which have a lower precedence than any operator
+    /// the arguments are translated to.
+    fn connect(
+        &mut self,
+        args: &[Expression],
+        separator: &str,
+        empty: &str,
+    ) -> Result<String, String> {
+        if args.is_empty() {
+            return Ok(empty.to_owned());
+        }
+
+        let parts = args
+            .iter()
+            .map(|arg| self.translate_as(arg, Some(SqlType::Boolean)))
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(format!("({})", parts.join(separator)))
+    }
+
+    fn function(
+        &mut self,
+        args: &[Expression],
+        postgres_name: &str,
+        sqlite_name: &str,
+    ) -> Result<String, String> {
+        let name = match self.translator.dialect {
+            SqlDialect::PostgreSql => postgres_name,
+            SqlDialect::Sqlite => sqlite_name,
+        };
+        let parts = args
+            .iter()
+            .map(|arg| self.translate_as(arg, Some(SqlType::Numeric)))
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(format!("{}({})", name, parts.join(", ")))
+    }
+
+    fn if_else(&mut self, args: &[Expression]) -> Result<String, String> {
+        if args.len() <= 1 {
+            return self.arg(args, 0);
+        }
+
+        let mut sql = String::from("CASE");
+        let mut pairs = args.chunks(2);
+        for pair in &mut pairs {
+            match pair {
+                [condition, then] => {
+                    let condition = self.translate_as(condition, Some(SqlType::Boolean))?;
+                    let then = self.translate(then)?;
+                    sql.push_str(&format!(" WHEN {} THEN {}", condition, then));
+                }
+                [otherwise] => sql.push_str(&format!(" ELSE {}", self.translate(otherwise)?)),
+                _ => unreachable!(),
+            }
+        }
+        sql.push_str(" END");
+        Ok(sql)
+    }
+
+    fn is_in(&mut self, args: &[Expression]) -> Result<String, String> {
+        match args.get(1) {
+            Some(Expression::Constant(Value::Array(list))) => {
+                if list.is_empty() {
+                    return Ok(String::from("FALSE"));
+                }
+                let ty = list
+                    .first()
+                    .and_then(|el| SqlType::of(&Expression::Constant(el)));
+                let a = self.operand_as(args, 0, ty)?;
+                let list = list
+                    .iter()
+                    .map(|el| self.constant(el))
+                    .collect::<Result<Vec<_>, String>>()?;
+                Ok(format!("{} IN ({})", a, list.join(", ")))
+            }
+            // Test for a substring.
+            Some(_) => {
+                let a = self.arg(args, 0)?;
+                let b = self.arg(args, 1)?;
+                Ok(match self.translator.dialect {
+                    SqlDialect::PostgreSql => format!("strpos({}, {}) > 0", b, a),
+                    SqlDialect::Sqlite => format!("instr({}, {}) > 0", b, a),
+                })
+            }
+            None => Ok(String::from("FALSE")),
+        }
+    }
+
+    /// Returns the columns of the constant keys of `missing` and `missing_some`.
+    fn key_columns(&self, keys: &[&Value]) -> Result<Vec<String>, String> {
+        keys.iter()
+            .map(|key| match key {
+                Value::String(path) => self.translator.column(path),
+                Value::Number(index) => self.translator.column(&index.to_string()),
+                _ => Err(String::from(
+                    "only constant keys of missing can be translated to SQL",
+                )),
+            })
+            .collect()
+    }
+
+    fn missing(&mut self, args: &[Expression]) -> Result<String, String> {
+        let keys: Vec<&Value> = args
+            .iter()
+            .map(|arg| {
+                arg.as_constant().ok_or_else(|| {
+                    String::from("only constant keys of missing can be translated to SQL")
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        let keys: Vec<&Value> = match keys.as_slice() {
+            [Value::Array(keys)] => keys.iter().collect(),
+            _ => keys,
+        };
+        if keys.is_empty() {
+            return Ok(String::from("FALSE"));
+        }
+
+        let columns: Vec<_> = self
+            .key_columns(&keys)?
+            .into_iter()
+            .map(|column| format!("{} IS NULL", column))
+            .collect();
+        Ok(format!("({})", columns.join(" OR ")))
+    }
+
+    fn missing_some(&mut self, args: &[Expression]) -> Result<String, String> {
+        let constants: Vec<_> = args.iter().map(Expression::as_constant).collect();
+        let (min, keys) = match constants.as_slice() {
+            [Some(Value::Number(min)), Some(Value::Array(keys))] => (min, keys),
+            _ => {
+                return Err(String::from(
+                    "only missing_some with constant arguments can be translated to SQL",
+                ))
+            }
+        };
+
+        let present: Vec<_> = self
+            .key_columns(&keys.iter().collect::<Vec<_>>())?
+            .into_iter()
+            .map(|column| format!("CASE WHEN {} IS NULL THEN 0 ELSE 1 END", column))
+            .collect();
+        if present.is_empty() {
+            return Ok(format!("0 < {}", self.bind(&Value::Number(min.clone()))));
+        }
+        let min = self.bind(&Value::Number(min.clone()));
+        Ok(format!("({}) < {}", present.join(" + "), min))
+    }
+}
+
+/// Returns the SQL name of the type that text is cast to when it is used as the type.
+fn cast_name(ty: Option<SqlType>) -> Option<&'static str> {
+    match ty? {
+        SqlType::Numeric => Some("NUMERIC"),
+        SqlType::Boolean => Some("BOOLEAN"),
+        SqlType::Text => None,
+    }
+}
+
+/// Returns the path of a `var` operation, which must be a constant.
+fn variable_path(args: &[Expression]) -> Result<String, String> {
+    match args.first().and_then(Expression::as_constant) {
+        Some(Value::String(path)) if !path.is_empty() => Ok(path.clone()),
+        Some(Value::Number(index)) => Ok(index.to_string()),
+        _ => Err(String::from(
+            "only var with a constant, non empty path can be translated to SQL",
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn translator(dialect: SqlDialect) -> SqlTranslator {
+        let mut translator = SqlTranslator::new(dialect);
+        translator.map_column("age", "age");
+        translator.map_column("name", "first_name");
+        translator.set_json_column("data");
+        translator
+    }
+
+    fn sqlite(json_logic: Value) -> Result<(String, Vec<Value>), String> {
+        translator(SqlDialect::Sqlite)
+            .translate(&json_logic)
+            .map(|query| (query.sql, query.params))
+    }
+
+    fn postgres(json_logic: Value) -> Result<(String, Vec<Value>), String> {
+        translator(SqlDialect::PostgreSql)
+            .translate(&json_logic)
+            .map(|query| (query.sql, query.params))
+    }
+
+    #[test]
+    fn variables() {
+        assert_eq!(
+            sqlite(json!({ "var": "age" })),
+            Ok((String::from("age"), vec![]))
+        );
+        assert_eq!(
+            sqlite(json!({ "var": ["name", "unknown"] })),
+            Ok((
+                String::from("COALESCE(first_name, ?)"),
+                vec![json!("unknown")]
+            ))
+        );
+        assert_eq!(
+            sqlite(json!({ "var": "address.lines.0" })),
+            Ok((
+                String::from("json_extract(data, '$.address.lines[0]')"),
+                vec![]
+            ))
+        );
+        assert_eq!(
+            postgres(json!({ "==": [{ "var": "address.lines.0" }, "x"] })),
+            Ok((
+                String::from("(data #>> '{address,lines,0}') = $1"),
+                vec![json!("x")]
+            ))
+        );
+        assert_eq!(
+            sqlite(json!({ "var": "a'b" })),
+            Err(String::from(
+                "variable a'b cannot be used as a JSON path in SQL"
+            ))
+        );
+        assert_eq!(
+            SqlTranslator::new(SqlDialect::Sqlite).translate(&json!({ "var": "a" })),
+            Err(String::from("variable a is not mapped to a column"))
+        );
+        assert_eq!(
+            sqlite(json!({ "var": { "var": "a" } })),
+            Err(String::from(
+                "only var with a constant, non empty path can be translated to SQL"
+            ))
+        );
+    }
+
+    #[test]
+    fn comparisons() {
+        assert_eq!(
+            postgres(json!({ "==": [{ "var": "age" }, 18] })),
+            Ok((String::from("age = $1"), vec![json!(18)]))
+        );
+        assert_eq!(
+            postgres(json!({ "!==": [{ "var": "name" }, "Bob"] })),
+            Ok((String::from("first_name <> $1"), vec![json!("Bob")]))
+        );
+        assert_eq!(
+            postgres(json!({ "==": [null, { "var": "age" }] })),
+            Ok((String::from("age IS NULL"), vec![]))
+        );
+        assert_eq!(
+            postgres(json!({ "!=": [{ "var": "age" }, null] })),
+            Ok((String::from("age IS NOT NULL"), vec![]))
+        );
+        assert_eq!(
+            postgres(json!({ ">": [{ "var": "age" }, 18] })),
+            Ok((String::from("age > $1"), vec![json!(18)]))
+        );
+        assert_eq!(
+            postgres(json!({ "<=": [18, { "var": "age" }, 65] })),
+            Ok((
+                String::from("($1 <= age AND age <= $2)"),
+                vec![json!(18), json!(65)]
+            ))
+        );
+    }
+
+    #[test]
+    fn postgres_casts() {
+        assert_eq!(
+            postgres(json!({ "<": [{ "var": "a" }, 10] })),
+            Ok((
+                String::from("CAST(data #>> '{a}' AS NUMERIC) < $1"),
+                vec![json!(10)]
+            ))
+        );
+        assert_eq!(
+            postgres(json!({ "<": [{ "var": "a" }, { "var": "b" }] })),
+            Ok((
+                String::from("CAST(data #>> '{a}' AS NUMERIC) < CAST(data #>> '{b}' AS NUMERIC)"),
+                vec![]
+            ))
+        );
+        assert_eq!(
+            postgres(json!({ "<": [{ "var": "a" }, "m"] })),
+            Ok((String::from("(data #>> '{a}') < $1"), vec![json!("m")]))
+        );
+        assert_eq!(
+            postgres(json!({ "<=": [1, { "var": "a" }, { "var": "age" }] })),
+            Ok((
+                String::from("($1 <= CAST(data #>> '{a}' AS NUMERIC) AND CAST(data #>> '{a}' AS NUMERIC) <= age)"),
+                vec![json!(1)]
+            ))
+        );
+        assert_eq!(
+            postgres(json!({ "==": [{ "var": "a" }, { "+": [{ "var": "b" }, 1] }] })),
+            Ok((
+                String::from(
+                    "CAST(data #>> '{a}' AS NUMERIC) = (CAST(data #>> '{b}' AS NUMERIC) + $1)"
+                ),
+                vec![json!(1)]
+            ))
+        );
+        assert_eq!(
+            postgres(json!({ "==": [{ "var": "a" }, true] })),
+            Ok((
+                String::from("CAST(data #>> '{a}' AS BOOLEAN) = $1"),
+                vec![json!(true)]
+            ))
+        );
+        assert_eq!(
+            postgres(json!({ "==": [{ "var": "a" }, { "var": "name" }] })),
+            Ok((String::from("(data #>> '{a}') = first_name"), vec![]))
+        );
+        assert_eq!(
+            postgres(json!({ "in": [{ "var": "a" }, [1, 2]] })),
+            Ok((
+                String::from("CAST(data #>> '{a}' AS NUMERIC) IN ($1, $2)"),
+                vec![json!(1), json!(2)]
+            ))
+        );
+        assert_eq!(
+            postgres(json!({ "max": [{ "var": ["a", 0] }, { "-": { "var": "b" } }] })),
+            Ok((
+                String::from("GREATEST(COALESCE(CAST(data #>> '{a}' AS NUMERIC), $1), -CAST(data #>> '{b}' AS NUMERIC))"),
+                vec![json!(0)]
+            ))
+        );
+        assert_eq!(
+            postgres(json!({ "if": [
+                { "and": [{ "var": "vip" }, { "!": { "var": "blocked" } }] },
+                { "cat": [{ "var": "a" }, "!"] },
+                { "var": "b" }
+            ]})),
+            Ok((
+                String::from("CASE WHEN (CAST(data #>> '{vip}' AS BOOLEAN) AND NOT (CAST(data #>> '{blocked}' AS BOOLEAN))) THEN ((data #>> '{a}') || $1) ELSE data #>> '{b}' END"),
+                vec![json!("!")]
+            ))
+        );
+        assert_eq!(
+            postgres(json!({ "var": "active" })),
+            Ok((String::from("CAST(data #>> '{active}' AS BOOLEAN)"), vec![]))
+        );
+    }
+
+    #[test]
+    fn logic() {
+        assert_eq!(
+            sqlite(json!({ "or": [
+                { "!": { "<": [{ "var": "age" }, 18] } },
+                { "and": [{ "var": "vip" }, { "!!": { "var": "verified" } }] }
+            ]})),
+            Ok((
+                String::from(
+                    "(NOT (age < ?) OR (json_extract(data, '$.vip') AND (json_extract(data, '$.verified'))))"
+                ),
+                vec![json!(18)]
+            ))
+        );
+        assert_eq!(
+            sqlite(json!({ "and": [] })),
+            Ok((String::from("FALSE"), vec![]))
+        );
+        assert_eq!(
+            sqlite(
+                json!({ "if": [{ "<": [{ "var": "age" }, 18] }, "minor", { "<": [{ "var": "age" }, 65] }, "adult", "senior"] })
+            ),
+            Ok((
+                String::from("CASE WHEN age < ? THEN ? WHEN age < ? THEN ? ELSE ? END"),
+                vec![
+                    json!(18),
+                    json!("minor"),
+                    json!(65),
+                    json!("adult"),
+                    json!("senior")
+                ]
+            ))
+        );
+        assert_eq!(
+            sqlite(json!({ "if": [{ "var": "vip" }, 1] })),
+            Ok((
+                String::from("CASE WHEN json_extract(data, '$.vip') THEN ? END"),
+                vec![json!(1)]
+            ))
+        );
+    }
+
+    #[test]
+    fn is_in() {
+        assert_eq!(
+            postgres(json!({ "in": [{ "var": "name" }, ["Ann", "Bob"]] })),
+            Ok((
+                String::from("first_name IN ($1, $2)"),
+                vec![json!("Ann"), json!("Bob")]
+            ))
+        );
+        assert_eq!(
+            postgres(json!({ "in": [{ "var": "name" }, []] })),
+            Ok((String::from("FALSE"), vec![]))
+        );
+        assert_eq!(
+            postgres(json!({ "in": ["nn", { "var": "name" }] })),
+            Ok((
+                String::from("strpos(first_name, $1) > 0"),
+                vec![json!("nn")]
+            ))
+        );
+        assert_eq!(
+            sqlite(json!({ "in": ["nn", { "var": "name" }] })),
+            Ok((String::from("instr(first_name, ?) > 0"), vec![json!("nn")]))
+        );
+        assert_eq!(
+            sqlite(json!({ "in": [{ "var": "name" }, [["Ann"]]] })),
+            Err(String::from(
+                "arrays and objects cannot be translated to SQL, except as list of in"
+            ))
+        );
+    }
+
+    #[test]
+    fn arithmetic() {
+        assert_eq!(
+            postgres(
+                json!({ ">": [{ "+": [{ "var": "age" }, 1, { "*": [2, { "var": "age" }] }] }, { "-": [100, { "%": [{ "var": "age" }, 7] }] }] })
+            ),
+            Ok((
+                String::from("(age + $1 + ($2 * age)) > ($3 - (age % $4))"),
+                vec![json!(1), json!(2), json!(100), json!(7)]
+            ))
+        );
+        assert_eq!(
+            postgres(json!({ "/": [{ "var": "age" }, 2] })),
+            Ok((
+                String::from("(CAST(age AS DOUBLE PRECISION) / $1)"),
+                vec![json!(2)]
+            ))
+        );
+        assert_eq!(
+            sqlite(json!({ "-": { "+": { "var": "age" } } })),
+            Ok((String::from("-CAST(age AS REAL)"), vec![]))
+        );
+        assert_eq!(
+            postgres(json!({ "min": [{ "var": "age" }, 5] })),
+            Ok((String::from("LEAST(age, $1)"), vec![json!(5)]))
+        );
+        assert_eq!(
+            sqlite(json!({ "max": [{ "var": "age" }, 5] })),
+            Ok((String::from("MAX(age, ?)"), vec![json!(5)]))
+        );
+        assert_eq!(
+            sqlite(json!({ "cat": [{ "var": "name" }, "!"] })),
+            Ok((String::from("(first_name || ?)"), vec![json!("!")]))
+        );
+    }
+
+    /// Evaluates the rule with SQLite, where the variables `a`, `b` and `c` are columns, and
+    /// with `apply`, and compares the results. SQLite's booleans are the integers 0 and 1.
+    fn assert_same_result(json_logic: Value) {
+        let mut translator = SqlTranslator::new(SqlDialect::Sqlite);
+        translator.map_column("a", "a");
+        translator.map_column("b", "b");
+        translator.map_column("c", "c");
+        let query = translator.translate(&json_logic).unwrap();
+
+        let connection = rusqlite::Connection::open_in_memory().unwrap();
+        let params: Vec<_> = query
+            .params
+            .iter()
+            .map(|param| match param {
+                Value::Number(num) if num.is_i64() => {
+                    rusqlite::types::Value::Integer(num.as_i64().unwrap())
+                }
+                Value::Number(num) => rusqlite::types::Value::Real(num.as_f64().unwrap()),
+                Value::Bool(b) => rusqlite::types::Value::Integer(*b as i64),
+                other => panic!("unexpected parameter {}", other),
+            })
+            .collect();
+        let sql = format!("SELECT {} FROM (SELECT 7 AS a, 4 AS b, 3 AS c)", query.sql);
+        let result: f64 = connection
+            .query_row(&sql, rusqlite::params_from_iter(params), |row| row.get(0))
+            .unwrap();
+
+        let expected = crate::apply(&json_logic, &json!({ "a": 7, "b": 4, "c": 3 })).unwrap();
+        let expected = match expected {
+            Value::Bool(b) => b as i64 as f64,
+            other => other.as_f64().unwrap(),
+        };
+        assert_eq!(
+            result, expected,
+            "{} translated to {}",
+            json_logic, query.sql
+        );
+    }
+
+    #[test]
+    fn nesting() {
+        let a = json!({ "var": "a" });
+        let b = json!({ "var": "b" });
+        let c = json!({ "var": "c" });
+        assert_same_result(json!({ "*": [{ "-": [a, b] }, c] }));
+        assert_same_result(json!({ "-": [a, { "-": [b, c] }] }));
+        assert_same_result(json!({ "-": { "-": [a, b] } }));
+        assert_same_result(json!({ "-": { "-": a } }));
+        assert_same_result(json!({ "%": [{ "-": [a, 1] }, 3] }));
+        assert_same_result(json!({ "%": [a, { "-": [b, 1] }] }));
+        assert_same_result(json!({ "/": [a, { "-": [b, 2] }] }));
+        assert_same_result(json!({ "==": [{ "<": [a, b] }, { "<": [c, b] }] }));
+        assert_same_result(json!({ "!=": [{ "<": [a, b] }, { "<": [c, b] }] }));
+        assert_same_result(json!({ "<": [{ "-": [a, b] }, { "%": [c, 2] }] }));
+        assert_same_result(json!({ "<": [{ "-": [c, b] }, { "-": [b, c] }, { "-": [a, c] }] }));
+        assert_same_result(json!({ "==": [{ "!": { "<": [a, b] } }, false] }));
+        assert_same_result(json!({ "==": [{ "==": [a, b] }, null] }));
+        assert_same_result(json!({ "in": [{ "-": [a, b] }, [1, 3]] }));
+
+        assert_eq!(
+            sqlite(json!({ "-": { "-": [{ "var": "age" }, 1] } })),
+            Ok((String::from("-(age - ?)"), vec![json!(1)]))
+        );
+    }
+
+    #[test]
+    fn missing() {
+        assert_eq!(
+            sqlite(json!({ "missing": ["age", "name"] })),
+            Ok((String::from("(age IS NULL OR first_name IS NULL)"), vec![]))
+        );
+        assert_eq!(
+            sqlite(json!({ "!": { "missing": [["age"]] } })),
+            Ok((String::from("NOT ((age IS NULL))"), vec![]))
+        );
+        assert_eq!(
+            postgres(json!({ "missing_some": [1, ["age", "email"]] })),
+            Ok((
+                String::from("(CASE WHEN age IS NULL THEN 0 ELSE 1 END + CASE WHEN data #>> '{email}' IS NULL THEN 0 ELSE 1 END) < $1"),
+                vec![json!(1)]
+            ))
+        );
+        assert_eq!(
+            sqlite(json!({ "missing": [{ "var": "key" }] })),
+            Err(String::from(
+                "only constant keys of missing can be translated to SQL"
+            ))
+        );
+    }
+
+    #[test]
+    fn unsupported() {
+        assert_eq!(
+            sqlite(json!({ "map": [{ "var": "items" }, { "var": "" }] })),
+            Err(String::from("operation map cannot be translated to SQL"))
+        );
+        assert_eq!(
+            sqlite(json!({ "substr": ["abc", 1] })),
+            Err(String::from("operation substr cannot be translated to SQL"))
+        );
+        assert_eq!(
+            sqlite(json!({ "==": [{ "var": "age" }, [1]] })),
+            Err(String::from(
+                "arrays and objects cannot be translated to SQL, except as list of in"
+            ))
+        );
+    }
+}
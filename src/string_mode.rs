@@ -0,0 +1,123 @@
+#[cfg(feature = "graphemes")]
+use unicode_segmentation::UnicodeSegmentation;
+
+/// How strings are measured and indexed by `substr`, `length`, `pad_start`, `index_of` and the
+/// character access of `var`, like `{"var": "name.0"}`. Rules use `StringMode::Chars`, unless
+/// another mode is set with `with_string_mode`.
+///
+/// # Example
+///
+/// ```
+/// use serde_json::json;
+/// use jsonlogic::{Rule, StringMode};
+///
+/// let json_logic = json!({ "length": "😀" });
+///
+/// let rule = Rule::compile(&json_logic).unwrap();
+/// assert_eq!(rule.apply(&json!(null)), Ok(json!(1)));
+///
+/// let rule = Rule::compile(&json_logic).unwrap().with_string_mode(StringMode::Utf16);
+/// assert_eq!(rule.apply(&json!(null)), Ok(json!(2)));
+/// ```
+///
+/// More modes may be added with features, so matches on it need a wildcard arm.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+#[non_exhaustive]
+pub enum StringMode {
+    /// Unicode scalar values, i.e. Rust's `char`s. `"Grüße"` has 5 of them.
+    #[default]
+    Chars,
+    /// UTF-16 code units, like strings in JavaScript, for parity with json-logic-js. Characters
+    /// outside of the Basic Multilingual Plane, like most emoji, have 2 of them. Substrings that
+    /// split such a character replace its half with U+FFFD, since Rust strings cannot hold lone
+    /// surrogates.
+    Utf16,
+    /// Extended grapheme clusters, i.e. what users perceive as characters. `"y̆"`, a `y` with a
+    /// combining breve, and the family emoji `"👨‍👩‍👧"` are one of them. Requires the `graphemes`
+    /// feature.
+    #[cfg(feature = "graphemes")]
+    Graphemes,
+}
+
+impl StringMode {
+    /// Returns the length of the string.
+    pub(crate) fn length(self, s: &str) -> usize {
+        match self {
+            StringMode::Chars => s.chars().count(),
+            StringMode::Utf16 => s.encode_utf16().count(),
+            #[cfg(feature = "graphemes")]
+            StringMode::Graphemes => s.graphemes(true).count(),
+        }
+    }
+
+    /// Returns the part of the string beginning at index `start`, limited to `len` if given.
+    pub(crate) fn substring(self, s: &str, start: usize, len: Option<usize>) -> String {
+        let len = len.unwrap_or(usize::MAX);
+        match self {
+            StringMode::Chars => s.chars().skip(start).take(len).collect(),
+            StringMode::Utf16 => {
+                let units: Vec<u16> = s.encode_utf16().skip(start).take(len).collect();
+                String::from_utf16_lossy(&units)
+            }
+            #[cfg(feature = "graphemes")]
+            StringMode::Graphemes => s.graphemes(true).skip(start).take(len).collect(),
+        }
+    }
+
+    /// Returns the character at the index, or `None` if the string is not long enough.
+    pub(crate) fn char_at(self, s: &str, index: usize) -> Option<String> {
+        Some(self.substring(s, index, Some(1))).filter(|ch| !ch.is_empty())
+    }
+
+    /// Converts an index in bytes, like the ones returned by `str::find`, into an index in this
+    /// mode.
+    pub(crate) fn index(self, s: &str, byte_index: usize) -> usize {
+        self.length(&s[..byte_index])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FAMILY: &str = "👨\u{200d}👩\u{200d}👧";
+
+    #[test]
+    fn chars() {
+        let mode = StringMode::Chars;
+        assert_eq!(mode.length("Grüße"), 5);
+        assert_eq!(mode.length("😀"), 1);
+        assert_eq!(mode.length("y\u{306}"), 2);
+        assert_eq!(mode.substring("Grüße", 2, Some(2)), "üß");
+        assert_eq!(mode.char_at("Grüße", 4), Some(String::from("e")));
+        assert_eq!(mode.char_at("Grüße", 5), None);
+        assert_eq!(mode.index("Grüße", "Grüß".len()), 4);
+    }
+
+    #[test]
+    fn utf16() {
+        let mode = StringMode::Utf16;
+        assert_eq!(mode.length("Grüße"), 5);
+        assert_eq!(mode.length("😀"), 2);
+        assert_eq!(mode.length(FAMILY), 8);
+        assert_eq!(mode.substring("a😀b", 1, Some(2)), "😀");
+        assert_eq!(mode.substring("a😀b", 2, None), "\u{fffd}b");
+        assert_eq!(mode.char_at("a😀b", 3), Some(String::from("b")));
+        assert_eq!(mode.index("a😀b", "a😀".len()), 3);
+    }
+
+    #[cfg(feature = "graphemes")]
+    #[test]
+    fn graphemes() {
+        let mode = StringMode::Graphemes;
+        assert_eq!(mode.length("Grüße"), 5);
+        assert_eq!(mode.length("y\u{306}"), 1);
+        assert_eq!(mode.length(FAMILY), 1);
+        assert_eq!(mode.substring(&format!("a{}b", FAMILY), 1, Some(1)), FAMILY);
+        assert_eq!(
+            mode.char_at("y\u{306}es", 0),
+            Some(String::from("y\u{306}"))
+        );
+        assert_eq!(mode.index(&format!("{}b", FAMILY), FAMILY.len()), 1);
+    }
+}
@@ -0,0 +1,313 @@
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet};
+use std::str::FromStr;
+
+use crate::expression::Expression;
+use crate::Rule;
+
+/// The type of a template parameter.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum ParamType {
+    /// Any JSON value.
+    Any,
+    Boolean,
+    Number,
+    String,
+    Array,
+    Object,
+}
+
+impl ParamType {
+    /// Returns whether the given value is of this type.
+    pub fn matches(self, value: &Value) -> bool {
+        match self {
+            ParamType::Any => true,
+            ParamType::Boolean => value.is_boolean(),
+            ParamType::Number => value.is_number(),
+            ParamType::String => value.is_string(),
+            ParamType::Array => value.is_array(),
+            ParamType::Object => value.is_object(),
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            ParamType::Any => "any value",
+            ParamType::Boolean => "a boolean",
+            ParamType::Number => "a number",
+            ParamType::String => "a string",
+            ParamType::Array => "an array",
+            ParamType::Object => "an object",
+        }
+    }
+}
+
+impl FromStr for ParamType {
+    type Err = String;
+
+    /// Returns the parameter type matching the given string representation.
+    fn from_str(s: &str) -> Result<ParamType, String> {
+        match s {
+            "any" => Ok(ParamType::Any),
+            "boolean" => Ok(ParamType::Boolean),
+            "number" => Ok(ParamType::Number),
+            "string" => Ok(ParamType::String),
+            "array" => Ok(ParamType::Array),
+            "object" => Ok(ParamType::Object),
+            _ => Err(format!("Unrecognized parameter type {}", s)),
+        }
+    }
+}
+
+/// A JsonLogic rule with typed placeholders of the form `{"param": "<name>"}`, which is turned
+/// into a `Rule` by replacing every placeholder with a parameter value.
+///
+/// The template is validated when it is created: it must be a valid JsonLogic rule, and every
+/// parameter it uses must be declared and vice versa.
+///
+/// # Example
+///
+/// ```
+/// use serde_json::json;
+/// use jsonlogic::{ParamType, RuleTemplate};
+///
+/// let template_json = json!({
+///     "params": { "min_score": "number" },
+///     "rule": { ">=": [{ "var": "score" }, { "param": "min_score" }] }
+/// });
+/// let template = RuleTemplate::from_json(&template_json).unwrap();
+/// assert_eq!(template.params(), vec![("min_score", ParamType::Number)]);
+///
+/// let params = json!({ "min_score": 700 });
+/// let rule = template.instantiate(&params).unwrap();
+/// assert_eq!(rule.apply(&json!({ "score": 720 })), Ok(json!(true)));
+///
+/// assert_eq!(
+///     template.instantiate(&json!({ "min_score": "700" })).map(|_| ()),
+///     Err(String::from("parameter min_score must be a number"))
+/// );
+/// ```
+#[derive(Debug, PartialEq)]
+pub struct RuleTemplate<'a> {
+    params: BTreeMap<String, ParamType>,
+    rule: &'a Value,
+}
+
+impl<'a> RuleTemplate<'a> {
+    /// Creates a template from the given rule and its parameter declarations. Errors if the rule
+    /// is invalid, uses an undeclared parameter or does not use a declared parameter.
+    pub fn new(rule: &'a Value, params: &[(&str, ParamType)]) -> Result<RuleTemplate<'a>, String> {
+        let mut declared = BTreeMap::new();
+        for &(name, param_type) in params.iter() {
+            if declared.insert(name.to_owned(), param_type).is_some() {
+                return Err(format!("parameter {} is declared twice", name));
+            }
+        }
+
+        let mut used = BTreeSet::new();
+        Expression::from_json_with_extension(rule, &mut |operation, value| {
+            if operation != "param" {
+                return None;
+            }
+            Some(
+                Expression::reference_name(operation, value).and_then(|name| {
+                    if !declared.contains_key(name) {
+                        return Err(format!("parameter {} is not declared", name));
+                    }
+                    used.insert(name.to_owned());
+                    Ok(Expression::Constant(&Value::Null))
+                }),
+            )
+        })?;
+
+        if let Some(name) = declared.keys().find(|name| !used.contains(*name)) {
+            return Err(format!("parameter {} is declared but not used", name));
+        }
+
+        Ok(RuleTemplate {
+            params: declared,
+            rule,
+        })
+    }
+
+    /// Loads a template from a JSON document of the form
+    ///
+    /// ```ignore
+    /// {
+    ///     "params": { "<name>": "any" | "boolean" | "number" | "string" | "array" | "object", ... },
+    ///     "rule": <JsonLogic with { "param": "<name>" } placeholders>
+    /// }
+    /// ```
+    pub fn from_json(json: &'a Value) -> Result<RuleTemplate<'a>, String> {
+        let rule = json.get("rule").ok_or("template must have a rule")?;
+        let params = match json.get("params") {
+            Some(Value::Object(params)) => params
+                .iter()
+                .map(|(name, param_type)| match param_type {
+                    Value::String(param_type) => Ok((name.as_str(), param_type.parse()?)),
+                    _ => Err(format!("type of parameter {} must be a string", name)),
+                })
+                .collect::<Result<Vec<_>, String>>()?,
+            None => vec![],
+            Some(_) => return Err(String::from("template params must be an object")),
+        };
+
+        RuleTemplate::new(rule, &params)
+    }
+
+    /// Returns the names and types of all parameters, ordered by name.
+    pub fn params(&self) -> Vec<(&str, ParamType)> {
+        self.params
+            .iter()
+            .map(|(name, param_type)| (name.as_str(), *param_type))
+            .collect()
+    }
+
+    /// Creates a rule by replacing every placeholder with the value of the parameter of the same
+    /// name in the given JSON object. Errors if a parameter is missing, has the wrong type or if
+    /// there are parameters the template does not declare.
+    pub fn instantiate(&self, params: &'a Value) -> Result<Rule<'a>, String> {
+        let values = params
+            .as_object()
+            .ok_or("template parameters must be an object")?;
+
+        if let Some(name) = values.keys().find(|name| !self.params.contains_key(*name)) {
+            return Err(format!("unknown parameter {}", name));
+        }
+        for (name, param_type) in self.params.iter() {
+            match values.get(name) {
+                None => return Err(format!("missing parameter {}", name)),
+                Some(value) if !param_type.matches(value) => {
+                    return Err(format!(
+                        "parameter {} must be {}",
+                        name,
+                        param_type.description()
+                    ))
+                }
+                Some(_) => {}
+            }
+        }
+
+        Expression::from_json_with_extension(self.rule, &mut |operation, value| {
+            if operation != "param" {
+                return None;
+            }
+            // The parameters were validated in `new`, so every placeholder has a value.
+            Some(
+                Expression::reference_name(operation, value)
+                    .map(|name| Expression::Constant(&values[name])),
+            )
+        })
+        .map(Rule::from_expression)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn template_json() -> Value {
+        json!({
+            "params": { "min": "number", "product": "string", "flags": "any" },
+            "rule": { "and": [
+                { ">=": [{ "var": "score" }, { "param": "min" }] },
+                { "==": [{ "var": "product" }, { "param": ["product"] }] },
+                { "param": "flags" }
+            ]}
+        })
+    }
+
+    #[test]
+    fn instantiate() {
+        let json = template_json();
+        let template = RuleTemplate::from_json(&json).unwrap();
+        assert_eq!(
+            template.params(),
+            vec![
+                ("flags", ParamType::Any),
+                ("min", ParamType::Number),
+                ("product", ParamType::String)
+            ]
+        );
+
+        let params = json!({ "min": 700, "product": "loan", "flags": { "a": 1 } });
+        let rule = template.instantiate(&params).unwrap();
+        assert_eq!(
+            rule.apply(&json!({ "score": 720, "product": "loan" })),
+            Ok(json!({ "a": 1 }))
+        );
+        assert_eq!(
+            rule.apply(&json!({ "score": 720, "product": "card" })),
+            Ok(json!(false))
+        );
+
+        let params = json!({ "min": 800, "product": "card", "flags": true });
+        let rule = template.instantiate(&params).unwrap();
+        assert_eq!(
+            rule.apply(&json!({ "score": 820, "product": "card" })),
+            Ok(json!(true))
+        );
+    }
+
+    #[test]
+    fn instantiate_errors() {
+        let json = template_json();
+        let template = RuleTemplate::from_json(&json).unwrap();
+
+        assert_eq!(
+            template.instantiate(&json!({ "min": 1, "product": "a" })),
+            Err(String::from("missing parameter flags"))
+        );
+        assert_eq!(
+            template.instantiate(&json!({ "min": 1, "product": "a", "flags": 1, "max": 2 })),
+            Err(String::from("unknown parameter max"))
+        );
+        assert_eq!(
+            template.instantiate(&json!({ "min": 1, "product": 2, "flags": 1 })),
+            Err(String::from("parameter product must be a string"))
+        );
+        assert_eq!(
+            template.instantiate(&json!([1])),
+            Err(String::from("template parameters must be an object"))
+        );
+    }
+
+    #[test]
+    fn definition_errors() {
+        assert_eq!(
+            RuleTemplate::from_json(&json!({ "params": { "a": "number" } })),
+            Err(String::from("template must have a rule"))
+        );
+        assert_eq!(
+            RuleTemplate::from_json(&json!({ "params": { "a": "int" }, "rule": { "param": "a" } })),
+            Err(String::from("Unrecognized parameter type int"))
+        );
+        assert_eq!(
+            RuleTemplate::from_json(&json!({ "params": { "a": 1 }, "rule": { "param": "a" } })),
+            Err(String::from("type of parameter a must be a string"))
+        );
+        assert_eq!(
+            RuleTemplate::from_json(&json!({ "params": {}, "rule": { "param": "a" } })),
+            Err(String::from("parameter a is not declared"))
+        );
+        assert_eq!(
+            RuleTemplate::from_json(&json!({ "params": { "a": "any" }, "rule": true })),
+            Err(String::from("parameter a is declared but not used"))
+        );
+        assert_eq!(
+            RuleTemplate::from_json(&json!({ "rule": { "param": { "var": "a" } } })),
+            Err(String::from("param reference must be a constant string"))
+        );
+        assert_eq!(
+            RuleTemplate::from_json(&json!({ "rule": { "foo": 1 } })),
+            Err(String::from("Unrecognized operation foo"))
+        );
+
+        let rule = json!({ "param": "a" });
+        assert_eq!(
+            RuleTemplate::new(&rule, &[("a", ParamType::Any), ("a", ParamType::Number)]),
+            Err(String::from("parameter a is declared twice"))
+        );
+    }
+}
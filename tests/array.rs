@@ -57,7 +57,7 @@ fn map() {
 
     assert_eq!(
         apply(&rule, &json!({ "integers": [1, 2, 3, 4, 5] })),
-        Ok(json!([2.0, 4.0, 6.0, 8.0, 10.0]))
+        Ok(json!([2, 4, 6, 8, 10]))
     );
     assert_eq!(
         apply(&rule, &json!({ "_integers": [1, 2, 3, 4, 5] })),
@@ -99,7 +99,7 @@ fn reduce() {
     ]});
     assert_eq!(
         apply(&rule, &json!({ "integers": [1, 2, 3, 4, 5] })),
-        Ok(json!(15.0))
+        Ok(json!(15))
     );
 
     // Return initial value if data is not an array.
@@ -266,3 +266,104 @@ fn none() {
     let rule = json!({ "none": ["bbb", { "===": [{ "var": "" }, "a"] }] });
     assert_eq!(apply(&rule, &json!(null)), Ok(json!(true)));
 }
+
+// sort, reverse and slice
+#[test]
+fn top_scores() {
+    let rule = json!({"slice": [
+        {"reverse": {"sort": [{"var": "players"}, {"var": "score"}]}},
+        0, 2
+    ]});
+    assert_eq!(
+        apply(
+            &rule,
+            &json!({"players": [
+                {"name": "a", "score": 70},
+                {"name": "b", "score": 95},
+                {"name": "c", "score": 80}
+            ]})
+        ),
+        Ok(json!([{"name": "b", "score": 95}, {"name": "c", "score": 80}]))
+    );
+    assert_eq!(
+        apply(&json!({"sort": [[3, "b", 1, "a", null]]}), &Value::Null),
+        Ok(json!([null, 1, 3, "a", "b"]))
+    );
+}
+
+// length, unique, flatten and count
+#[test]
+fn tags() {
+    let data = json!({"posts": [
+        {"tags": ["rust", "json"]},
+        {"tags": ["json"]},
+        {"tags": []}
+    ]});
+    assert_eq!(
+        apply(
+            &json!({"unique": {"flatten": {"map": [{"var": "posts"}, {"var": "tags"}]}}}),
+            &data
+        ),
+        Ok(json!(["rust", "json"]))
+    );
+    assert_eq!(
+        apply(
+            &json!({"count": [{"var": "posts"}, {"length": {"var": "tags"}}]}),
+            &data
+        ),
+        Ok(json!(2))
+    );
+}
+
+// find, sum and group_by
+#[test]
+fn orders() {
+    let data = json!({"orders": [
+        {"id": 1, "status": "open", "total": 20},
+        {"id": 2, "status": "paid", "total": 15},
+        {"id": 3, "status": "open", "total": 7}
+    ]});
+    assert_eq!(
+        apply(
+            &json!({"find": [{"var": "orders"}, {"==": [{"var": "status"}, "paid"]}]}),
+            &data
+        ),
+        Ok(json!({"id": 2, "status": "paid", "total": 15}))
+    );
+    assert_eq!(
+        apply(
+            &json!({"sum": {"map": [{"var": "orders"}, {"var": "total"}]}}),
+            &data
+        ),
+        Ok(json!(42))
+    );
+    assert_eq!(
+        apply(
+            &json!({"group_by": [{"var": "orders"}, {"var": "status"}]}),
+            &data
+        ),
+        Ok(json!({
+            "open": [
+                {"id": 1, "status": "open", "total": 20},
+                {"id": 3, "status": "open", "total": 7}
+            ],
+            "paid": [{"id": 2, "status": "paid", "total": 15}]
+        }))
+    );
+}
+
+// zip and range
+#[test]
+fn zip_and_range() {
+    assert_eq!(
+        apply(
+            &json!({"zip": [{"range": [1, 4]}, {"var": "names"}]}),
+            &json!({"names": ["a", "b", "c"]})
+        ),
+        Ok(json!([[1, "a"], [2, "b"], [3, "c"]]))
+    );
+    assert_eq!(
+        apply(&json!({"range": [0, 10, 5]}), &Value::Null),
+        Ok(json!([0, 5]))
+    );
+}
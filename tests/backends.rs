@@ -0,0 +1,85 @@
+//! Runs the other test suites against `BytecodeRule` and `ClosureRule` by replacing
+//! `jsonlogic::apply` with a function that applies every rule with all backends and asserts that
+//! the results are equal.
+
+use serde_json::Value;
+
+pub fn apply(json_logic: &Value, data: &Value) -> Result<Value, ::jsonlogic::Error> {
+    let expected = ::jsonlogic::Rule::compile(json_logic).map(|rule| rule.apply(data));
+    let bytecode = ::jsonlogic::BytecodeRule::compile(json_logic).map(|rule| rule.apply(data));
+    assert_eq!(
+        bytecode, expected,
+        "bytecode result of {} with data {}",
+        json_logic, data
+    );
+    let closure = ::jsonlogic::ClosureRule::compile(json_logic).map(|rule| rule.apply(data));
+    assert_eq!(
+        closure, expected,
+        "closure result of {} with data {}",
+        json_logic, data
+    );
+    ::jsonlogic::apply(json_logic, data)
+}
+
+macro_rules! differential {
+    ($name:ident, $file:expr) => {
+        mod $name {
+            /// Shadows the `jsonlogic` crate in the included tests.
+            mod jsonlogic {
+                pub use super::super::apply;
+                #[allow(unused_imports)]
+                pub use ::jsonlogic::{parse_condition, Error};
+            }
+
+            include!($file);
+        }
+    };
+}
+
+differential!(array, "array.rs");
+differential!(bindings, "bindings.rs");
+differential!(condition_parser, "condition_parser.rs");
+differential!(data_access, "data_access.rs");
+#[cfg(feature = "datetime")]
+differential!(datetime, "datetime.rs");
+differential!(errors, "errors.rs");
+differential!(logic_and_boolean, "logic_and_boolean.rs");
+#[cfg(feature = "regex")]
+differential!(matching, "matching.rs");
+differential!(misc, "misc.rs");
+differential!(non_finite, "non_finite.rs");
+differential!(numeric, "numeric.rs");
+differential!(object, "object.rs");
+differential!(string, "string.rs");
+differential!(types, "types.rs");
+differential!(values, "values.rs");
+
+#[test]
+fn large_integer_comparisons() {
+    use serde_json::json;
+
+    // 2^53 + 1 and 2^53 are equal as floats, but not as integers.
+    let (a, b) = (json!(9007199254740993u64), json!(9007199254740992u64));
+    for operator in ["<", "<=", ">", ">=", "==", "!="].iter() {
+        for (x, y) in [(&a, &b), (&b, &a), (&a, &a)].iter() {
+            let data = json!({ "x": x, "y": y });
+            let expected = json!(match *operator {
+                "<" => x.as_u64() < y.as_u64(),
+                "<=" => x.as_u64() <= y.as_u64(),
+                ">" => x.as_u64() > y.as_u64(),
+                ">=" => x.as_u64() >= y.as_u64(),
+                "==" => x.as_u64() == y.as_u64(),
+                _ => x.as_u64() != y.as_u64(),
+            });
+            for rule in [
+                json!({ *operator: [{ "var": "x" }, { "var": "y" }] }),
+                json!({ *operator: [{ "var": "x" }, y] }),
+                json!({ *operator: [x, { "var": "y" }] }),
+            ]
+            .iter()
+            {
+                assert_eq!(apply(rule, &data), Ok(expected.clone()), "{}", rule);
+            }
+        }
+    }
+}
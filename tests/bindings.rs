@@ -0,0 +1,58 @@
+use jsonlogic::apply;
+use serde_json::{json, Value};
+
+#[test]
+fn let_and_ref() {
+    // The discount is computed once and used in three comparisons.
+    let rule = json!({"let": [
+        "discount", {"*": [{"var": "price"}, {"var": "rate"}]},
+        {"if": [
+            {">": [{"ref": "discount"}, 100]}, "large",
+            {">": [{"ref": "discount"}, 10]}, "medium",
+            {">": [{"ref": "discount"}, 0]}, "small",
+            "none"
+        ]}
+    ]});
+    assert_eq!(
+        apply(&rule, &json!({"price": 2000, "rate": 0.1})),
+        Ok(json!("large"))
+    );
+    assert_eq!(
+        apply(&rule, &json!({"price": 200, "rate": 0.1})),
+        Ok(json!("medium"))
+    );
+    assert_eq!(
+        apply(&rule, &json!({"price": 20, "rate": 0})),
+        Ok(json!("none"))
+    );
+
+    // A bound name does not hide the key of the data with the same name.
+    let rule = json!({"let": [
+        "price", {"+": [{"var": "price"}, 1]},
+        {"cat": [{"var": "price"}, " -> ", {"ref": "price"}]}
+    ]});
+    assert_eq!(apply(&rule, &json!({"price": 1})), Ok(json!("1 -> 2")));
+}
+
+#[test]
+fn nested_scopes() {
+    let rule = json!({"let": [
+        "minimum", {"var": "minimum"},
+        {"filter": [{"var": "scores"}, {">=": [{"var": ""}, {"ref": "minimum"}]}]}
+    ]});
+    assert_eq!(
+        apply(&rule, &json!({"minimum": 50, "scores": [30, 50, 70]})),
+        Ok(json!([50, 70]))
+    );
+
+    let rule = json!({"let": [
+        "fallback", "n/a",
+        {"try": [{"throw": "missing"}, {"ref": "fallback"}]}
+    ]});
+    assert_eq!(apply(&rule, &Value::Null), Ok(json!("n/a")));
+
+    assert_eq!(
+        apply(&json!({"ref": ["unbound", 0]}), &Value::Null),
+        Ok(json!(0))
+    );
+}
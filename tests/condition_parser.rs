@@ -0,0 +1,101 @@
+use jsonlogic::{apply, parse_condition};
+use serde_json::{json, Value};
+
+/// Checks that the parsed condition evaluates like the hand-written rule for all data.
+fn assert_equivalent(condition: &str, json_logic: Value, data: &[Value]) {
+    let parsed = parse_condition(condition).unwrap();
+    for data in data.iter() {
+        assert_eq!(
+            apply(&parsed, data),
+            apply(&json_logic, data),
+            "{} with data {}",
+            condition,
+            data
+        );
+    }
+}
+
+fn customers() -> Vec<Value> {
+    vec![
+        json!({ "age": 17, "country": "US", "name": "Ann", "tags": ["beta"], "score": 10 }),
+        json!({ "age": 18, "country": "CA", "name": "Bob", "tags": [], "score": 55.5 }),
+        json!({ "age": 40, "country": "DE", "name": "Eve", "tags": ["vip", "beta"] }),
+        json!({ "age": "21", "country": null, "score": -3 }),
+        json!({}),
+    ]
+}
+
+#[test]
+fn comparisons() {
+    assert_equivalent(
+        "age >= 18 AND country IN ('US', 'CA')",
+        json!({ "and": [
+            { ">=": [{ "var": "age" }, 18] },
+            { "in": [{ "var": "country" }, ["US", "CA"]] }
+        ]}),
+        &customers(),
+    );
+    assert_equivalent(
+        "name = 'Bob' OR name <> 'Eve' AND score < 20",
+        json!({ "or": [
+            { "==": [{ "var": "name" }, "Bob"] },
+            { "and": [
+                { "!=": [{ "var": "name" }, "Eve"] },
+                { "<": [{ "var": "score" }, 20] }
+            ]}
+        ]}),
+        &customers(),
+    );
+    assert_equivalent(
+        "score BETWEEN 0 AND 50 OR country IS NULL",
+        json!({ "or": [
+            { "<=": [0, { "var": "score" }, 50] },
+            { "==": [{ "var": "country" }, null] }
+        ]}),
+        &customers(),
+    );
+}
+
+#[test]
+fn negations() {
+    assert_equivalent(
+        "NOT (age > 30) AND country NOT IN ('DE') AND 'vip' NOT IN tags",
+        json!({ "and": [
+            { "!": { ">": [{ "var": "age" }, 30] } },
+            { "!": { "in": [{ "var": "country" }, ["DE"]] } },
+            { "!": { "in": ["vip", { "var": "tags" }] } }
+        ]}),
+        &customers(),
+    );
+    assert_equivalent(
+        "name IS NOT NULL AND score NOT BETWEEN 0 AND 50",
+        json!({ "and": [
+            { "!=": [{ "var": "name" }, null] },
+            { "!": { "<=": [0, { "var": "score" }, 50] } }
+        ]}),
+        &customers(),
+    );
+}
+
+#[test]
+fn arithmetic_and_functions() {
+    assert_equivalent(
+        "age * 2 + score % 7 - 1 > max(score, 30) / 2",
+        json!({ ">": [
+            { "-": [
+                { "+": [{ "*": [{ "var": "age" }, 2] }, { "%": [{ "var": "score" }, 7] }] },
+                1
+            ]},
+            { "/": [{ "max": [{ "var": "score" }, 30] }, 2] }
+        ]}),
+        &customers(),
+    );
+    assert_equivalent(
+        "name || '!' = 'Ann!' OR substr(country, 0, 1) = 'D'",
+        json!({ "or": [
+            { "==": [{ "cat": [{ "var": "name" }, "!"] }, "Ann!"] },
+            { "==": [{ "substr": [{ "var": "country" }, 0, 1] }, "D"] }
+        ]}),
+        &customers(),
+    );
+}
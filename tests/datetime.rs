@@ -0,0 +1,166 @@
+use jsonlogic::apply;
+use serde_json::{json, Value};
+use std::time::{Duration, SystemTime};
+
+// date_diff
+#[test]
+fn account_age() {
+    let rule = json!({">": [{"date_diff": [{"var": "today"}, {"var": "created"}, "days"]}, 30]});
+    assert_eq!(
+        apply(
+            &rule,
+            &json!({"today": "2024-03-31", "created": "2024-02-28T12:00:00Z"})
+        ),
+        Ok(json!(true))
+    );
+    assert_eq!(
+        apply(
+            &rule,
+            &json!({"today": "2024-03-31", "created": "2024-03-01T12:00:00Z"})
+        ),
+        Ok(json!(false))
+    );
+    assert_eq!(
+        apply(&rule, &json!({"today": "2024-03-31"})),
+        Ok(json!(false))
+    );
+}
+
+// date_add, date_trunc and date_before
+#[test]
+fn end_of_quarter() {
+    // The end of the quarter is the start of the month after the quarter.
+    let end_of_quarter = json!({"date_add": [
+        {"date_trunc": [{"var": "date"}, "month"]},
+        {"-": [3, {"%": [{"-": [{"date_part": [{"var": "date"}, "month"]}, 1]}, 3]}]},
+        "months"
+    ]});
+    assert_eq!(
+        apply(&end_of_quarter, &json!({"date": "2024-05-15T08:00:00Z"})),
+        Ok(json!("2024-07-01T00:00:00Z"))
+    );
+
+    let rule = json!({"date_before": [{"var": "due"}, end_of_quarter]});
+    assert_eq!(
+        apply(
+            &rule,
+            &json!({"date": "2024-05-15T08:00:00Z", "due": "2024-06-30T23:59:59Z"})
+        ),
+        Ok(json!(true))
+    );
+    assert_eq!(
+        apply(
+            &rule,
+            &json!({"date": "2024-05-15T08:00:00Z", "due": "2024-07-01T00:00:00Z"})
+        ),
+        Ok(json!(false))
+    );
+}
+
+// date_part
+#[test]
+fn business_hours() {
+    let rule = json!({"and": [
+        {"<=": [{"date_part": [{"var": "time"}, "weekday", "-05:00"]}, 5]},
+        {"<=": [9, {"date_part": [{"var": "time"}, "hour", "-05:00"]}, 16]}
+    ]});
+    assert_eq!(
+        apply(&rule, &json!({"time": "2024-03-29T14:00:00Z"})),
+        Ok(json!(true))
+    );
+    assert_eq!(
+        apply(&rule, &json!({"time": "2024-03-29T22:00:00Z"})),
+        Ok(json!(false))
+    );
+    assert_eq!(
+        apply(&rule, &json!({"time": "2024-03-30T14:00:00Z"})),
+        Ok(json!(false))
+    );
+}
+
+// datetime
+#[test]
+fn datetime() {
+    assert_eq!(
+        apply(&json!({"datetime": 0}), &Value::Null),
+        Ok(json!("1970-01-01T00:00:00Z"))
+    );
+    assert_eq!(
+        apply(&json!({"datetime": "tomorrow"}), &Value::Null),
+        Ok(Value::Null)
+    );
+}
+
+#[test]
+fn calendar_edges() {
+    // The last millisecond and the first day that can be represented.
+    let (max, min) = (json!(8_210_266_876_799_999i64), json!("-262143-01-01"));
+    let cases = [
+        (json!({"datetime": [min, "America/New_York"]}), Value::Null),
+        (json!({"datetime": [min, "-05:00"]}), Value::Null),
+        (
+            json!({"datetime": [min, "+01:00"]}),
+            json!("-262143-01-01T01:00:00+01:00"),
+        ),
+        (
+            json!({"datetime": [max, "Pacific/Kiritimati"]}),
+            Value::Null,
+        ),
+        (
+            json!({"datetime": [max, "-10:00"]}),
+            json!("+262142-12-31T13:59:59.999-10:00"),
+        ),
+        (
+            json!({"date_trunc": [max, "day", "Pacific/Kiritimati"]}),
+            Value::Null,
+        ),
+        (
+            json!({"date_trunc": [max, "day", "-10:00"]}),
+            json!("+262142-12-31T00:00:00-10:00"),
+        ),
+        (json!({"date_trunc": [min, "week"]}), Value::Null),
+        (
+            json!({"date_trunc": [min, "year", "America/New_York"]}),
+            Value::Null,
+        ),
+        (
+            json!({"date_part": [max, "year", "Pacific/Kiritimati"]}),
+            Value::Null,
+        ),
+        (
+            json!({"date_diff": [max, {"datetime": ["2024-01-01", "+14:00"]}, "months"]}),
+            Value::Null,
+        ),
+        (json!({"date_add": [max, 1, "millisecond"]}), Value::Null),
+        (json!({"date_add": [min, -1, "month"]}), Value::Null),
+    ];
+    for (rule, expected) in cases.iter() {
+        assert_eq!(apply(rule, &Value::Null), Ok(expected.clone()), "{}", rule);
+    }
+}
+
+// now
+#[test]
+fn clock() {
+    let json_logic = json!({"date_add": [{"now": []}, 1, "day"]});
+    let clock = || SystemTime::UNIX_EPOCH + Duration::from_secs(1_711_843_200);
+    let expected = Ok(json!("2024-04-01T00:00:00Z"));
+    assert_eq!(
+        ::jsonlogic::Rule::compile(&json_logic)
+            .unwrap()
+            .apply_with_clock(&Value::Null, &clock),
+        expected
+    );
+    assert_eq!(
+        ::jsonlogic::BytecodeRule::compile(&json_logic)
+            .unwrap()
+            .apply_with_clock(&Value::Null, &clock),
+        expected
+    );
+    assert_eq!(
+        ::jsonlogic::ClosureRule::compile(&json_logic)
+            .unwrap()
+            .apply_with_clock(&Value::Null, &clock),
+        expected
+    );
+}
@@ -0,0 +1,93 @@
+use jsonlogic::{apply, Error};
+use serde_json::{json, Value};
+
+#[test]
+fn throw() {
+    let rule = json!({"if": [
+        {"missing": "email"},
+        {"throw": "MissingEmail"},
+        {"var": "email"}
+    ]});
+    assert_eq!(
+        apply(&rule, &json!({"email": "jane@example.com"})),
+        Ok(json!("jane@example.com"))
+    );
+    assert_eq!(
+        apply(&rule, &json!({})),
+        Err(Error::Thrown(json!("MissingEmail")))
+    );
+
+    let rule = json!({"throw": {"object": ["code", 422, "field", {"var": "field"}]}});
+    assert_eq!(
+        apply(&rule, &json!({"field": "age"})),
+        Err(Error::Thrown(json!({"code": 422, "field": "age"})))
+    );
+}
+
+#[test]
+fn try_alternatives() {
+    let parse = json!({"if": [
+        {"is_number": {"var": "amount"}}, {"var": "amount"},
+        {"throw": "NotANumber"}
+    ]});
+    let rule = json!({"try": [parse, {"to_number": {"var": "data.amount"}}, 0]});
+    assert_eq!(apply(&rule, &json!({"amount": 5})), Ok(json!(5)));
+    assert_eq!(apply(&rule, &json!({"amount": "7"})), Ok(json!(7)));
+
+    let rule = json!({"try": [
+        {"map": [{"var": "items"}, {"if": [
+            {">=": [{"var": ""}, 0]}, {"var": ""}, {"throw": {"var": ""}}
+        ]}]},
+        {"cat": ["negative item ", {"var": "error"}]}
+    ]});
+    assert_eq!(apply(&rule, &json!({"items": [1, 2]})), Ok(json!([1, 2])));
+    assert_eq!(
+        apply(&rule, &json!({"items": [1, -2, -3]})),
+        Ok(json!("negative item -2"))
+    );
+
+    assert_eq!(
+        apply(&json!({"try": [{"throw": 1}, {"throw": 2}]}), &Value::Null),
+        Err(Error::Thrown(json!(2)))
+    );
+}
+
+#[test]
+fn arguments_are_evaluated_in_order() {
+    // Operators evaluate all of their arguments from left to right, even those they ignore.
+    let rules = [
+        json!({"!": [false, {"throw": "x"}]}),
+        json!({"upper": ["a", {"throw": "x"}]}),
+        json!({"abs": [-1, {"throw": "x"}]}),
+        json!({"throw": [{"throw": "x"}, "y"]}),
+        json!({"reduce": [{"throw": "x"}, {"var": "current"}, {"throw": "y"}]}),
+        json!({"reduce": [5, {"throw": "y"}, {"throw": "x"}]}),
+    ];
+    for rule in rules.iter() {
+        assert_eq!(
+            apply(rule, &Value::Null),
+            Err(Error::Thrown(json!("x"))),
+            "{}",
+            rule
+        );
+    }
+
+    // `if`, `and`, `or` and the default of `var` only evaluate the arguments they need.
+    assert_eq!(
+        apply(&json!({"and": [false, {"throw": "x"}]}), &Value::Null),
+        Ok(json!(false))
+    );
+    assert_eq!(
+        apply(&json!({"if": [true, 1, {"throw": "x"}]}), &Value::Null),
+        Ok(json!(1))
+    );
+    assert_eq!(
+        apply(&json!({"var": ["a", {"throw": "x"}]}), &json!({"a": 1})),
+        Ok(json!(1))
+    );
+    // The logic of `map` is not evaluated for an empty array.
+    assert_eq!(
+        apply(&json!({"map": [[], {"throw": "x"}]}), &Value::Null),
+        Ok(json!([]))
+    );
+}
@@ -0,0 +1,45 @@
+use jsonlogic::apply;
+use serde_json::{json, Value};
+
+// match
+#[test]
+fn match_operator() {
+    let rule = json!({"match": [{"var": "zip"}, "^\\d{5}(-\\d{4})?$"]});
+    assert_eq!(apply(&rule, &json!({"zip": "12345"})), Ok(json!(true)));
+    assert_eq!(apply(&rule, &json!({"zip": "12345-6789"})), Ok(json!(true)));
+    assert_eq!(apply(&rule, &json!({"zip": 12345})), Ok(json!(true)));
+    assert_eq!(apply(&rule, &json!({"zip": "1234"})), Ok(json!(false)));
+
+    assert_eq!(
+        apply(
+            &json!({"filter": [{"var": "names"}, {"match": [{"var": ""}, "^J"]}]}),
+            &json!({"names": ["Jane", "Bob", "John"]})
+        ),
+        Ok(json!(["Jane", "John"]))
+    );
+}
+
+// captures
+#[test]
+fn captures() {
+    let rule = json!({"captures": [{"var": "version"}, "^v?(\\d+)\\.(\\d+)"]});
+    assert_eq!(
+        apply(&rule, &json!({"version": "v1.42.0"})),
+        Ok(json!(["v1.42", "1", "42"]))
+    );
+    assert_eq!(apply(&rule, &json!({"version": "latest"})), Ok(Value::Null));
+}
+
+#[test]
+fn patterns() {
+    let rule = json!({"match": [{"var": "value"}, {"var": "pattern"}]});
+    assert_eq!(
+        apply(&rule, &json!({"value": "abc", "pattern": "^a"})),
+        Ok(json!(true))
+    );
+    assert_eq!(
+        apply(&rule, &json!({"value": "abc", "pattern": "["})),
+        Ok(Value::Null)
+    );
+    assert!(apply(&json!({"match": ["abc", "["]}), &Value::Null).is_err());
+}
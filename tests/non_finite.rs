@@ -0,0 +1,325 @@
+use jsonlogic::apply;
+use serde_json::{json, Value};
+
+#[test]
+fn non_finite_results() {
+    // NaN and ±Infinity cannot be represented in JSON, so the result is null.
+    let rules = [
+        json!({"+": [1e308, 1e308]}),
+        json!({"+": [-1e308, -1e308]}),
+        json!({"+": ["Infinity", 1]}),
+        json!({"+": ["NaN"]}),
+        json!({"-": [-1e308, 1e308]}),
+        json!({"-": ["Infinity"]}),
+        json!({"*": [1e308, 10]}),
+        json!({"*": ["-Infinity", 0]}),
+        json!({"/": [1e308, 1e-308]}),
+        json!({"%": ["Infinity", 2]}),
+        json!({"min": ["-Infinity", 1]}),
+        json!({"max": ["Infinity", 1]}),
+        json!({"max": ["NaN", 1]}),
+    ];
+    for rule in rules.iter() {
+        assert_eq!(apply(rule, &Value::Null), Ok(Value::Null), "{}", rule);
+    }
+
+    let data = json!({ "a": 1e308, "b": "Infinity" });
+    assert_eq!(
+        apply(&json!({"+": [{"var": "a"}, {"var": "a"}]}), &data),
+        Ok(Value::Null)
+    );
+    assert_eq!(
+        apply(&json!({"*": [{"var": "b"}, 2]}), &data),
+        Ok(Value::Null)
+    );
+}
+
+#[test]
+fn non_finite_operands() {
+    assert_eq!(
+        apply(&json!({"!!": {"+": [1e308, 1e308]}}), &Value::Null),
+        Ok(json!(false))
+    );
+    assert_eq!(
+        apply(&json!({"==": ["NaN", "NaN"]}), &Value::Null),
+        Ok(json!(true))
+    );
+    assert_eq!(
+        apply(&json!({"==": ["NaN", 1]}), &Value::Null),
+        Ok(json!(false))
+    );
+    assert_eq!(
+        apply(&json!({"==": [true, "NaN"]}), &Value::Null),
+        Ok(json!(false))
+    );
+    assert_eq!(
+        apply(&json!({"<": ["Infinity", 1]}), &Value::Null),
+        Ok(json!(false))
+    );
+    assert_eq!(
+        apply(&json!({">": [1, "NaN"]}), &Value::Null),
+        Ok(json!(false))
+    );
+    assert_eq!(
+        apply(&json!({"<": [1, "NaN"]}), &Value::Null),
+        Ok(json!(false))
+    );
+    assert_eq!(
+        apply(&json!({">=": [1, "NaN"]}), &Value::Null),
+        Ok(json!(false))
+    );
+    assert_eq!(
+        apply(
+            &json!({"substr": ["jsonlogic", -1e300, -1e300]}),
+            &Value::Null
+        ),
+        Ok(json!(""))
+    );
+    assert_eq!(
+        apply(&json!({"substr": ["jsonlogic", 1e300]}), &Value::Null),
+        Ok(json!(""))
+    );
+}
+
+/// A xorshift generator, so the generated rules are the same for every run.
+struct Random(u64);
+
+impl Random {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+}
+
+const OPERATORS: &[&str] = &[
+    "==",
+    "===",
+    "!=",
+    "!==",
+    "var",
+    "!",
+    "!!",
+    "if",
+    "?:",
+    "or",
+    "and",
+    "<",
+    "<=",
+    ">",
+    ">=",
+    "missing",
+    "missing_some",
+    "min",
+    "max",
+    "+",
+    "-",
+    "*",
+    "/",
+    "%",
+    "in",
+    "cat",
+    "substr",
+    "merge",
+    "map",
+    "filter",
+    "reduce",
+    "all",
+    "some",
+    "none",
+    "upper",
+    "lower",
+    "trim",
+    "split",
+    "join",
+    "replace",
+    "starts_with",
+    "ends_with",
+    "length",
+    "pad_start",
+    "index_of",
+    "sort",
+    "unique",
+    "flatten",
+    "slice",
+    "find",
+    "count",
+    "sum",
+    "group_by",
+    "zip",
+    "reverse",
+    "range",
+    "keys",
+    "values",
+    "entries",
+    "get",
+    "pick",
+    "omit",
+    "merge_objects",
+    "object",
+    "abs",
+    "floor",
+    "ceil",
+    "round",
+    "trunc",
+    "pow",
+    "sqrt",
+    "ln",
+    "exp",
+    "clamp",
+    "typeof",
+    "is_number",
+    "is_string",
+    "is_array",
+    "to_number",
+    "parse_float",
+    "to_string",
+    "to_bool",
+    "throw",
+    "try",
+    "let",
+    "ref",
+    #[cfg(feature = "datetime")]
+    "datetime",
+    #[cfg(feature = "datetime")]
+    "date_add",
+    #[cfg(feature = "datetime")]
+    "date_diff",
+    #[cfg(feature = "datetime")]
+    "date_trunc",
+    #[cfg(feature = "datetime")]
+    "date_part",
+    #[cfg(feature = "datetime")]
+    "date_before",
+    #[cfg(feature = "datetime")]
+    "date_after",
+];
+
+fn extreme_value(random: &mut Random) -> Value {
+    let values = [
+        json!(0),
+        json!(-0.0),
+        json!(1),
+        json!(-1),
+        json!(0.5),
+        json!(1e308),
+        json!(-1e308),
+        json!(f64::MIN_POSITIVE),
+        json!(5e-324),
+        json!(u64::MAX),
+        json!(i64::MIN),
+        json!(i64::MAX),
+        json!(9_007_199_254_740_993u64),
+        json!("Infinity"),
+        json!("-Infinity"),
+        json!("NaN"),
+        json!("1e308"),
+        json!("1e309"),
+        json!("18446744073709551616"),
+        json!(""),
+        json!("a"),
+        json!("a.b"),
+        json!("arr.1"),
+        Value::Null,
+        json!(true),
+        json!(false),
+        json!([]),
+        json!([1e308]),
+        json!(["NaN"]),
+        json!({"x": 1}),
+    ];
+    random.pick(&values).clone()
+}
+
+fn random_rule(random: &mut Random, depth: usize) -> Value {
+    match random.below(if depth == 0 { 2 } else { 5 }) {
+        0 => extreme_value(random),
+        1 => json!({"var": *random.pick(&["a", "b", "c", "arr", "", "missing", "arr.0"])}),
+        _ => {
+            let operator = *random.pick(OPERATORS);
+            let args: Vec<Value> = (0..random.below(4))
+                .map(|_| random_rule(random, depth - 1))
+                .collect();
+            json!({ operator: args })
+        }
+    }
+}
+
+#[test]
+fn random_rules_do_not_panic() {
+    let mut random = Random(0x2545_f491_4f6c_dd1d);
+    for _ in 0..5000 {
+        let data = json!({
+            "a": extreme_value(&mut random),
+            "b": extreme_value(&mut random),
+            "c": extreme_value(&mut random),
+            "arr": [extreme_value(&mut random), extreme_value(&mut random)],
+        });
+        let rule = random_rule(&mut random, 4);
+        let _ = apply(&rule, &data);
+    }
+}
+
+/// Returns a timestamp at the edges of the calendar, a time zone or a unit.
+#[cfg(feature = "datetime")]
+fn calendar_edge(random: &mut Random) -> Value {
+    let values = [
+        json!(8_210_266_876_799_999i64),
+        json!(-8_334_601_228_800_000i64),
+        json!("-262143-01-01"),
+        json!("-262143-01-01T00:00:00"),
+        json!("2024-03-10T07:30:00Z"),
+        json!("America/New_York"),
+        json!("Pacific/Kiritimati"),
+        json!("+14:00"),
+        json!("-12:00"),
+        json!("week"),
+        json!("month"),
+        json!("year"),
+        json!(1),
+        json!(-1),
+    ];
+    random.pick(&values).clone()
+}
+
+#[cfg(feature = "datetime")]
+fn random_datetime_rule(random: &mut Random, depth: usize) -> Value {
+    const OPERATORS: &[&str] = &[
+        "datetime",
+        "date_add",
+        "date_diff",
+        "date_trunc",
+        "date_part",
+        "date_before",
+        "date_after",
+    ];
+    match random.below(if depth == 0 { 1 } else { 3 }) {
+        0 => calendar_edge(random),
+        _ => {
+            let operator = *random.pick(OPERATORS);
+            let args: Vec<Value> = (0..random.below(4))
+                .map(|_| random_datetime_rule(random, depth - 1))
+                .collect();
+            json!({ operator: args })
+        }
+    }
+}
+
+#[test]
+#[cfg(feature = "datetime")]
+fn random_datetime_rules_do_not_panic() {
+    let mut random = Random(0x2545_f491_4f6c_dd1d);
+    for _ in 0..5000 {
+        let rule = random_datetime_rule(&mut random, 3);
+        let _ = apply(&rule, &Value::Null);
+    }
+}
@@ -1,6 +1,16 @@
 use jsonlogic::apply;
 use serde_json::{json, Value};
 
+/// The result of `/` or `%` for an integral value, which has no fractional part with the `decimal`
+/// feature.
+fn float(num: f64) -> Value {
+    if cfg!(feature = "decimal") {
+        json!(num as i64)
+    } else {
+        json!(num)
+    }
+}
+
 #[test]
 fn compare() {
     // Only simple tests here, the hardcore coercion tests are in the logic.rs file.
@@ -55,15 +65,12 @@ fn between_inclusive() {
 
 #[test]
 fn min() {
-    assert_eq!(apply(&json!({"min":[1,2,3]}), &Value::Null), Ok(json!(1.0)));
+    assert_eq!(apply(&json!({"min":[1,2,3]}), &Value::Null), Ok(json!(1)));
 }
 
 #[test]
 fn max() {
-    assert_eq!(
-        apply(&json!({"max":[1,"4",3]}), &Value::Null),
-        Ok(json!(4.0))
-    )
+    assert_eq!(apply(&json!({"max":[1,"4",3]}), &Value::Null), Ok(json!(4)))
 }
 
 mod arithmetic {
@@ -72,27 +79,27 @@ mod arithmetic {
     #[test]
     #[allow(clippy::approx_constant)]
     fn adddition() {
-        assert_eq!(apply(&json!({"+":[4, 2]}), &Value::Null), Ok(json!(6.0)));
+        assert_eq!(apply(&json!({"+":[4, 2]}), &Value::Null), Ok(json!(6)));
         assert_eq!(
             apply(&json!({"+":[2,2,2,2,2]}), &Value::Null),
-            Ok(json!(10.0))
+            Ok(json!(10))
         );
         assert_eq!(apply(&json!({"+" : "3.14"}), &Value::Null), Ok(json!(3.14)));
     }
 
     #[test]
     fn substraction() {
-        assert_eq!(apply(&json!({"-": [4,2]}), &Value::Null), Ok(json!(2.0)));
-        assert_eq!(apply(&json!({"-": [2]}), &Value::Null), Ok(json!(-2.0)));
-        assert_eq!(apply(&json!({"-": "-2"}), &Value::Null), Ok(json!(2.0)));
+        assert_eq!(apply(&json!({"-": [4,2]}), &Value::Null), Ok(json!(2)));
+        assert_eq!(apply(&json!({"-": [2]}), &Value::Null), Ok(json!(-2)));
+        assert_eq!(apply(&json!({"-": "-2"}), &Value::Null), Ok(json!(2)));
     }
 
     #[test]
     fn multiplication() {
-        assert_eq!(apply(&json!({"*":[4, 2]}), &Value::Null), Ok(json!(8.0)));
+        assert_eq!(apply(&json!({"*":[4, 2]}), &Value::Null), Ok(json!(8)));
         assert_eq!(
             apply(&json!({"*":[2,2,2,2,2]}), &Value::Null),
-            Ok(json!(32.0))
+            Ok(json!(32))
         );
         assert_eq!(
             apply(&json!({"*" : "3.14"}), &Value::Null),
@@ -102,9 +109,9 @@ mod arithmetic {
 
     #[test]
     fn division() {
-        assert_eq!(apply(&json!({"/":[4, 2]}), &Value::Null), Ok(json!(2.0)));
+        assert_eq!(apply(&json!({"/":[4, 2]}), &Value::Null), Ok(float(2.0)));
         // null/2 === 0/2 === 0
-        assert_eq!(apply(&json!({"/":[null, 2]}), &Value::Null), Ok(json!(0.0)));
+        assert_eq!(apply(&json!({"/":[null, 2]}), &Value::Null), Ok(float(0.0)));
         assert_eq!(apply(&json!({"/":[4, 0]}), &Value::Null), Ok(json!(null)));
         // 4/null === 4/0 === null
         assert_eq!(
@@ -116,7 +123,7 @@ mod arithmetic {
 
 #[test]
 fn modulo() {
-    assert_eq!(apply(&json!({"%": [101, 2]}), &Value::Null), Ok(json!(1.0)));
+    assert_eq!(apply(&json!({"%": [101, 2]}), &Value::Null), Ok(json!(1)));
     assert_eq!(
         apply(&json!({"%": [101, null]}), &Value::Null),
         Ok(json!(null))
@@ -127,6 +134,59 @@ fn modulo() {
     );
     assert_eq!(
         apply(&json!({"%": [null, 101]}), &Value::Null),
-        Ok(json!(0.0))
+        Ok(float(0.0))
+    );
+}
+
+#[test]
+fn rounding() {
+    let rule = json!({"round": [{"*": [{"var": "price"}, 1.19]}, 2]});
+    assert_eq!(apply(&rule, &json!({"price": 10})), Ok(json!(11.9)));
+    assert_eq!(apply(&rule, &json!({"price": 19.99})), Ok(json!(23.79)));
+    assert_eq!(apply(&json!({"round": 2.5}), &Value::Null), Ok(json!(3)));
+    assert_eq!(apply(&json!({"round": -2.5}), &Value::Null), Ok(json!(-3)));
+    assert_eq!(
+        apply(&json!({"round": [1234, -2]}), &Value::Null),
+        Ok(json!(1200))
+    );
+    assert_eq!(apply(&json!({"floor": -1.5}), &Value::Null), Ok(json!(-2)));
+    assert_eq!(apply(&json!({"ceil": "1.2"}), &Value::Null), Ok(json!(2)));
+    assert_eq!(apply(&json!({"trunc": -1.5}), &Value::Null), Ok(json!(-1)));
+    assert_eq!(apply(&json!({"abs": -3}), &Value::Null), Ok(json!(3)));
+    assert_eq!(
+        apply(&json!({"round": "abc"}), &Value::Null),
+        Ok(Value::Null)
+    );
+}
+
+#[test]
+fn powers_and_logarithms() {
+    assert_eq!(
+        apply(&json!({"pow": [2, 10]}), &Value::Null),
+        Ok(json!(1024))
+    );
+    assert_eq!(
+        apply(&json!({"pow": [4, 0.5]}), &Value::Null),
+        Ok(json!(2.0))
+    );
+    assert_eq!(apply(&json!({"sqrt": 9}), &Value::Null), Ok(json!(3.0)));
+    assert_eq!(apply(&json!({"sqrt": -1}), &Value::Null), Ok(Value::Null));
+    assert_eq!(
+        apply(&json!({"ln": [100, 10]}), &Value::Null),
+        Ok(json!(2.0))
+    );
+    assert_eq!(apply(&json!({"ln": 0}), &Value::Null), Ok(Value::Null));
+    assert_eq!(apply(&json!({"exp": 0}), &Value::Null), Ok(json!(1.0)));
+}
+
+#[test]
+fn clamp() {
+    let rule = json!({"clamp": [{"var": "age"}, 0, 120]});
+    assert_eq!(apply(&rule, &json!({"age": 42})), Ok(json!(42)));
+    assert_eq!(apply(&rule, &json!({"age": -5})), Ok(json!(0)));
+    assert_eq!(apply(&rule, &json!({"age": 300})), Ok(json!(120)));
+    assert_eq!(
+        apply(&json!({"clamp": [5, 10, 0]}), &Value::Null),
+        Ok(Value::Null)
     );
 }
@@ -0,0 +1,72 @@
+use jsonlogic::apply;
+use serde_json::{json, Value};
+
+// object and get
+#[test]
+fn build_objects() {
+    assert_eq!(
+        apply(
+            &json!({"object": [
+                "name", {"cat": [{"var": "first"}, " ", {"var": "last"}]},
+                "adult", {">=": [{"var": "age"}, 18]}
+            ]}),
+            &json!({"first": "Jane", "last": "Doe", "age": 42})
+        ),
+        Ok(json!({"name": "Jane Doe", "adult": true}))
+    );
+    assert_eq!(
+        apply(
+            &json!({"get": [{"var": "prices"}, {"var": "currency"}, 0]}),
+            &json!({"prices": {"EUR": 10, "USD": 12}, "currency": "USD"})
+        ),
+        Ok(json!(12))
+    );
+}
+
+// keys, values and entries
+#[test]
+fn inspect_objects() {
+    let data = json!({"stock": {"apples": 3, "pears": 0}});
+    assert_eq!(
+        apply(&json!({"keys": {"var": "stock"}}), &data),
+        Ok(json!(["apples", "pears"]))
+    );
+    assert_eq!(
+        apply(&json!({"values": {"var": "stock"}}), &data),
+        Ok(json!([3, 0]))
+    );
+    assert_eq!(
+        apply(
+            &json!({"map": [
+                {"filter": [{"entries": {"var": "stock"}}, {"var": 1}]},
+                {"var": 0}
+            ]}),
+            &data
+        ),
+        Ok(json!(["apples"]))
+    );
+}
+
+// pick, omit and merge_objects
+#[test]
+fn reshape_objects() {
+    let data = json!({"user": {"id": 1, "name": "Jane", "password": "secret"}});
+    assert_eq!(
+        apply(&json!({"pick": [{"var": "user"}, "id", "name"]}), &data),
+        Ok(json!({"id": 1, "name": "Jane"}))
+    );
+    assert_eq!(
+        apply(
+            &json!({"merge_objects": [
+                {"omit": [{"var": "user"}, ["password"]]},
+                {"object": ["role", "admin"]}
+            ]}),
+            &data
+        ),
+        Ok(json!({"id": 1, "name": "Jane", "role": "admin"}))
+    );
+    assert_eq!(
+        apply(&json!({"omit": ["user", "password"]}), &Value::Null),
+        Ok(json!({}))
+    );
+}
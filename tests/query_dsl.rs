@@ -0,0 +1,26 @@
+use jsonlogic::{to_elasticsearch_query, to_mongodb_query};
+use serde_json::Value;
+
+/// Compares the translations of the rules in `tests/golden/query_dsl.json` to the expected
+/// queries. An expected query of `null` means that the rule cannot be translated.
+#[test]
+fn golden_queries() {
+    let cases: Value =
+        serde_json::from_str(include_str!("golden/query_dsl.json")).expect("invalid golden file");
+
+    for case in cases.as_array().unwrap() {
+        let rule = &case["rule"];
+        assert_eq!(
+            to_mongodb_query(rule).ok(),
+            Some(case["mongodb"].clone()).filter(|query| !query.is_null()),
+            "MongoDB query of {}",
+            rule
+        );
+        assert_eq!(
+            to_elasticsearch_query(rule).ok(),
+            Some(case["elasticsearch"].clone()).filter(|query| !query.is_null()),
+            "Elasticsearch query of {}",
+            rule
+        );
+    }
+}
@@ -52,3 +52,135 @@ fn substr() {
         Ok(json!("log"))
     );
 }
+
+// upper, lower and trim
+#[test]
+fn case_and_whitespace() {
+    assert_eq!(
+        apply(
+            &json!({"==": [{"lower": {"trim": {"var": "email"}}}, "jane@example.com"]}),
+            &json!({"email": "  Jane@Example.com "})
+        ),
+        Ok(json!(true))
+    );
+    assert_eq!(
+        apply(&json!({"upper": "Grüße"}), &Value::Null),
+        Ok(json!("GRÜSSE"))
+    );
+}
+
+// split and join
+#[test]
+fn split_and_join() {
+    assert_eq!(
+        apply(
+            &json!({"split": [{"var": "tags"}, ","]}),
+            &json!({"tags": "red,green,blue"})
+        ),
+        Ok(json!(["red", "green", "blue"]))
+    );
+    assert_eq!(
+        apply(
+            &json!({"join": [{"map": [{"var": "names"}, {"upper": {"var": ""}}]}, ", "]}),
+            &json!({"names": ["a", "b"]})
+        ),
+        Ok(json!("A, B"))
+    );
+}
+
+// replace
+#[test]
+fn replace() {
+    assert_eq!(
+        apply(
+            &json!({"replace": [{"var": "phone"}, " ", ""]}),
+            &json!({"phone": "+49 30 1234"})
+        ),
+        Ok(json!("+49301234"))
+    );
+}
+
+// starts_with, ends_with and index_of
+#[test]
+fn search() {
+    let data = json!({"file": "Grüße.txt"});
+    assert_eq!(
+        apply(&json!({"starts_with": [{"var": "file"}, "Grü"]}), &data),
+        Ok(json!(true))
+    );
+    assert_eq!(
+        apply(&json!({"ends_with": [{"var": "file"}, ".pdf"]}), &data),
+        Ok(json!(false))
+    );
+    assert_eq!(
+        apply(&json!({"index_of": [{"var": "file"}, "."]}), &data),
+        Ok(json!(5))
+    );
+}
+
+// length and pad_start
+#[test]
+fn length_and_padding() {
+    assert_eq!(
+        apply(
+            &json!({"length": {"var": "name"}}),
+            &json!({"name": "Grüße"})
+        ),
+        Ok(json!(5))
+    );
+    assert_eq!(
+        apply(
+            &json!({"cat": ["INV-", {"pad_start": [{"var": "number"}, 6, "0"]}]}),
+            &json!({"number": 42})
+        ),
+        Ok(json!("INV-000042"))
+    );
+}
+
+// StringMode
+#[test]
+fn string_modes() {
+    let json_logic = json!({"cat": [
+        {"length": {"var": "s"}}, " ",
+        {"substr": [{"var": "s"}, -2]}, " ",
+        {"var": "s.1"}
+    ]});
+    let data = json!({"s": "y\u{306}😀"});
+    let expected = |mode| match mode {
+        ::jsonlogic::StringMode::Chars => json!("3 \u{306}😀 \u{306}"),
+        ::jsonlogic::StringMode::Utf16 => json!("4 😀 \u{306}"),
+        #[cfg(feature = "graphemes")]
+        ::jsonlogic::StringMode::Graphemes => json!("2 y\u{306}😀 😀"),
+        _ => unreachable!(),
+    };
+    let modes = [
+        ::jsonlogic::StringMode::Chars,
+        ::jsonlogic::StringMode::Utf16,
+        #[cfg(feature = "graphemes")]
+        ::jsonlogic::StringMode::Graphemes,
+    ];
+
+    for mode in modes {
+        assert_eq!(
+            ::jsonlogic::Rule::compile(&json_logic)
+                .unwrap()
+                .with_string_mode(mode)
+                .apply(&data),
+            Ok(expected(mode))
+        );
+        assert_eq!(
+            ::jsonlogic::BytecodeRule::compile(&json_logic)
+                .unwrap()
+                .with_string_mode(mode)
+                .apply(&data),
+            Ok(expected(mode))
+        );
+        assert_eq!(
+            ::jsonlogic::ClosureRule::compile(&json_logic)
+                .unwrap()
+                .with_string_mode(mode)
+                .apply(&data),
+            Ok(expected(mode))
+        );
+    }
+}
@@ -0,0 +1,60 @@
+use jsonlogic::apply;
+use serde_json::{json, Value};
+
+#[test]
+fn type_of() {
+    let rule = json!({"typeof": {"var": "value"}});
+    assert_eq!(apply(&rule, &json!({"value": 1})), Ok(json!("number")));
+    assert_eq!(apply(&rule, &json!({"value": "1"})), Ok(json!("string")));
+    assert_eq!(apply(&rule, &json!({"value": [1]})), Ok(json!("array")));
+    assert_eq!(apply(&rule, &json!({"value": {}})), Ok(json!("object")));
+    assert_eq!(apply(&rule, &json!({"value": true})), Ok(json!("boolean")));
+    assert_eq!(apply(&rule, &json!({})), Ok(json!("null")));
+
+    let rule = json!({"and": [
+        {"is_string": {"var": "name"}},
+        {"is_number": {"var": "age"}},
+        {"is_array": {"var": "tags"}}
+    ]});
+    assert_eq!(
+        apply(&rule, &json!({"name": "Jane", "age": 42, "tags": []})),
+        Ok(json!(true))
+    );
+    assert_eq!(
+        apply(&rule, &json!({"name": "Jane", "age": "42", "tags": []})),
+        Ok(json!(false))
+    );
+}
+
+#[test]
+fn conversions() {
+    let rule = json!({">=": [{"to_number": {"var": "age"}}, 18]});
+    assert_eq!(apply(&rule, &json!({"age": " 21 "})), Ok(json!(true)));
+    assert_eq!(apply(&rule, &json!({"age": "17"})), Ok(json!(false)));
+    assert_eq!(
+        apply(&json!({"to_number": "abc"}), &Value::Null),
+        Ok(Value::Null)
+    );
+
+    assert_eq!(
+        apply(&json!({"parse_float": "12.5px"}), &Value::Null),
+        Ok(json!(12.5))
+    );
+    assert_eq!(
+        apply(&json!({"parse_float": "px"}), &Value::Null),
+        Ok(Value::Null)
+    );
+
+    assert_eq!(
+        apply(&json!({"to_string": {"var": "id"}}), &json!({"id": 42})),
+        Ok(json!("42"))
+    );
+    assert_eq!(
+        apply(&json!({"to_bool": {"var": "flag"}}), &json!({"flag": "0"})),
+        Ok(json!(true))
+    );
+    assert_eq!(
+        apply(&json!({"to_bool": {"var": "flag"}}), &json!({"flag": 0})),
+        Ok(json!(false))
+    );
+}